@@ -1,12 +1,16 @@
+use account::{aura::AuthorityId as AuraId, AccountId20};
+use author_vrf::AuthorityId as AuthorVrfId;
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	opaque::SessionKeys, AccountId, AllowlistConfig, BalancesConfig, CouncilConfig,
+	EthereumAccount, GenesisConfig, SessionConfig, Signature, SudoConfig, SystemConfig,
+	TechnicalCommitteeConfig, ValidatorSetConfig, WASM_BINARY,
 };
+use pallet_allowlist::Role;
+use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 use sc_service::ChainType;
-use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{ecdsa, sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::traits::{Convert, IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -31,9 +35,30 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Generate the Ethereum address of the secp256k1 key from seed.
+pub fn get_address_from_seed(seed: &str) -> AccountId20 {
+	get_from_seed::<ecdsa::Public>(seed).into()
+}
+
+/// Generate the accounts of the secp256k1 keys from seeds, e.g. governance members.
+pub fn ethereum_accounts_from_seeds(seeds: &[&str]) -> Vec<AccountId> {
+	seeds
+		.iter()
+		.map(|seed| get_account_id_from_seed::<ecdsa::Public>(seed))
+		.collect()
+}
+
+/// Generate a validator's secp256k1 key and its session keys from seed.
+pub fn authority_keys_from_seed(s: &str) -> (ecdsa::Public, SessionKeys) {
+	(
+		get_from_seed::<ecdsa::Public>(s),
+		SessionKeys {
+			aura: get_from_seed::<AuraId>(s),
+			grandpa: get_from_seed::<GrandpaId>(s),
+			im_online: get_from_seed::<ImOnlineId>(s),
+			author_vrf: get_from_seed::<AuthorVrfId>(s),
+		},
+	)
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
@@ -51,14 +76,26 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice")],
 				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<ecdsa::Public>("Alice"),
 				// Pre-funded accounts
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+					// Ethereum-keyed (secp256k1) development accounts
+					get_account_id_from_seed::<ecdsa::Public>("Alice"),
+					get_account_id_from_seed::<ecdsa::Public>("Bob"),
 				],
+				// Allowlisted addresses
+				vec![
+					(get_address_from_seed("Alice"), Role::Operator),
+					(get_address_from_seed("Bob"), Role::User),
+				],
+				// Council
+				ethereum_accounts_from_seeds(&["Alice", "Bob"]),
+				// Technical committee
+				ethereum_accounts_from_seeds(&["Alice"]),
 				true,
 			)
 		},
@@ -91,7 +128,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
 				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<ecdsa::Public>("Alice"),
 				// Pre-funded accounts
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -106,7 +143,19 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+					// Ethereum-keyed (secp256k1) development accounts
+					get_account_id_from_seed::<ecdsa::Public>("Alice"),
+					get_account_id_from_seed::<ecdsa::Public>("Bob"),
 				],
+				// Allowlisted addresses
+				vec![
+					(get_address_from_seed("Alice"), Role::Operator),
+					(get_address_from_seed("Bob"), Role::User),
+				],
+				// Council
+				ethereum_accounts_from_seeds(&["Alice", "Bob"]),
+				// Technical committee
+				ethereum_accounts_from_seeds(&["Alice"]),
 				true,
 			)
 		},
@@ -127,9 +176,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	initial_authorities: Vec<(ecdsa::Public, SessionKeys)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	allowlist: Vec<(AccountId20, Role)>,
+	council: Vec<AccountId>,
+	technical_committee: Vec<AccountId>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -141,16 +193,36 @@ fn testnet_genesis(
 			// Configure endowed accounts with initial balance of 1 << 60.
 			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
 		},
-		aura: AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+		validator_set: ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
 		},
-		grandpa: GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+		session: SessionConfig {
+			keys: initial_authorities
+				.iter()
+				.map(|x| {
+					let account = EthereumAccount::convert(x.0.clone());
+					(account.clone(), account, x.1.clone())
+				})
+				.collect(),
 		},
+		// Aura, the author VRF and GRANDPA get their authorities from the session.
+		aura: Default::default(),
+		author_vrf: Default::default(),
+		grandpa: Default::default(),
+		im_online: Default::default(),
 		sudo: SudoConfig {
 			// Assign network admin rights.
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		fee_router: Default::default(),
+		allowlist: AllowlistConfig { members: allowlist },
+		council: CouncilConfig { members: council, phantom: Default::default() },
+		technical_committee: TechnicalCommitteeConfig {
+			members: technical_committee,
+			phantom: Default::default(),
+		},
+		democracy: Default::default(),
+		eth_bridge: Default::default(),
 	}
 }
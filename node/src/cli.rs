@@ -1,4 +1,36 @@
+use crate::rpc::siwe::SiweConfig;
+use account::AccountId20;
 use sc_cli::RunCmd;
+use std::str::FromStr;
+
+/// How blocks are sealed when Aura and GRANDPA are replaced by manual sealing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal and finalize a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal only when asked to through the `engine_createBlock` RPC.
+	Manual,
+	/// Seal and finalize a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Self::Instant),
+			"manual" => Ok(Self::Manual),
+			millis => match millis.parse() {
+				Ok(0) | Err(_) => Err(format!(
+					"expected `instant`, `manual` or a positive interval in milliseconds, got `{}`",
+					millis
+				)),
+				Ok(millis) => Ok(Self::Interval(millis)),
+			},
+		}
+	}
+}
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +39,36 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks with manual sealing instead of Aura and GRANDPA: `instant`, `manual` or an
+	/// interval in milliseconds.
+	///
+	/// Blocks can always be sealed and finalized on demand through the `engine_createBlock` and
+	/// `engine_finalizeBlock` RPCs.
+	#[arg(long, value_name = "instant|manual|INTERVAL_MS")]
+	pub sealing: Option<Sealing>,
+
+	/// Let the operator with this address call unsafe RPC methods after signing in with Ethereum.
+	///
+	/// Can be given several times. Operators sign in through the `siwe_*` RPCs on any interface,
+	/// whatever `--rpc-methods` allows, which must not expose unsafe methods to everyone.
+	#[arg(long = "rpc-operator", value_name = "ADDRESS")]
+	pub rpc_operators: Vec<AccountId20>,
+
+	/// The domain operators' sign-in messages must be for.
+	#[arg(long, value_name = "DOMAIN", default_value = "localhost")]
+	pub rpc_siwe_domain: String,
+}
+
+impl Cli {
+	/// Who may sign in to call unsafe RPC methods, if anyone.
+	pub fn siwe_config(&self) -> Option<SiweConfig> {
+		(!self.rpc_operators.is_empty()).then(|| SiweConfig {
+			domain: self.rpc_siwe_domain.clone(),
+			chain_id: node_template_runtime::CHAIN_ID,
+			operators: self.rpc_operators.clone(),
+		})
+	}
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -50,4 +112,8 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Sign and submit transactions, e.g. from an air-gapped machine.
+	#[command(subcommand)]
+	Tx(crate::tx::TxCmd),
 }
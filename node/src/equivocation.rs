@@ -0,0 +1,149 @@
+//! Reporting of Aura equivocations noticed on block import.
+//!
+//! Aura's import queue only logs authorities that seal two blocks for the same slot.
+//! [`AuraEquivocationImport`] takes over that check and hands the conflicting headers to the
+//! runtime through [`AuraEquivocationApi`], which submits an unsigned report to the transaction
+//! pool for the next block author to include.
+
+use account::aura::{AuthorityId as AuraId, AuthoritySignature};
+use aura_equivocation_runtime_api::AuraEquivocationApi;
+use node_template_runtime::opaque::Block;
+use sc_client_api::AuxStore;
+use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::{CacheKeyId, Error as ConsensusError};
+use sp_consensus_aura::{AuraApi, Slot, SlotDuration};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+	DigestItem,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Block import reporting authors that sealed another block for the same slot before passing the
+/// block on to `inner`.
+///
+/// The headers of recent slots are kept in the client's auxiliary storage to compare with.
+pub struct AuraEquivocationImport<I, C> {
+	inner: I,
+	client: Arc<C>,
+	slot_duration: SlotDuration,
+}
+
+impl<I: Clone, C> Clone for AuraEquivocationImport<I, C> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			client: self.client.clone(),
+			slot_duration: self.slot_duration,
+		}
+	}
+}
+
+impl<I, C> AuraEquivocationImport<I, C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore + Send + Sync,
+	C::Api: AuraApi<Block, AuraId> + AuraEquivocationApi<Block, AuraId>,
+{
+	/// Wrap `inner`, telling slots apart by `slot_duration`.
+	pub fn new(inner: I, client: Arc<C>, slot_duration: SlotDuration) -> Self {
+		Self { inner, client, slot_duration }
+	}
+
+	/// Report the author of the block with `header`, whose seal is among `post_digests`, if it
+	/// sealed another block of the same slot.
+	fn report_equivocation(
+		&self,
+		header: &<Block as BlockT>::Header,
+		post_digests: &[DigestItem],
+	) -> Result<(), String> {
+		let slot = sc_consensus_aura::find_pre_digest::<Block, AuthoritySignature>(header)
+			.map_err(|e| e.to_string())?;
+		let authorities = self
+			.client
+			.runtime_api()
+			.authorities(&BlockId::Hash(*header.parent_hash()))
+			.map_err(|e| e.to_string())?;
+		if authorities.is_empty() {
+			return Ok(())
+		}
+		let author = &authorities[*slot as usize % authorities.len()];
+
+		// The runtime checks the seals, which Aura moved into the post digests.
+		let mut sealed = header.clone();
+		sealed.digest_mut().logs.extend(post_digests.iter().cloned());
+		let slot_now = Slot::from_timestamp(sp_timestamp::Timestamp::current(), self.slot_duration);
+		let equivocation_proof = match sc_consensus_slots::check_equivocation(
+			&*self.client,
+			slot_now,
+			slot,
+			&sealed,
+			author,
+		)
+		.map_err(|e| e.to_string())?
+		{
+			Some(equivocation_proof) => equivocation_proof,
+			None => return Ok(()),
+		};
+		log::warn!(
+			target: "aura",
+			"Slot author {:?} is equivocating at slot {}, reporting",
+			equivocation_proof.offender,
+			*slot,
+		);
+
+		let best = BlockId::Hash(self.client.info().best_hash);
+		let key_owner_proof = self
+			.client
+			.runtime_api()
+			.generate_key_ownership_proof(&best, equivocation_proof.offender.clone())
+			.map_err(|e| e.to_string())?
+			.ok_or("the offender is no validator of the current session")?;
+		self.client
+			.runtime_api()
+			.submit_report_equivocation_unsigned_extrinsic(
+				&best,
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| "the transaction pool rejected the report".to_string())
+	}
+}
+
+#[async_trait::async_trait]
+impl<I, C> BlockImport<Block> for AuraEquivocationImport<I, C>
+where
+	I: BlockImport<Block, Error = ConsensusError> + Send,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore + Send + Sync,
+	C::Api: AuraApi<Block, AuraId> + AuraEquivocationApi<Block, AuraId>,
+{
+	type Error = ConsensusError;
+	type Transaction = I::Transaction;
+
+	async fn check_block(
+		&mut self,
+		block: BlockCheckParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(
+		&mut self,
+		block: BlockImportParams<Block, Self::Transaction>,
+		cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, Self::Error> {
+		// Equivocating blocks are valid blocks, so they are imported all the same.
+		if let Err(error) = self.report_equivocation(&block.header, &block.post_digests) {
+			log::warn!(
+				target: "aura",
+				"Failed to check block {} for equivocations: {}",
+				block.header.hash(),
+				error,
+			);
+		}
+
+		self.inner.import_block(block, cache).await
+	}
+}
@@ -1,3 +1,9 @@
+mod benchmarking;
 pub mod chain_spec;
+pub mod cli;
+pub mod command;
+pub mod equivocation;
+pub mod preverify;
 pub mod rpc;
 pub mod service;
+pub mod tx;
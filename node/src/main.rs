@@ -7,7 +7,10 @@ mod service;
 mod benchmarking;
 mod cli;
 mod command;
+mod equivocation;
+mod preverify;
 mod rpc;
+mod tx;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
@@ -0,0 +1,174 @@
+//! Parallel signature pre-verification for imported blocks.
+//!
+//! Recovering the signer of every extrinsic dominates the execution time of blocks full of
+//! ECDSA-signed transfers. [`PreverifyBlockImport`] checks all signatures of a block on a thread
+//! pool before the block is executed and hands the results to the runtime through
+//! [`signature_batch`], so that `execute_block` only has to look them up.
+
+use node_template_runtime::{self as runtime, opaque::Block};
+use rayon::prelude::*;
+use sc_client_api::execution_extensions::ExtensionsFactory;
+use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult, StateAction};
+use signature_batch::{signature_key, SignatureKey, VerifiedSignatures, VerifiedSignaturesExt};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::{CacheKeyId, Error as ConsensusError};
+use sp_core::{offchain::Capabilities, Decode, Encode};
+use sp_externalities::Extensions;
+use sp_runtime::{
+	generic::{BlockId, Era},
+	traits::{Block as BlockT, Header as HeaderT, SaturatedConversion, Verify},
+	MultiAddress,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Makes [`VerifiedSignatures`] available to every runtime call issued by the client.
+pub struct VerifiedSignaturesFactory(pub VerifiedSignatures);
+
+impl ExtensionsFactory for VerifiedSignaturesFactory {
+	fn extensions_for(&self, _: Capabilities) -> Extensions {
+		let mut extensions = Extensions::new();
+		extensions.register(VerifiedSignaturesExt(self.0.clone()));
+		extensions
+	}
+}
+
+/// Block import checking extrinsic signatures in parallel before passing the block on to `inner`.
+///
+/// Signatures that verify are recorded in [`VerifiedSignatures`] for as long as the block is being
+/// imported. Anything that can't be checked up front, e.g. because the era's birth block is not on
+/// the canonical chain, is left for the runtime to verify as usual.
+pub struct PreverifyBlockImport<I, C> {
+	inner: I,
+	client: Arc<C>,
+	verified: VerifiedSignatures,
+	pool: Arc<rayon::ThreadPool>,
+}
+
+impl<I: Clone, C> Clone for PreverifyBlockImport<I, C> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			client: self.client.clone(),
+			verified: self.verified.clone(),
+			pool: self.pool.clone(),
+		}
+	}
+}
+
+impl<I, C> PreverifyBlockImport<I, C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: Core<Block>,
+{
+	/// Wrap `inner`, using one verification thread per CPU.
+	pub fn new(
+		inner: I,
+		client: Arc<C>,
+		verified: VerifiedSignatures,
+	) -> Result<Self, rayon::ThreadPoolBuildError> {
+		let pool = rayon::ThreadPoolBuilder::new()
+			.thread_name(|index| format!("preverify-{}", index))
+			.build()?;
+
+		Ok(Self { inner, client, verified, pool: Arc::new(pool) })
+	}
+
+	/// Verify the signed extrinsics of the block with `header`, returning the keys of the
+	/// signatures that passed.
+	fn preverify(
+		&self,
+		header: &<Block as BlockT>::Header,
+		extrinsics: &[<Block as BlockT>::Extrinsic],
+	) -> Vec<SignatureKey> {
+		let parent = BlockId::Hash(*header.parent_hash());
+		let (version, genesis_hash) =
+			match (self.client.runtime_api().version(&parent), self.client.hash(0)) {
+				(Ok(version), Ok(Some(genesis_hash))) => (version, genesis_hash),
+				_ => return Vec::new(),
+			};
+		let number = (*header.number()).saturated_into::<u64>();
+		let client = &self.client;
+
+		self.pool.install(|| {
+			extrinsics
+				.par_iter()
+				.filter_map(|xt| {
+					let xt = runtime::UncheckedExtrinsic::decode(&mut &xt.encode()[..]).ok()?;
+					let (address, signature, extra) = xt.signature?;
+					let signer = match address {
+						MultiAddress::Id(signer) => signer,
+						_ => return None,
+					};
+
+					// `CheckEra` signs the hash of the era's birth block, which only exists once
+					// the birth block has a child.
+					let birth = Era::decode(&mut &extra.4.encode()[..]).ok()?.birth(number);
+					if birth >= number {
+						return None
+					}
+					let birth_hash = client.hash(birth.saturated_into()).ok()??;
+
+					let payload = runtime::SignedPayload::from_raw(
+						xt.function,
+						extra,
+						(
+							(),
+							version.spec_version,
+							version.transaction_version,
+							genesis_hash,
+							birth_hash,
+							(),
+							(),
+							(),
+							(),
+						),
+					);
+					payload.using_encoded(|msg| {
+						signature
+							.0
+							.verify(msg, &signer)
+							.then(|| signature_key(&signature.encode(), &signer.encode(), msg))
+					})
+				})
+				.collect()
+		})
+	}
+}
+
+#[async_trait::async_trait]
+impl<I, C> BlockImport<Block> for PreverifyBlockImport<I, C>
+where
+	I: BlockImport<Block, Error = ConsensusError> + Send,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: Core<Block>,
+{
+	type Error = ConsensusError;
+	type Transaction = I::Transaction;
+
+	async fn check_block(
+		&mut self,
+		block: BlockCheckParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(
+		&mut self,
+		block: BlockImportParams<Block, Self::Transaction>,
+		cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, Self::Error> {
+		// Blocks we authored ourselves come with their state changes already computed.
+		let verified = match (&block.state_action, &block.body) {
+			(StateAction::Execute | StateAction::ExecuteIfPossible, Some(body)) =>
+				self.preverify(&block.header, body),
+			_ => Vec::new(),
+		};
+
+		self.verified.insert(verified.iter().copied());
+		let result = self.inner.import_block(block, cache).await;
+		self.verified.remove(&verified);
+
+		result
+	}
+}
@@ -7,15 +7,33 @@
 
 use std::sync::Arc;
 
-use jsonrpsee::RpcModule;
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use futures::channel::mpsc;
+use jsonrpsee::{
+	core::Error as JsonRpseeError,
+	types::error::{CallError, ErrorCode, ErrorObject},
+	RpcModule,
+};
+use node_template_runtime::{opaque::Block, AccountId, Balance, Hash, Index, RuntimeCall};
+use sc_consensus_manual_seal::EngineCommand;
+use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
+use sc_utils::mpsc::TracingUnboundedSender;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_keystore::SyncCryptoStorePtr;
 
 pub use sc_rpc_api::DenyUnsafe;
 
+pub mod conversion;
+pub mod eth;
+pub mod maintenance;
+pub mod personal;
+pub mod siwe;
+
+/// Code of errors raised by runtime APIs.
+const RUNTIME_ERROR: i32 = 1;
+
 /// Full client dependencies.
 pub struct FullDeps<C, P> {
 	/// The client instance to use.
@@ -24,6 +42,24 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Channel to the manual seal authoring task, if blocks are sealed manually.
+	pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
+	/// Dependencies of the unsafe methods operators call after signing in, if any may.
+	pub siwe: Option<SiweDeps>,
+}
+
+/// Dependencies of the unsafe methods available to operators through `siwe_call`.
+pub struct SiweDeps {
+	/// The operators and their sessions.
+	pub operators: Arc<siwe::Operators>,
+	/// The keystore `author_insertKey` and `author_rotateKeys` write to.
+	pub keystore: SyncCryptoStorePtr,
+	/// Executor of the `author_*` subscriptions.
+	pub executor: SubscriptionTaskExecutor,
+	/// The node's name, version and chain, as `system_*` methods report them.
+	pub system_info: sc_rpc::system::SystemInfo,
+	/// Channel to the network, which `system_addReservedPeer` and the like use.
+	pub system_rpc_tx: TracingUnboundedSender<sc_rpc::system::Request<Block>>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -36,17 +72,59 @@ where
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<
+		Block,
+		Balance,
+		RuntimeCall,
+	>,
+	C::Api: eth_rpc_runtime_api::EthApi<Block, Balance>,
+	C::Api: maintenance_runtime_api::MaintenanceApi<Block>,
+	C::Api: account_conversion_runtime_api::AccountConversionApi<Block, AccountId>,
+	C::Api: sp_session::SessionKeys<Block>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + 'static,
+	P: TransactionPool<Block = Block, Hash = Hash> + 'static,
 {
+	use conversion::{ConversionApiServer, Converter};
+	use eth::{Eth, EthApiServer};
+	use maintenance::{Maintenance, MaintenanceApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use personal::{Personal, PersonalApiServer};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
+	use sc_rpc::{
+		author::{Author, AuthorApiServer},
+		system::{System as NodeSystem, SystemApiServer as NodeSystemApiServer},
+	};
+	use siwe::{Siwe, SiweApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, command_sink, siwe } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Eth::new(client.clone()).into_rpc())?;
+	module.merge(Maintenance::new(client.clone()).into_rpc())?;
+	module.merge(Personal.into_rpc())?;
+	module.merge(Converter::new(client.clone()).into_rpc())?;
+
+	if let Some(SiweDeps { operators, keystore, executor, system_info, system_rpc_tx }) = siwe {
+		// The methods `--rpc-methods` would deny, allowed here since `Siwe` only lets operators
+		// call them.
+		let mut unsafe_methods = RpcModule::new(());
+		unsafe_methods.merge(
+			Author::new(client.clone(), pool.clone(), keystore, DenyUnsafe::No, executor)
+				.into_rpc(),
+		)?;
+		unsafe_methods
+			.merge(NodeSystem::new(system_info, system_rpc_tx, DenyUnsafe::No).into_rpc())?;
+		unsafe_methods.merge(System::new(client, pool, DenyUnsafe::No).into_rpc())?;
+		module.merge(Siwe::new(operators, unsafe_methods).into_rpc())?;
+	}
+
+	if let Some(command_sink) = command_sink {
+		// `engine_createBlock` and `engine_finalizeBlock` forward to the manual seal task.
+		module.merge(ManualSeal::new(command_sink).into_rpc())?;
+	}
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
@@ -55,3 +133,18 @@ where
 
 	Ok(module)
 }
+
+/// Error of a failed runtime API call.
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(RUNTIME_ERROR, message, Some(format!("{:?}", e)))).into()
+}
+
+/// Error of a request with invalid parameters.
+fn invalid_params(message: impl Into<String>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		ErrorCode::InvalidParams.code(),
+		message.into(),
+		None::<()>,
+	))
+	.into()
+}
@@ -0,0 +1,95 @@
+//! RPC method converting between Ethereum addresses, public keys and accounts, as the runtime does.
+
+use std::{str::FromStr, sync::Arc};
+
+use super::{invalid_params, runtime_error};
+use account::AccountId20;
+use account_conversion_runtime_api::AccountConversionApi as AccountConversionRuntimeApi;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use node_template_runtime::{opaque::Block, AccountId, Hash};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::ecdsa;
+use sp_runtime::generic::BlockId;
+
+/// An account and its Ethereum address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversion {
+	/// The account, or none if the input is an address whose key never signed a transaction.
+	pub account: Option<AccountId>,
+	/// The address, with its EIP-55 checksum, or none if the input is an account whose key never
+	/// signed a transaction.
+	pub address: Option<AccountId20>,
+}
+
+/// Account conversion RPC methods.
+#[rpc(client, server)]
+pub trait ConversionApi<BlockHash> {
+	/// The account and address of `input` at block `at`, or at the best block.
+	///
+	/// `input` is either a `0x`-prefixed address, a `0x`-prefixed compressed secp256k1 public key,
+	/// or an account in SS58 or as `0x`-prefixed hex.
+	#[method(name = "account_convert")]
+	fn convert(&self, input: String, at: Option<BlockHash>) -> RpcResult<Conversion>;
+}
+
+/// Implementation of [`ConversionApiServer`] on top of the runtime's
+/// [`AccountConversionRuntimeApi`].
+pub struct Converter<C> {
+	client: Arc<C>,
+}
+
+impl<C> Converter<C> {
+	/// Serve requests from `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> ConversionApiServer<Hash> for Converter<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AccountConversionRuntimeApi<Block, AccountId>,
+{
+	fn convert(&self, input: String, at: Option<Hash>) -> RpcResult<Conversion> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let api = self.client.runtime_api();
+
+		match input.strip_prefix("0x").map(str::len) {
+			Some(40) => {
+				let address = AccountId20::from_str(&input)
+					.map_err(|e| invalid_params(format!("invalid address: {}", e)))?;
+				let account = api
+					.address_to_account(&at, address.into())
+					.map_err(|e| runtime_error("Unable to query the account.", e))?;
+				Ok(Conversion { account, address: Some(address) })
+			},
+			Some(66) => {
+				let public = sp_core::bytes::from_hex(&input)
+					.ok()
+					.and_then(|public| public.try_into().ok())
+					.map(ecdsa::Public::from_raw)
+					.ok_or_else(|| invalid_params("invalid public key"))?;
+				let address = api
+					.public_to_address(&at, public.clone())
+					.map_err(|e| runtime_error("Unable to query the address.", e))?
+					.ok_or_else(|| invalid_params("not a secp256k1 public key"))?;
+				let account = api
+					.public_to_account(&at, public)
+					.map_err(|e| runtime_error("Unable to query the account.", e))?;
+				Ok(Conversion { account: Some(account), address: Some(address.into()) })
+			},
+			_ => {
+				let account = AccountId::from_str(&input).map_err(|_| {
+					invalid_params("expected an address, a public key or an account")
+				})?;
+				let address = api
+					.account_to_address(&at, account.clone())
+					.map_err(|e| runtime_error("Unable to query the address.", e))?;
+				Ok(Conversion { account: Some(account), address: address.map(Into::into) })
+			},
+		}
+	}
+}
@@ -0,0 +1,243 @@
+//! Ethereum-compatible RPC methods, so that Ethereum tooling can price transactions.
+
+use std::sync::Arc;
+
+use super::{invalid_params, runtime_error};
+use eth_rpc_runtime_api::EthApi as EthRuntimeApi;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use node_template_runtime::{
+	opaque::Block, AccountId, Address, Balance, BalancesCall, Hash, RuntimeCall, UncheckedExtrinsic,
+};
+use node_template_sdk::{signed_extra, SigningParams};
+use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{ecdsa, Bytes, Decode, Encode, H160, U256};
+use sp_runtime::{
+	generic::{BlockId, Era},
+	MultiSignature, MultiSigner,
+};
+
+/// What to estimate the gas of: either a transfer of `value` to `to`, as Ethereum wallets send
+/// it, or the SCALE-encoded `RuntimeCall` in `data`.
+///
+/// Other fields of Ethereum call requests, like `from` or `gas`, are ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallRequest {
+	/// Recipient of the transfer.
+	pub to: Option<H160>,
+	/// Amount to transfer.
+	pub value: Option<U256>,
+	/// SCALE-encoded call, if not a transfer.
+	pub data: Option<Bytes>,
+}
+
+/// Estimated cost of a call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasEstimate {
+	/// Weight of the call in gas, see `WEIGHT_PER_GAS` of the runtime.
+	pub gas: U256,
+	/// Fee of the call signed by an Ethereum key without tip, including the base and length fees
+	/// that have no equivalent in gas.
+	pub fee: U256,
+}
+
+/// Block parameter of Ethereum RPC methods: a tag, a hex block number or a block hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlockNumber {
+	/// The best block.
+	#[default]
+	Latest,
+	/// The block being built on top of the best block, priced as the best block.
+	Pending,
+	/// The genesis block.
+	Earliest,
+	/// The last finalized block.
+	Safe,
+	/// The last finalized block.
+	Finalized,
+	/// The block of this number in the best chain.
+	Number(u64),
+	/// The block of this hash.
+	Hash(Hash),
+}
+
+impl Serialize for BlockNumber {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Latest => serializer.serialize_str("latest"),
+			Self::Pending => serializer.serialize_str("pending"),
+			Self::Earliest => serializer.serialize_str("earliest"),
+			Self::Safe => serializer.serialize_str("safe"),
+			Self::Finalized => serializer.serialize_str("finalized"),
+			Self::Number(number) => serializer.serialize_str(&format!("0x{:x}", number)),
+			Self::Hash(hash) => hash.serialize(serializer),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for BlockNumber {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let value = String::deserialize(deserializer)?;
+		match value.as_str() {
+			"latest" => Ok(Self::Latest),
+			"pending" => Ok(Self::Pending),
+			"earliest" => Ok(Self::Earliest),
+			"safe" => Ok(Self::Safe),
+			"finalized" => Ok(Self::Finalized),
+			hex => match hex.strip_prefix("0x") {
+				Some(digits) if digits.len() == 64 =>
+					digits.parse().map(Self::Hash).map_err(de::Error::custom),
+				Some(digits) if !digits.is_empty() && digits.len() <= 16 =>
+					u64::from_str_radix(digits, 16).map(Self::Number).map_err(de::Error::custom),
+				_ => Err(de::Error::custom(format!(
+					"expected a block tag, number or hash, got `{}`",
+					value
+				))),
+			},
+		}
+	}
+}
+
+/// Ethereum-compatible RPC methods.
+#[rpc(client, server)]
+pub trait EthApi {
+	/// Price of one unit of gas in the block after `block`, or after the best block, in the
+	/// smallest currency unit.
+	#[method(name = "eth_gasPrice")]
+	fn gas_price(&self, block: Option<BlockNumber>) -> RpcResult<U256>;
+
+	/// Gas of the call described by `request` in the block after `block`, or after the best block.
+	#[method(name = "eth_estimateGas")]
+	fn estimate_gas(&self, request: CallRequest, block: Option<BlockNumber>) -> RpcResult<U256>;
+
+	/// Gas and fee of the call described by `request` in the block after `block`, or after the
+	/// best block.
+	#[method(name = "gas_estimate")]
+	fn gas_estimate(
+		&self,
+		request: CallRequest,
+		block: Option<BlockNumber>,
+	) -> RpcResult<GasEstimate>;
+}
+
+/// Implementation of [`EthApiServer`] on top of the runtime's [`EthRuntimeApi`].
+pub struct Eth<C> {
+	client: Arc<C>,
+}
+
+impl<C> Eth<C> {
+	/// Serve requests from `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> Eth<C>
+where
+	C: HeaderBackend<Block>,
+{
+	/// The block `block` refers to, the best block by default.
+	fn block_id(&self, block: Option<BlockNumber>) -> RpcResult<BlockId<Block>> {
+		let info = self.client.info();
+		let hash = match block.unwrap_or_default() {
+			BlockNumber::Latest | BlockNumber::Pending => info.best_hash,
+			BlockNumber::Earliest => info.genesis_hash,
+			BlockNumber::Safe | BlockNumber::Finalized => info.finalized_hash,
+			BlockNumber::Hash(hash) => hash,
+			BlockNumber::Number(number) => u32::try_from(number)
+				.ok()
+				.map(|number| self.client.hash(number))
+				.transpose()
+				.map_err(|e| runtime_error("Unable to query block hash.", e))?
+				.flatten()
+				.ok_or_else(|| invalid_params(format!("block {} is not known", number)))?,
+		};
+		Ok(BlockId::hash(hash))
+	}
+}
+
+impl<C> EthApiServer for Eth<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: EthRuntimeApi<Block, Balance>,
+	C::Api: TransactionPaymentCallApi<Block, Balance, RuntimeCall>,
+{
+	fn gas_price(&self, block: Option<BlockNumber>) -> RpcResult<U256> {
+		let at = self.block_id(block)?;
+
+		let price = self
+			.client
+			.runtime_api()
+			.gas_price(&at)
+			.map_err(|e| runtime_error("Unable to query gas price.", e))?;
+		Ok(price.into())
+	}
+
+	fn estimate_gas(&self, request: CallRequest, block: Option<BlockNumber>) -> RpcResult<U256> {
+		Ok(self.gas_estimate(request, block)?.gas)
+	}
+
+	fn gas_estimate(
+		&self,
+		request: CallRequest,
+		block: Option<BlockNumber>,
+	) -> RpcResult<GasEstimate> {
+		let at = self.block_id(block)?;
+		let call = request_call(request)?;
+		let len = signed_len(call.clone());
+
+		let api = self.client.runtime_api();
+		let weight_per_gas =
+			api.weight_per_gas(&at).map_err(|e| runtime_error("Unable to query gas.", e))?;
+		let info = api
+			.query_call_info(&at, call, len)
+			.map_err(|e| runtime_error("Unable to query dispatch info.", e))?;
+
+		let weight = info.weight.ref_time();
+		Ok(GasEstimate {
+			gas: (weight / weight_per_gas + u64::from(weight % weight_per_gas != 0)).into(),
+			fee: info.partial_fee.into(),
+		})
+	}
+}
+
+/// The call `request` describes.
+fn request_call(request: CallRequest) -> RpcResult<RuntimeCall> {
+	// Wallets send plain transfers with empty `data`.
+	let data = request.data.filter(|data| !data.is_empty());
+
+	match (data, request.to) {
+		(Some(data), None) => RuntimeCall::decode(&mut &data[..])
+			.map_err(|e| invalid_params(format!("`data` is not a call: {}", e))),
+		(None, Some(_)) => {
+			let value = Balance::try_from(request.value.unwrap_or_default())
+				.map_err(|_| invalid_params("`value` exceeds the balance type"))?;
+			// The fee doesn't depend on the recipient, as long as its address has the same length
+			// as the one the transfer will be signed with.
+			let dest = Address::Id(AccountId::new([0; 32]));
+			Ok(BalancesCall::transfer { dest, value }.into())
+		},
+		_ => Err(invalid_params("expected either `data`, or `to` and an optional `value`")),
+	}
+}
+
+/// Length of `call` signed by an Ethereum key, without tip and immortal.
+fn signed_len(call: RuntimeCall) -> u32 {
+	let params = SigningParams {
+		nonce: 0,
+		era: Era::Immortal,
+		tip: 0,
+		genesis_hash: Default::default(),
+		birth_hash: Default::default(),
+		spec_version: 0,
+		transaction_version: 0,
+		sponsor: None,
+	};
+	let signature = MultiSignature::Ecdsa(ecdsa::Signature::from_raw([0; 65]));
+	let extra = signed_extra(&MultiSigner::Ecdsa(ecdsa::Public::from_raw([0; 33])), &params);
+	let signer = Address::Id(AccountId::new([0; 32]));
+
+	UncheckedExtrinsic::new_signed(call, signer, signature.into(), extra).encoded_size() as u32
+}
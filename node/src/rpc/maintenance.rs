@@ -0,0 +1,77 @@
+//! RPC methods reporting the calls the runtime pauses for maintenance.
+
+use std::sync::Arc;
+
+use super::runtime_error;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use maintenance_runtime_api::MaintenanceApi as MaintenanceRuntimeApi;
+use node_template_runtime::{opaque::Block, Hash};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+
+/// A paused pallet, or a paused call of a pallet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PausedCall {
+	/// Name of the pallet.
+	pub pallet: String,
+	/// Name of the call, or none if all calls of the pallet are paused.
+	pub call: Option<String>,
+}
+
+/// What the runtime pauses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStatus {
+	/// Whether every call but the whitelisted ones is paused.
+	pub maintenance_mode: bool,
+	/// Paused pallets and calls.
+	pub paused: Vec<PausedCall>,
+}
+
+/// Maintenance RPC methods.
+#[rpc(client, server)]
+pub trait MaintenanceApi<BlockHash> {
+	/// What is paused at block `at`, or at the best block.
+	#[method(name = "maintenance_status")]
+	fn status(&self, at: Option<BlockHash>) -> RpcResult<MaintenanceStatus>;
+}
+
+/// Implementation of [`MaintenanceApiServer`] on top of the runtime's [`MaintenanceRuntimeApi`].
+pub struct Maintenance<C> {
+	client: Arc<C>,
+}
+
+impl<C> Maintenance<C> {
+	/// Serve requests from `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> MaintenanceApiServer<Hash> for Maintenance<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: MaintenanceRuntimeApi<Block>,
+{
+	fn status(&self, at: Option<Hash>) -> RpcResult<MaintenanceStatus> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let api = self.client.runtime_api();
+
+		let maintenance_mode = api
+			.maintenance_mode(&at)
+			.map_err(|e| runtime_error("Unable to query maintenance mode.", e))?;
+		let paused = api
+			.paused(&at)
+			.map_err(|e| runtime_error("Unable to query paused calls.", e))?
+			.into_iter()
+			.map(|(pallet, call)| PausedCall {
+				pallet: String::from_utf8_lossy(&pallet).into_owned(),
+				call: call.map(|call| String::from_utf8_lossy(&call).into_owned()),
+			})
+			.collect();
+
+		Ok(MaintenanceStatus { maintenance_mode, paused })
+	}
+}
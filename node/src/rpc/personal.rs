@@ -0,0 +1,70 @@
+//! RPC methods recovering and verifying the signers of `personal_sign` messages.
+//!
+//! They verify signatures with the `account` crate, like the runtime, so a signature the node
+//! accepts is one the runtime accepts.
+
+use super::invalid_params;
+use account::{recover_message_signer, verify_message, AccountId20, VerifyError};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+
+/// Outcome of verifying a signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+	/// Whether the signature is the address's signature of the message.
+	pub valid: bool,
+	/// Why the signature is not valid.
+	pub reason: Option<String>,
+}
+
+/// Signed message RPC methods.
+#[rpc(client, server)]
+pub trait PersonalApi {
+	/// The address whose `personal_sign` signature of `message` is the 65-byte `r ‖ s ‖ v`
+	/// `signature`, with its EIP-55 checksum.
+	#[method(name = "personal_ecRecover")]
+	fn ec_recover(&self, message: Bytes, signature: Bytes) -> RpcResult<AccountId20>;
+
+	/// Whether `signature` is the `personal_sign` signature of `message` by `address`.
+	#[method(name = "account_verifyMessage")]
+	fn verify_message(
+		&self,
+		address: AccountId20,
+		message: Bytes,
+		signature: Bytes,
+	) -> RpcResult<Verification>;
+}
+
+/// Implementation of [`PersonalApiServer`].
+pub struct Personal;
+
+impl PersonalApiServer for Personal {
+	fn ec_recover(&self, message: Bytes, signature: Bytes) -> RpcResult<AccountId20> {
+		let signature = signature_bytes(&signature).map_err(invalid_params)?;
+		recover_message_signer(&message, signature)
+			.ok_or_else(|| invalid_params(VerifyError::InvalidSignature.to_string()))
+	}
+
+	fn verify_message(
+		&self,
+		address: AccountId20,
+		message: Bytes,
+		signature: Bytes,
+	) -> RpcResult<Verification> {
+		let verified = signature_bytes(&signature).and_then(|signature| {
+			verify_message(&address, &message, signature).map_err(|e| e.to_string())
+		});
+
+		Ok(match verified {
+			Ok(()) => Verification { valid: true, reason: None },
+			Err(reason) => Verification { valid: false, reason: Some(reason) },
+		})
+	}
+}
+
+fn signature_bytes(signature: &[u8]) -> Result<&[u8; 65], String> {
+	signature
+		.try_into()
+		.map_err(|_| format!("expected a 65-byte signature, got {} bytes", signature.len()))
+}
@@ -0,0 +1,541 @@
+//! Sign-In With Ethereum (ERC-4361), so that operators can call unsafe RPC methods.
+//!
+//! `--rpc-methods` makes unsafe methods, like `author_insertKey` or `system_addReservedPeer`,
+//! available either to every client of an interface or to none. Operators named with
+//! `--rpc-operator` reach them on any interface instead:
+//!
+//! 1. `siwe_nonce` issues a nonce, which can be signed in with once within five minutes. Nonces
+//!    aren't stored until they are used: they carry when they expire, authenticated with an HMAC
+//!    keyed with a secret the node draws at startup, so anyone may ask for any number.
+//! 2. The operator signs an ERC-4361 message for the node's `--rpc-siwe-domain` carrying the nonce
+//!    with `personal_sign`, as wallets sign in to any website.
+//! 3. `siwe_signIn` checks the message and returns a session token.
+//! 4. `siwe_call` calls an unsafe method with the token, until the session expires after an hour or
+//!    at the message's expiration time, or `siwe_signOut` ends it.
+//!
+//! Subscriptions, like `author_submitAndWatchExtrinsic`, are not available through `siwe_call`,
+//! which rejects them.
+//!
+//! Messages must be for the node's chain id and for a URI on its domain, and issued between their
+//! nonce and now, give or take a minute of clock skew.
+//!
+//! Signing in only guards unsafe methods that aren't exposed otherwise: nodes with operators must
+//! keep `--rpc-methods` at `safe`, or at `auto` with their RPC interfaces on localhost, or else
+//! anyone reaching the interfaces calls unsafe methods without signing in.
+
+use std::{
+	collections::HashMap,
+	str::FromStr,
+	sync::{Arc, Mutex, MutexGuard, PoisonError},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use account::{verify_message, AccountId20};
+use jsonrpsee::{
+	core::{
+		async_trait,
+		server::rpc_module::{MethodCallback, MethodKind},
+		Error as JsonRpseeError, RpcResult,
+	},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorCode, ErrorObject},
+	RpcModule,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sp_core::{hashing::sha2_256, Bytes};
+
+/// Code of failed sign-ins.
+const SIGN_IN_ERROR: i32 = 2;
+/// Code of calls with an unknown or expired session token.
+const INVALID_SESSION: i32 = 3;
+
+/// Seconds a nonce can be signed in with.
+const NONCE_TTL: u64 = 5 * 60;
+/// Seconds a session lasts at most.
+const SESSION_TTL: u64 = 60 * 60;
+/// Seconds the clocks of the node and of the operator may differ by.
+const MAX_CLOCK_SKEW: u64 = 60;
+/// Bytes of a nonce: when it expires, random bytes and the truncated HMAC of both.
+const NONCE_LEN: usize = 32;
+/// Bytes of a nonce before its HMAC.
+const NONCE_BODY_LEN: usize = 16;
+
+/// The first line of a sign-in message, after the domain.
+const HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+/// An ERC-4361 sign-in message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+	/// Host, and optionally port, of the service signed in to.
+	pub domain: String,
+	/// Address of the signer.
+	pub address: AccountId20,
+	/// What the signer agrees to, if anything.
+	pub statement: Option<String>,
+	/// URI of the resource signed in to.
+	pub uri: String,
+	/// The chain the signer's wallet was connected to.
+	pub chain_id: u64,
+	/// The nonce issued by the service.
+	pub nonce: String,
+	/// When the message was issued, in seconds since the Unix epoch.
+	pub issued_at: u64,
+	/// When the message expires, in seconds since the Unix epoch.
+	pub expiration_time: Option<u64>,
+	/// When the message becomes valid, in seconds since the Unix epoch.
+	pub not_before: Option<u64>,
+	/// Identifier of the request.
+	pub request_id: Option<String>,
+	/// Resources the signer grants access to.
+	pub resources: Vec<String>,
+}
+
+impl FromStr for Message {
+	type Err = String;
+
+	/// Parse a message following the ABNF of ERC-4361, with the address carrying its EIP-55
+	/// checksum.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut lines = Lines(s.split('\n').peekable());
+
+		let domain = lines
+			.next("header")?
+			.strip_suffix(HEADER_SUFFIX)
+			.filter(|domain| !domain.is_empty())
+			.ok_or("expected `<domain> wants you to sign in with your Ethereum account:`")?;
+		let address = lines.next("address")?;
+		let address = AccountId20::from_str(address)
+			.ok()
+			.filter(|parsed| parsed.to_string() == address)
+			.ok_or("expected an address with EIP-55 checksum")?;
+		lines.empty()?;
+		let statement = match lines.next("statement")? {
+			"" => None,
+			statement => {
+				lines.empty()?;
+				Some(statement.into())
+			},
+		};
+
+		let uri = lines.field("URI")?.into();
+		if lines.field("Version")? != "1" {
+			return Err("expected `Version: 1`".into())
+		}
+		let chain_id = lines.field("Chain ID")?.parse().map_err(|_| "invalid chain ID")?;
+		let nonce = lines.field("Nonce")?;
+		if nonce.len() < 8 || !nonce.bytes().all(|byte| byte.is_ascii_alphanumeric()) {
+			return Err("expected a nonce of at least 8 letters and digits".into())
+		}
+		let issued_at = timestamp(lines.field("Issued At")?)?;
+		let expiration_time = lines.optional("Expiration Time").map(timestamp).transpose()?;
+		let not_before = lines.optional("Not Before").map(timestamp).transpose()?;
+		let request_id = lines.optional("Request ID").map(Into::into);
+
+		let mut resources = Vec::new();
+		if lines.0.next_if_eq(&"Resources:").is_some() {
+			while let Some(resource) = lines.0.next_if(|line| line.starts_with("- ")) {
+				resources.push(resource[2..].into());
+			}
+		}
+		if lines.0.next().is_some() {
+			return Err("unexpected lines after the message".into())
+		}
+
+		Ok(Self {
+			domain: domain.into(),
+			address,
+			statement,
+			uri,
+			chain_id,
+			nonce: nonce.into(),
+			issued_at,
+			expiration_time,
+			not_before,
+			request_id,
+			resources,
+		})
+	}
+}
+
+/// The lines of a message.
+struct Lines<'a>(std::iter::Peekable<std::str::Split<'a, char>>);
+
+impl<'a> Lines<'a> {
+	fn next(&mut self, what: &str) -> Result<&'a str, String> {
+		self.0.next().ok_or_else(|| format!("missing {}", what))
+	}
+
+	fn empty(&mut self) -> Result<(), String> {
+		self.0.next_if_eq(&"").map(drop).ok_or_else(|| "expected an empty line".into())
+	}
+
+	/// The value of the line `<tag>: <value>`.
+	fn field(&mut self, tag: &str) -> Result<&'a str, String> {
+		self.optional(tag).ok_or_else(|| format!("expected `{}: `", tag))
+	}
+
+	/// The value of the line `<tag>: <value>`, if the next line has the tag.
+	fn optional(&mut self, tag: &str) -> Option<&'a str> {
+		let line: &'a str = *self.0.peek()?;
+		let value = line.strip_prefix(tag)?.strip_prefix(": ")?;
+		self.0.next();
+		Some(value)
+	}
+}
+
+/// The authority of `uri` without user information, like `localhost:9944` of
+/// `http://user@localhost:9944/rpc`, if it has one.
+fn authority(uri: &str) -> Option<&str> {
+	let (_scheme, rest) = uri.split_once("://")?;
+	let authority = rest.split(['/', '?', '#']).next()?;
+	Some(authority.rsplit_once('@').map_or(authority, |(_, host)| host))
+}
+
+/// Seconds since the Unix epoch of an RFC 3339 timestamp like `2021-09-30T16:25:24Z`.
+///
+/// Fractions of a second are dropped.
+fn timestamp(s: &str) -> Result<u64, String> {
+	let invalid = || format!("invalid RFC 3339 timestamp `{}`", s);
+	let number = |start: usize, len: usize| digits(s, start, len).ok_or_else(invalid);
+	let bytes = s.as_bytes();
+	if bytes.len() < 20 ||
+		bytes[4] != b'-' ||
+		bytes[7] != b'-' ||
+		!matches!(bytes[10], b'T' | b't') ||
+		bytes[13] != b':' ||
+		bytes[16] != b':'
+	{
+		return Err(invalid())
+	}
+
+	let (year, month, day) = (number(0, 4)?, number(5, 2)?, number(8, 2)?);
+	let (hour, minute, second) = (number(11, 2)?, number(14, 2)?, number(17, 2)?);
+	if !(1..=12).contains(&month) ||
+		!(1..=31).contains(&day) ||
+		hour > 23 ||
+		minute > 59 ||
+		second > 60
+	{
+		return Err(invalid())
+	}
+
+	let mut rest = &s[19..];
+	if let Some(fraction) = rest.strip_prefix('.') {
+		let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+		if digits == 0 {
+			return Err(invalid())
+		}
+		rest = &fraction[digits..];
+	}
+	let offset = match rest {
+		"Z" | "z" => 0,
+		_ => {
+			let sign = match rest.as_bytes().first() {
+				Some(b'+') => 1,
+				Some(b'-') => -1,
+				_ => return Err(invalid()),
+			};
+			if rest.len() != 6 || rest.as_bytes()[3] != b':' {
+				return Err(invalid())
+			}
+			let number = |start: usize| digits(rest, start, 2).ok_or_else(invalid);
+			sign * (number(1)? * 3600 + number(4)? * 60)
+		},
+	};
+
+	let seconds =
+		days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset;
+	u64::try_from(seconds).map_err(|_| invalid())
+}
+
+/// The number written with the `len` digits of `s` from `start`.
+fn digits(s: &str, start: usize, len: usize) -> Option<i64> {
+	s.get(start..start + len)
+		.filter(|digits| digits.bytes().all(|byte| byte.is_ascii_digit()))
+		.and_then(|digits| digits.parse().ok())
+}
+
+/// Days from 1970-01-01 to a date of the proleptic Gregorian calendar, after Howard Hinnant's
+/// `days_from_civil`, which counts years from March so that leap days end them.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let year_of_era = year - era * 400;
+	let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	era * 146_097 + day_of_era - 719_468
+}
+
+/// Who may sign in.
+#[derive(Debug, Clone)]
+pub struct SiweConfig {
+	/// The domain sign-in messages must be for.
+	pub domain: String,
+	/// The chain id sign-in messages must be for.
+	pub chain_id: u64,
+	/// Addresses of the operators.
+	pub operators: Vec<AccountId20>,
+}
+
+/// A signed-in operator's session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+	/// The token to pass to `siwe_call`.
+	pub token: String,
+	/// Address of the operator.
+	pub address: AccountId20,
+	/// When the session ends, in seconds since the Unix epoch.
+	pub expires_at: u64,
+}
+
+#[derive(Default)]
+struct State {
+	/// Bodies of the nonces signed in with, and when they expire.
+	used_nonces: HashMap<[u8; NONCE_BODY_LEN], u64>,
+	/// Sessions by token.
+	sessions: HashMap<String, Session>,
+}
+
+/// The operators, their nonces and their sessions, shared by the RPC servers of the node.
+pub struct Operators {
+	config: SiweConfig,
+	/// The key of the nonces' HMACs.
+	secret: [u8; 32],
+	state: Mutex<State>,
+}
+
+impl Operators {
+	/// Operators of `config`, none of them signed in.
+	pub fn new(config: SiweConfig) -> Self {
+		Self { config, secret: rand::thread_rng().gen(), state: Default::default() }
+	}
+
+	/// Issue a nonce at `now`, in seconds since the Unix epoch.
+	///
+	/// The nonce is the hex of when it expires, random bytes and the HMAC of both, so that issuing
+	/// it changes no state.
+	pub fn issue_nonce(&self, now: u64) -> String {
+		let mut nonce = [0; NONCE_LEN];
+		nonce[..8].copy_from_slice(&(now + NONCE_TTL).to_be_bytes());
+		rand::thread_rng().fill(&mut nonce[8..NONCE_BODY_LEN]);
+		let mac = hmac_sha256(&self.secret, &nonce[..NONCE_BODY_LEN]);
+		nonce[NONCE_BODY_LEN..].copy_from_slice(&mac[..NONCE_LEN - NONCE_BODY_LEN]);
+		nonce.iter().map(|byte| format!("{:02x}", byte)).collect()
+	}
+
+	/// The body of `nonce` and when it expires, if this node issued it.
+	fn verify_nonce(&self, nonce: &str) -> Option<([u8; NONCE_BODY_LEN], u64)> {
+		if nonce.len() != 2 * NONCE_LEN {
+			return None
+		}
+		let mut bytes = [0; NONCE_LEN];
+		for (byte, hex) in bytes.iter_mut().zip(nonce.as_bytes().chunks(2)) {
+			*byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+		}
+		let (body, mac) = bytes.split_at(NONCE_BODY_LEN);
+		let expected = hmac_sha256(&self.secret, body);
+		// Compare in constant time, not to reveal how much of a forged HMAC is right.
+		if expected.iter().zip(mac).fold(0, |diff, (a, b)| diff | (a ^ b)) != 0 {
+			return None
+		}
+		let expires_at = u64::from_be_bytes(body[..8].try_into().ok()?);
+		Some((body.try_into().ok()?, expires_at))
+	}
+
+	/// Start a session at `now` if `signature` is an operator's `personal_sign` signature of the
+	/// sign-in `message`.
+	pub fn sign_in(&self, message: &str, signature: &[u8], now: u64) -> Result<Session, String> {
+		let parsed = Message::from_str(message).map_err(|e| format!("invalid message: {}", e))?;
+		let signature: &[u8; 65] =
+			signature.try_into().map_err(|_| "expected a 65-byte signature")?;
+
+		if parsed.domain != self.config.domain {
+			return Err(format!("expected a message for `{}`", self.config.domain))
+		}
+		if authority(&parsed.uri) != Some(self.config.domain.as_str()) {
+			return Err(format!("expected a URI on `{}`", self.config.domain))
+		}
+		if parsed.chain_id != self.config.chain_id {
+			return Err(format!("expected a message for chain {}", self.config.chain_id))
+		}
+		verify_message(&parsed.address, message.as_bytes(), signature)
+			.map_err(|e| format!("the message is not signed by {}: {}", parsed.address, e))?;
+		if !self.config.operators.contains(&parsed.address) {
+			return Err(format!("{} is not an operator", parsed.address))
+		}
+		if parsed.expiration_time.map_or(false, |expiration| expiration <= now) {
+			return Err("the message has expired".into())
+		}
+		if parsed.not_before.map_or(false, |not_before| not_before > now) {
+			return Err("the message is not valid yet".into())
+		}
+		if parsed.issued_at > now + MAX_CLOCK_SKEW {
+			return Err("the message is issued in the future".into())
+		}
+
+		let (body, expires_at) = self
+			.verify_nonce(&parsed.nonce)
+			.filter(|(_, expires_at)| *expires_at > now)
+			.ok_or("unknown or expired nonce")?;
+		if parsed.issued_at + NONCE_TTL + MAX_CLOCK_SKEW < expires_at {
+			return Err("the message is issued before its nonce".into())
+		}
+		let mut state = self.state(now);
+		// Keyed by the body, which parsing yields whatever the case of the hex digits.
+		if state.used_nonces.insert(body, expires_at).is_some() {
+			return Err("used nonce".into())
+		}
+		let session = Session {
+			token: random_string(32),
+			address: parsed.address,
+			expires_at: parsed.expiration_time.unwrap_or(u64::MAX).min(now + SESSION_TTL),
+		};
+		state.sessions.insert(session.token.clone(), session.clone());
+		log::info!(target: "rpc", "Operator {} signed in", session.address);
+
+		Ok(session)
+	}
+
+	/// End the session of `token`, returning whether there was one.
+	pub fn sign_out(&self, token: &str, now: u64) -> bool {
+		self.state(now).sessions.remove(token).is_some()
+	}
+
+	/// The session of `token` at `now`, if it hasn't ended.
+	pub fn session(&self, token: &str, now: u64) -> Option<Session> {
+		self.state(now).sessions.get(token).cloned()
+	}
+
+	/// The state without what expired by `now`.
+	fn state(&self, now: u64) -> MutexGuard<State> {
+		let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+		state.used_nonces.retain(|_, expires_at| *expires_at > now);
+		state.sessions.retain(|_, session| session.expires_at > now);
+		state
+	}
+}
+
+/// HMAC-SHA256 (RFC 2104) of `message` keyed with `key`.
+fn hmac_sha256(key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+	// The key is shorter than SHA-256's 64-byte blocks, so it is padded with zeros.
+	let pad = |byte: u8| key.iter().chain(&[0; 32]).map(move |k| k ^ byte);
+	let inner: Vec<u8> = pad(0x36).chain(message.iter().copied()).collect();
+	let outer: Vec<u8> = pad(0x5c).chain(sha2_256(&inner)).collect();
+	sha2_256(&outer)
+}
+
+fn random_string(len: usize) -> String {
+	rand::thread_rng()
+		.sample_iter(&Alphanumeric)
+		.take(len)
+		.map(char::from)
+		.collect()
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Sign-In With Ethereum RPC methods.
+#[rpc(client, server)]
+pub trait SiweApi {
+	/// A nonce for the sign-in message.
+	#[method(name = "siwe_nonce")]
+	fn nonce(&self) -> RpcResult<String>;
+
+	/// Start a session if `signature` is an operator's `personal_sign` signature of the ERC-4361
+	/// `message`.
+	#[method(name = "siwe_signIn")]
+	fn sign_in(&self, message: String, signature: Bytes) -> RpcResult<Session>;
+
+	/// End the session of `token`, returning whether there was one.
+	#[method(name = "siwe_signOut")]
+	fn sign_out(&self, token: String) -> RpcResult<bool>;
+
+	/// Call the unsafe `method` with `params` in the session of `token`.
+	#[method(name = "siwe_call")]
+	async fn call(&self, token: String, method: String, params: Option<Value>) -> RpcResult<Value>;
+}
+
+/// Implementation of [`SiweApiServer`], calling into `unsafe_methods` for signed-in operators.
+pub struct Siwe {
+	operators: Arc<Operators>,
+	unsafe_methods: RpcModule<()>,
+}
+
+impl Siwe {
+	/// Serve sign-ins of `operators`, who may call `unsafe_methods`.
+	pub fn new(operators: Arc<Operators>, unsafe_methods: RpcModule<()>) -> Self {
+		Self { operators, unsafe_methods }
+	}
+}
+
+#[async_trait]
+impl SiweApiServer for Siwe {
+	fn nonce(&self) -> RpcResult<String> {
+		Ok(self.operators.issue_nonce(unix_time()))
+	}
+
+	fn sign_in(&self, message: String, signature: Bytes) -> RpcResult<Session> {
+		self.operators
+			.sign_in(&message, &signature, unix_time())
+			.map_err(|e| error(SIGN_IN_ERROR, e, None))
+	}
+
+	fn sign_out(&self, token: String) -> RpcResult<bool> {
+		Ok(self.operators.sign_out(&token, unix_time()))
+	}
+
+	async fn call(&self, token: String, method: String, params: Option<Value>) -> RpcResult<Value> {
+		let session = self
+			.operators
+			.session(&token, unix_time())
+			.ok_or_else(|| error(INVALID_SESSION, "unknown or expired session", None))?;
+		match self.unsafe_methods.method(&method).map(MethodCallback::inner) {
+			Some(MethodKind::Sync(_) | MethodKind::Async(_)) => {},
+			// The notifications of a subscription would have no connection to go to.
+			Some(_) =>
+				return Err(error(
+					ErrorCode::MethodNotFound.code(),
+					format!("`{}` is a subscription, which `siwe_call` doesn't serve", method),
+					None,
+				)),
+			None =>
+				return Err(error(
+					ErrorCode::MethodNotFound.code(),
+					format!("`{}` is not available through `siwe_call`", method),
+					None,
+				)),
+		}
+		log::info!(target: "rpc", "Operator {} calls {}", session.address, method);
+
+		let request = json!({
+			"jsonrpc": "2.0",
+			"id": 0,
+			"method": method,
+			"params": params.unwrap_or_else(|| json!([])),
+		});
+		let (response, _) = self.unsafe_methods.raw_json_request(&request.to_string()).await?;
+		let mut response: Value = serde_json::from_str(&response)?;
+
+		if let Some(result) = response.get_mut("result") {
+			return Ok(result.take())
+		}
+		let failure = response["error"].take();
+		Err(error(
+			failure["code"].as_i64().unwrap_or_default() as i32,
+			failure["message"].as_str().unwrap_or_default(),
+			failure.get("data").cloned(),
+		))
+	}
+}
+
+fn error(code: i32, message: impl Into<String>, data: Option<Value>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(code, message.into(), data)).into()
+}
@@ -1,15 +1,43 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::BlockBackend;
-use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
+use crate::{
+	cli::Sealing,
+	equivocation::AuraEquivocationImport,
+	preverify::{PreverifyBlockImport, VerifiedSignaturesFactory},
+	rpc::siwe::{Operators, SiweConfig},
+};
+use account::aura::AuthorityPair as AuraPair;
+use author_vrf::AuthorVrfApi;
+use futures::{channel::mpsc, prelude::*};
+use node_template_runtime::{self, opaque::Block, Hash, RuntimeApi, SLOT_DURATION};
+use sc_client_api::{BlockBackend, ExecutorProvider, StorageProvider};
+use sc_consensus_aura::{CheckForEquivocation, ImportQueueParams, SlotProportion, StartAuraParams};
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
 use sc_keystore::LocalKeystore;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_service::{
+	config::RpcMethods, error::Error as ServiceError, Configuration, RpcHandlers, TaskManager,
+};
 use sc_telemetry::{Telemetry, TelemetryWorker};
-use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use sc_transaction_pool_api::TransactionPool;
+use signature_batch::VerifiedSignatures;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::Slot;
+use sp_core::{storage::StorageKey, Decode};
+use sp_keystore::SyncCryptoStore;
+use sp_runtime::generic::BlockId;
+use std::{
+	pin::Pin,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -17,10 +45,14 @@ pub struct ExecutorDispatch;
 impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
 	/// Only enable the benchmarking host functions when we actually want to benchmark.
 	#[cfg(feature = "runtime-benchmarks")]
-	type ExtendHostFunctions = frame_benchmarking::benchmarking::HostFunctions;
-	/// Otherwise we only use the default Substrate host functions.
+	type ExtendHostFunctions = (
+		frame_benchmarking::benchmarking::HostFunctions,
+		signature_batch::batch_verify::HostFunctions,
+	);
+	/// Otherwise we only add the lookup of pre-verified signatures to the default Substrate host
+	/// functions.
 	#[cfg(not(feature = "runtime-benchmarks"))]
-	type ExtendHostFunctions = ();
+	type ExtendHostFunctions = signature_batch::batch_verify::HostFunctions;
 
 	fn dispatch(method: &str, data: &[u8]) -> Option<Vec<u8>> {
 		node_template_runtime::api::dispatch(method, data)
@@ -31,20 +63,22 @@ impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
 	}
 }
 
-pub(crate) type FullClient =
+pub type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullPool = sc_transaction_pool::FullPool<Block, FullClient>;
 
 pub fn new_partial(
 	config: &Configuration,
+	sealing: Option<Sealing>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block, FullClient>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		FullPool,
 		(
 			sc_finality_grandpa::GrandpaBlockImport<
 				FullBackend,
@@ -88,6 +122,12 @@ pub fn new_partial(
 		)?;
 	let client = Arc::new(client);
 
+	// Signatures checked ahead of execution by `PreverifyBlockImport`, visible to the runtime.
+	let verified_signatures = VerifiedSignatures::default();
+	client
+		.execution_extensions()
+		.set_extensions_factory(Box::new(VerifiedSignaturesFactory(verified_signatures.clone())));
+
 	let telemetry = telemetry.map(|(worker, telemetry)| {
 		task_manager.spawn_handle().spawn("telemetry", None, worker.run());
 		telemetry
@@ -110,11 +150,46 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
+	let preverify_error =
+		|e| ServiceError::Other(format!("Failed to spawn signature verifiers: {}", e));
+
+	if sealing.is_some() {
+		let block_import =
+			PreverifyBlockImport::new(client.clone(), client.clone(), verified_signatures)
+				.map_err(preverify_error)?;
+		let import_queue = sc_consensus_manual_seal::import_queue(
+			Box::new(block_import),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		);
+
+		return Ok(sc_service::PartialComponents {
+			client,
+			backend,
+			task_manager,
+			import_queue,
+			keystore_container,
+			select_chain,
+			transaction_pool,
+			other: (grandpa_block_import, grandpa_link, telemetry),
+		})
+	}
+
+	let preverify_block_import = PreverifyBlockImport::new(
+		grandpa_block_import.clone(),
+		client.clone(),
+		verified_signatures,
+	)
+	.map_err(preverify_error)?;
+
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
+	let equivocation_block_import =
+		AuraEquivocationImport::new(preverify_block_import, client.clone(), slot_duration);
+
 	let import_queue =
 		sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(ImportQueueParams {
-			block_import: grandpa_block_import.clone(),
+			block_import: equivocation_block_import,
 			justification_import: Some(Box::new(grandpa_block_import.clone())),
 			client: client.clone(),
 			create_inherent_data_providers: move |_, ()| async move {
@@ -130,7 +205,8 @@ pub fn new_partial(
 			},
 			spawner: &task_manager.spawn_essential_handle(),
 			registry: config.prometheus_registry(),
-			check_for_equivocation: Default::default(),
+			// `AuraEquivocationImport` reports equivocations instead of only logging them.
+			check_for_equivocation: CheckForEquivocation::No,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 		})?;
 
@@ -153,8 +229,83 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 	Err("Remote Keystore not supported.")
 }
 
+/// Timestamp stored in the state of block `hash`, or zero for the genesis block.
+fn block_timestamp(client: &FullClient, hash: Hash) -> Result<u64, ServiceError> {
+	let key = StorageKey(frame_support::storage::storage_prefix(b"Timestamp", b"Now").to_vec());
+	client
+		.storage(&BlockId::Hash(hash), &key)?
+		.map_or(Ok(0), |data| u64::decode(&mut &data.0[..]))
+		.map_err(|e| ServiceError::Other(format!("Invalid timestamp of block {}: {}", hash, e)))
+}
+
+/// Timestamp for the next manually sealed block.
+///
+/// Aura accepts at most one block per slot, so blocks sealed in quick succession are moved into
+/// the following slots rather than all being stamped with the current time. `last` starts at the
+/// timestamp of the best block, so that this holds across restarts too.
+fn next_timestamp(last: &AtomicU64) -> u64 {
+	let now = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.expect("Current time is always after unix epoch; qed")
+		.as_millis() as u64;
+	let next = |last: u64| now.max(last + SLOT_DURATION);
+
+	let last = last
+		.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(next(last)))
+		.expect("Update closure always returns `Some`; qed");
+	next(last)
+}
+
+/// The author VRF of the block on top of `parent` in `slot`, if the keystore has the key of the
+/// slot's author.
+fn author_vrf(
+	client: &FullClient,
+	keystore: &dyn SyncCryptoStore,
+	parent: Hash,
+	slot: Slot,
+) -> Result<author_vrf::InherentDataProvider, Box<dyn std::error::Error + Send + Sync>> {
+	let at = BlockId::Hash(parent);
+	let number = client.number(parent)?.ok_or("Parent block is unknown")? + 1;
+	let authorities = client.runtime_api().authorities(&at)?;
+	if authorities.is_empty() {
+		return Err("No author VRF keys".into())
+	}
+	let author = &authorities[(*slot % authorities.len() as u64) as usize];
+	let randomness = client.runtime_api().randomness(&at)?;
+
+	Ok(author_vrf::InherentDataProvider::new(keystore, author, &randomness, number.into()))
+}
+
+/// Result of [`new_full_base`].
+pub struct NewFullBase {
+	/// The task manager of the node.
+	pub task_manager: TaskManager,
+	/// The client instance of the node.
+	pub client: Arc<FullClient>,
+	/// The transaction pool of the node.
+	pub transaction_pool: Arc<FullPool>,
+	/// The rpc handlers of the node.
+	pub rpc_handlers: RpcHandlers,
+}
+
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// Aura and GRANDPA are replaced by manual sealing if `sealing` is given. The operators of `siwe`,
+/// if any, may call unsafe RPC methods after signing in with Ethereum.
+pub fn new_full(
+	config: Configuration,
+	sealing: Option<Sealing>,
+	siwe: Option<SiweConfig>,
+) -> Result<TaskManager, ServiceError> {
+	new_full_base(config, sealing, siwe).map(|NewFullBase { task_manager, .. }| task_manager)
+}
+
+/// Creates a full service from the configuration, returning handles to its components.
+pub fn new_full_base(
+	mut config: Configuration,
+	sealing: Option<Sealing>,
+	siwe: Option<SiweConfig>,
+) -> Result<NewFullBase, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -164,7 +315,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial(&config, sealing)?;
 
 	if let Some(url) = &config.keystore_remote {
 		match remote_keystore(url) {
@@ -218,18 +369,57 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// Channel for the `engine_*` RPCs to drive manual sealing.
+	let (command_sink, rpc_commands) = match sealing {
+		Some(_) => {
+			let (sink, commands) = mpsc::channel(1024);
+			(Some(sink), Some(commands))
+		},
+		None => (None, None),
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
-
-		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+		if siwe.is_some() && matches!(config.rpc_methods, RpcMethods::Unsafe) {
+			log::warn!(
+				target: "rpc",
+				"Unsafe RPC methods are open to everyone, not only to operators who sign in. \
+				 Run with `--rpc-methods safe` to reserve them for operators."
+			);
+		}
+		// Shared by the HTTP and WebSocket servers, so that sessions are valid on both.
+		let operators = siwe.map(|config| Arc::new(Operators::new(config)));
+		let keystore = keystore_container.sync_keystore();
+		let system_info = sc_rpc::system::SystemInfo {
+			impl_name: config.impl_name.clone(),
+			impl_version: config.impl_version.clone(),
+			chain_name: config.chain_spec.name().into(),
+			properties: config.chain_spec.properties(),
+			chain_type: config.chain_spec.chain_type(),
+		};
+		let system_rpc_tx = system_rpc_tx.clone();
+
+		Box::new(move |deny_unsafe, executor| {
+			let siwe = operators.clone().map(|operators| crate::rpc::SiweDeps {
+				operators,
+				keystore: keystore.clone(),
+				executor,
+				system_info: system_info.clone(),
+				system_rpc_tx: system_rpc_tx.clone(),
+			});
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: command_sink.clone(),
+				siwe,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
 
-	let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		network: network.clone(),
 		client: client.clone(),
 		keystore: keystore_container.sync_keystore(),
@@ -243,25 +433,98 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	if let (Some(sealing), Some(rpc_commands)) = (sealing, rpc_commands) {
+		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		);
+
+		let seal_and_finalize = |create_empty| EngineCommand::SealNewBlock {
+			create_empty,
+			finalize: true,
+			parent_hash: None,
+			sender: None,
+		};
+		let commands_stream: Pin<Box<dyn Stream<Item = EngineCommand<Hash>> + Send>> = match sealing
+		{
+			Sealing::Instant => Box::pin(stream::select(
+				rpc_commands,
+				transaction_pool
+					.import_notification_stream()
+					.map(move |_| seal_and_finalize(false)),
+			)),
+			Sealing::Manual => Box::pin(rpc_commands),
+			Sealing::Interval(millis) => Box::pin(stream::select(
+				rpc_commands,
+				stream::unfold((), move |()| async move {
+					futures_timer::Delay::new(Duration::from_millis(millis)).await;
+					Some((seal_and_finalize(true), ()))
+				}),
+			)),
+		};
+
+		let last_timestamp =
+			Arc::new(AtomicU64::new(block_timestamp(&client, client.info().best_hash)?));
+		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+		let keystore = keystore_container.sync_keystore();
+		let inherents_client = client.clone();
+		let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env: proposer_factory,
+			client: client.clone(),
+			pool: transaction_pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client.clone()))),
+			create_inherent_data_providers: move |parent, ()| {
+				let timestamp =
+					sp_timestamp::InherentDataProvider::new(next_timestamp(&last_timestamp).into());
+				let slot =
+					sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+						*timestamp,
+						slot_duration,
+					);
+				let vrf = author_vrf(&inherents_client, &*keystore, parent, *slot);
+				async move { Ok((timestamp, vrf?)) }
+			},
+		});
+
+		// the manual-seal authoring task is considered essential, i.e. if it
+		// fails we take down the service with it.
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"manual-seal",
+			Some("block-authoring"),
+			manual_seal,
+		);
+
+		network_starter.start_network();
+		return Ok(NewFullBase { task_manager, client, transaction_pool, rpc_handlers })
+	}
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
-			transaction_pool,
+			transaction_pool.clone(),
 			prometheus_registry.as_ref(),
 			telemetry.as_ref().map(|x| x.handle()),
 		);
 
 		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+		let keystore = keystore_container.sync_keystore();
+		let inherents_client = client.clone();
 
 		let aura = sc_consensus_aura::start_aura::<AuraPair, _, _, _, _, _, _, _, _, _, _>(
 			StartAuraParams {
 				slot_duration,
-				client,
+				client: client.clone(),
 				select_chain,
 				block_import,
 				proposer_factory,
-				create_inherent_data_providers: move |_, ()| async move {
+				create_inherent_data_providers: move |parent, ()| {
 					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
 
 					let slot =
@@ -269,8 +532,9 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 							*timestamp,
 							slot_duration,
 						);
+					let vrf = author_vrf(&inherents_client, &*keystore, parent, *slot);
 
-					Ok((slot, timestamp))
+					async move { Ok((slot, timestamp, vrf?)) }
 				},
 				force_authoring,
 				backoff_authoring_blocks,
@@ -334,5 +598,5 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	}
 
 	network_starter.start_network();
-	Ok(task_manager)
+	Ok(NewFullBase { task_manager, client, transaction_pool, rpc_handlers })
 }
@@ -0,0 +1,700 @@
+//! Offline transaction signing, submission and decoding.
+//!
+//! `tx sign` needs nothing but its arguments to produce a signed extrinsic, so it can run on an
+//! air-gapped machine. Everything that would otherwise be fetched from a node, i.e. nonce, era,
+//! genesis hash and runtime versions, is passed on the command line. `tx submit` then pushes the
+//! result to a node from an online machine. `tx decode` turns an extrinsic back into JSON against
+//! the runtime compiled into this binary.
+
+use account::AccountId20;
+use codec::Compact;
+use frame_support::{
+	dispatch::GetCallMetadata,
+	metadata::RuntimeMetadata,
+	scale_info::{
+		form::PortableForm, Field, PortableRegistry, Type, TypeDef, TypeDefPrimitive, Variant,
+	},
+};
+use node_template_runtime::{
+	self as runtime, AccountId, Balance, BalancesCall, Hash, Index, RuntimeCall, SystemCall,
+};
+pub use node_template_sdk::{sign_extrinsic, signed_payload, SigningParams};
+use sc_cli::Result;
+use serde_json::{json, Map, Value};
+use sp_core::{bytes, ecdsa, Bytes, Decode, Encode, Pair, U256};
+use sp_runtime::{
+	generic::Era,
+	traits::{IdentifyAccount, Verify},
+	MultiAddress, MultiSignature, MultiSigner,
+};
+use std::str::FromStr;
+
+/// Validity period of a transaction as given on the command line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mortality {
+	/// Valid forever.
+	Immortal,
+	/// Valid for about `period` blocks from block number `current` on.
+	Mortal { period: u64, current: u64 },
+}
+
+impl FromStr for Mortality {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		let invalid = || format!("expected `immortal` or `PERIOD@BLOCK_NUMBER`, got `{}`", s);
+
+		match s.split_once('@') {
+			None if s == "immortal" => Ok(Self::Immortal),
+			None => Err(invalid()),
+			Some((period, current)) => Ok(Self::Mortal {
+				period: period.parse().map_err(|_| invalid())?,
+				current: current.parse().map_err(|_| invalid())?,
+			}),
+		}
+	}
+}
+
+/// Offline transaction utilities.
+#[derive(Debug, clap::Subcommand)]
+pub enum TxCmd {
+	/// Sign a call without connecting to a node and print the extrinsic as hex.
+	Sign(SignCmd),
+
+	/// Submit a signed extrinsic to a node.
+	Submit(SubmitCmd),
+
+	/// Decode an extrinsic and print it as JSON.
+	Decode(DecodeCmd),
+}
+
+impl TxCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		match self {
+			Self::Sign(cmd) => cmd.run(),
+			Self::Submit(cmd) => cmd.run(),
+			Self::Decode(cmd) => cmd.run(),
+		}
+	}
+}
+
+/// The `tx sign` command.
+#[derive(Debug, clap::Parser)]
+pub struct SignCmd {
+	/// The call, either SCALE-encoded as `0x`-prefixed hex or one of `balances.transfer DEST
+	/// VALUE`, `balances.transfer_keep_alive DEST VALUE`, `balances.transfer_all DEST KEEP_ALIVE`
+	/// and `system.remark HEX`.
+	#[arg(value_name = "CALL")]
+	pub call: String,
+
+	/// Arguments of a named call. Accounts are SS58 addresses or `0x`-prefixed hex.
+	#[arg(value_name = "ARGS")]
+	pub args: Vec<String>,
+
+	/// Secret URI of the Ethereum (secp256k1) signing key, or a path to a file containing it.
+	///
+	/// Accepts a `0x`-prefixed private key, a dev seed like `//Alice` or a BIP-39 phrase. Note
+	/// that phrases are expanded the Substrate way, not along Ethereum's BIP-44 derivation path.
+	/// Read from stdin if omitted.
+	#[arg(long)]
+	pub suri: Option<String>,
+
+	/// Transaction index of the signer.
+	#[arg(long)]
+	pub nonce: Index,
+
+	/// Validity period: `immortal` or `PERIOD@BLOCK_NUMBER` for a mortal transaction.
+	#[arg(long, default_value = "immortal", value_name = "immortal|PERIOD@BLOCK_NUMBER")]
+	pub era: Mortality,
+
+	/// Hash of the block a mortal era starts at, which is `BLOCK_NUMBER` rounded down to the era's
+	/// phase. Required unless the era is immortal.
+	#[arg(long)]
+	pub era_hash: Option<Hash>,
+
+	/// Tip for the block author.
+	#[arg(long, default_value_t = 0)]
+	pub tip: Balance,
+
+	/// Genesis hash of the target chain.
+	#[arg(long)]
+	pub genesis_hash: Hash,
+
+	/// `spec_version` of the target runtime. Defaults to the version compiled into this binary.
+	#[arg(long, default_value_t = runtime::VERSION.spec_version)]
+	pub spec_version: u32,
+
+	/// `transaction_version` of the target runtime. Defaults to the version compiled into this
+	/// binary.
+	#[arg(long, default_value_t = runtime::VERSION.transaction_version)]
+	pub transaction_version: u32,
+}
+
+impl SignCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		println!("{}", bytes::to_hex(&self.sign()?.encode(), false));
+		Ok(())
+	}
+
+	/// The signed extrinsic.
+	pub fn sign(&self) -> Result<runtime::UncheckedExtrinsic> {
+		let call = parse_call(&self.call, &self.args)?;
+		let uri = sc_cli::utils::read_uri(self.suri.as_ref())?;
+		let signer = ecdsa::Pair::from_string(&uri, None)
+			.map_err(|e| format!("invalid signing key: {:?}", e))?;
+
+		let (era, birth_hash) = match (self.era, self.era_hash) {
+			(Mortality::Immortal, _) => (Era::Immortal, self.genesis_hash),
+			(Mortality::Mortal { period, current }, Some(hash)) =>
+				(Era::mortal(period, current), hash),
+			(Mortality::Mortal { period, current }, None) =>
+				return Err(format!(
+					"`--era-hash` must be the hash of block {}",
+					Era::mortal(period, current).birth(current)
+				)
+				.into()),
+		};
+		let params = SigningParams {
+			nonce: self.nonce,
+			era,
+			tip: self.tip,
+			genesis_hash: self.genesis_hash,
+			birth_hash,
+			spec_version: self.spec_version,
+			transaction_version: self.transaction_version,
+			sponsor: None,
+		};
+
+		Ok(sign_extrinsic(&signer, call, &params))
+	}
+}
+
+/// The `tx submit` command.
+#[derive(Debug, clap::Parser)]
+pub struct SubmitCmd {
+	/// The signed extrinsic as `0x`-prefixed hex, as printed by `tx sign`.
+	#[arg(value_name = "EXTRINSIC")]
+	pub extrinsic: String,
+
+	/// HTTP RPC endpoint of the node to submit to.
+	#[arg(long, default_value = "http://127.0.0.1:9933")]
+	pub url: String,
+}
+
+impl SubmitCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		use jsonrpsee::{core::client::ClientT, http_client::HttpClientBuilder, rpc_params};
+
+		let xt = decode_hex::<runtime::UncheckedExtrinsic>(&self.extrinsic)?;
+		let client = HttpClientBuilder::default()
+			.build(&self.url)
+			.map_err(|e| format!("invalid RPC endpoint `{}`: {}", self.url, e))?;
+
+		let hash: Hash = tokio::runtime::Runtime::new()?
+			.block_on(client.request("author_submitExtrinsic", rpc_params![Bytes(xt.encode())]))
+			.map_err(|e| format!("submission failed: {}", e))?;
+		println!("{:?}", hash);
+
+		Ok(())
+	}
+}
+
+/// The `tx decode` command.
+#[derive(Debug, clap::Parser)]
+pub struct DecodeCmd {
+	/// The extrinsic as `0x`-prefixed hex.
+	#[arg(value_name = "EXTRINSIC")]
+	pub extrinsic: String,
+
+	/// Genesis hash of the chain the extrinsic was signed for. Required to check the signature
+	/// and, for secp256k1 signatures, to recover the signer's Ethereum address.
+	#[arg(long)]
+	pub genesis_hash: Option<Hash>,
+
+	/// Hash of the block a mortal era starts at.
+	#[arg(long)]
+	pub era_hash: Option<Hash>,
+
+	/// `spec_version` the extrinsic was signed for. Defaults to the version compiled into this
+	/// binary.
+	#[arg(long, default_value_t = runtime::VERSION.spec_version)]
+	pub spec_version: u32,
+
+	/// `transaction_version` the extrinsic was signed for. Defaults to the version compiled into
+	/// this binary.
+	#[arg(long, default_value_t = runtime::VERSION.transaction_version)]
+	pub transaction_version: u32,
+}
+
+impl DecodeCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<()> {
+		print_json(&self.decode()?)
+	}
+
+	/// The extrinsic as JSON.
+	pub fn decode(&self) -> Result<Value> {
+		let xt = decode_hex::<runtime::UncheckedExtrinsic>(&self.extrinsic)?;
+		let metadata = xt.function.get_call_metadata();
+		let call = json!({
+			"pallet": metadata.pallet_name,
+			"name": metadata.function_name,
+			"args": call_args(&xt.function)?,
+		});
+
+		let (address, signature, extra) = match xt.signature {
+			Some(signature) => signature,
+			None => return Ok(json!({ "signed": false, "call": call })),
+		};
+		let era = Era::decode(&mut &extra.4.encode()[..]).expect("`CheckEra` encodes its era; qed");
+		let nonce = extra.5 .0;
+		let tip = extra.7.tip();
+		let sponsor = extra.7.sponsor().cloned();
+		let account = match &address {
+			MultiAddress::Id(account) => json!(account.to_string()),
+			other => json!(format!("{:?}", other)),
+		};
+		let mut decoded = json!({
+			"signed": true,
+			"signer": account,
+			"account": account,
+			"era": match era {
+				Era::Immortal => json!("immortal"),
+				Era::Mortal(period, phase) => json!({ "period": period, "phase": phase }),
+			},
+			"nonce": nonce,
+			"tip": tip.to_string(),
+			"sponsor": sponsor.as_ref().map_or(Value::Null, |sponsor| json!({
+				"address": AccountId20::from(sponsor.address).to_string(),
+				"nonce": sponsor.nonce,
+			})),
+			"call": call,
+		});
+
+		let genesis_hash = match (self.genesis_hash, &signature.0) {
+			(Some(genesis_hash), _) => genesis_hash,
+			(None, MultiSignature::Ecdsa(_)) =>
+				return Err(
+					"`--genesis-hash` is required to recover the address of a secp256k1 signer"
+						.into(),
+				),
+			(None, _) => return Ok(decoded),
+		};
+		if era != Era::Immortal && self.era_hash.is_none() {
+			return Err("`--era-hash` is required for mortal transactions".into())
+		}
+		let params = SigningParams {
+			nonce,
+			era,
+			tip,
+			genesis_hash,
+			birth_hash: self.era_hash.unwrap_or(genesis_hash),
+			spec_version: self.spec_version,
+			transaction_version: self.transaction_version,
+			sponsor,
+		};
+		let payload = signed_payload(xt.function, extra, &params);
+
+		if let MultiSignature::Ecdsa(signature) = &signature.0 {
+			// Ethereum-keyed accounts are known by their address rather than by the hash of their
+			// public key.
+			let public = payload.using_encoded(|msg| signature.recover(msg));
+			decoded["signer"] = public
+				.as_ref()
+				.map_or(Value::Null, |public| json!(AccountId20::from(public.clone()).to_string()));
+			decoded["public"] = public
+				.as_ref()
+				.map_or(Value::Null, |public| json!(bytes::to_hex(public.as_ref(), false)));
+		}
+		decoded["signature_valid"] = json!(match &address {
+			MultiAddress::Id(signer) =>
+				payload.using_encoded(|msg| signature.0.verify(msg, signer)),
+			_ => false,
+		});
+
+		Ok(decoded)
+	}
+}
+
+fn print_json(value: &Value) -> Result<()> {
+	println!("{}", serde_json::to_string_pretty(value).map_err(|e| e.to_string())?);
+	Ok(())
+}
+
+/// The arguments of `call` as a JSON object, decoded against the type information in the
+/// metadata of the runtime compiled into this binary.
+fn call_args(call: &RuntimeCall) -> Result<Value> {
+	let metadata = match runtime::Runtime::metadata().1 {
+		RuntimeMetadata::V14(metadata) => metadata,
+		_ => return Err("the runtime's metadata is not V14".into()),
+	};
+	let encoded = call.encode();
+	let calls = metadata
+		.pallets
+		.iter()
+		.find(|pallet| pallet.index == encoded[0])
+		.and_then(|pallet| pallet.calls.as_ref())
+		.ok_or("the runtime's metadata lacks the call's pallet")?;
+	let decoder = JsonDecoder { types: &metadata.types };
+	let mut input = &encoded[1..];
+
+	// Unlike other enums, the call itself is shown as just its arguments.
+	let variant = decoder.variant(calls.ty.id(), &mut input)?;
+	let args = match decoder.fields(variant.fields(), &mut input)? {
+		Value::Null => json!({}),
+		args => args,
+	};
+	if !input.is_empty() {
+		return Err(format!("{} trailing bytes after the call", input.len()).into())
+	}
+	Ok(args)
+}
+
+/// Decodes SCALE-encoded values to JSON along the types of a [`PortableRegistry`].
+///
+/// Structs become objects, or their only field's value if that is unnamed, enums follow serde's
+/// externally tagged representation, byte strings and arrays become `0x`-prefixed hex, and
+/// 128-bit or wider integers become decimal strings so that JSON parsers don't lose precision.
+struct JsonDecoder<'a> {
+	types: &'a PortableRegistry,
+}
+
+impl JsonDecoder<'_> {
+	fn decode(&self, id: u32, input: &mut &[u8]) -> Result<Value> {
+		let ty = self.resolve(id)?;
+
+		Ok(match ty.type_def() {
+			TypeDef::Composite(_)
+				if ty.path().segments().last().map(String::as_str) == Some("AccountId32") =>
+				json!(read::<AccountId>(input)?.to_string()),
+			TypeDef::Composite(composite) => self.fields(composite.fields(), input)?,
+			TypeDef::Variant(_) => {
+				let variant = self.variant(id, input)?;
+				match variant.fields() {
+					[] => json!(variant.name()),
+					fields => {
+						let mut tagged = Map::new();
+						tagged.insert(variant.name().clone(), self.fields(fields, input)?);
+						Value::Object(tagged)
+					},
+				}
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = read::<Compact<u32>>(input)?.0;
+				self.elements(sequence.type_param().id(), len, input)?
+			},
+			TypeDef::Array(array) => self.elements(array.type_param().id(), array.len(), input)?,
+			TypeDef::Tuple(tuple) => match tuple.fields() {
+				[] => Value::Null,
+				fields => fields
+					.iter()
+					.map(|field| self.decode(field.id(), input))
+					.collect::<Result<_>>()?,
+			},
+			TypeDef::Primitive(primitive) => match primitive {
+				TypeDefPrimitive::Bool => json!(read::<bool>(input)?),
+				TypeDefPrimitive::Char =>
+					json!(char::from_u32(read::<u32>(input)?).ok_or("invalid char")?.to_string()),
+				TypeDefPrimitive::Str => json!(read::<String>(input)?),
+				TypeDefPrimitive::U8 => json!(read::<u8>(input)?),
+				TypeDefPrimitive::U16 => json!(read::<u16>(input)?),
+				TypeDefPrimitive::U32 => json!(read::<u32>(input)?),
+				TypeDefPrimitive::U64 => json!(read::<u64>(input)?),
+				TypeDefPrimitive::U128 => json!(read::<u128>(input)?.to_string()),
+				TypeDefPrimitive::U256 => json!(read::<U256>(input)?.to_string()),
+				TypeDefPrimitive::I8 => json!(read::<i8>(input)?),
+				TypeDefPrimitive::I16 => json!(read::<i16>(input)?),
+				TypeDefPrimitive::I32 => json!(read::<i32>(input)?),
+				TypeDefPrimitive::I64 => json!(read::<i64>(input)?),
+				TypeDefPrimitive::I128 => json!(read::<i128>(input)?.to_string()),
+				TypeDefPrimitive::I256 =>
+					return Err("256-bit signed integers are not supported".into()),
+			},
+			TypeDef::Compact(compact) => {
+				let value = read::<Compact<u128>>(input)?.0;
+				match self.primitive(compact.type_param().id())? {
+					TypeDefPrimitive::U128 => json!(value.to_string()),
+					_ => json!(value as u64),
+				}
+			},
+			TypeDef::BitSequence(_) => return Err("bit sequences are not supported".into()),
+		})
+	}
+
+	/// The variant of the enum `id` selected by the index at the start of `input`.
+	fn variant(&self, id: u32, input: &mut &[u8]) -> Result<&Variant<PortableForm>> {
+		let ty = self.resolve(id)?;
+		let index = read::<u8>(input)?;
+
+		match ty.type_def() {
+			TypeDef::Variant(variant) => variant
+				.variants()
+				.iter()
+				.find(|variant| variant.index() == index)
+				.ok_or_else(|| format!("unknown variant {} of `{}`", index, path(ty)).into()),
+			_ => Err(format!("`{}` is not an enum", path(ty)).into()),
+		}
+	}
+
+	/// The values of `fields`: an object if they are named, the value of a single unnamed field,
+	/// an array otherwise.
+	fn fields(&self, fields: &[Field<PortableForm>], input: &mut &[u8]) -> Result<Value> {
+		match fields {
+			[] => Ok(Value::Null),
+			[field] if field.name().is_none() => self.decode(field.ty().id(), input),
+			_ if fields.iter().all(|field| field.name().is_some()) => fields
+				.iter()
+				.map(|field| {
+					let name = field.name().cloned().unwrap_or_default();
+					Ok((name, self.decode(field.ty().id(), input)?))
+				})
+				.collect::<Result<Map<_, _>>>()
+				.map(Value::Object),
+			_ => fields.iter().map(|field| self.decode(field.ty().id(), input)).collect(),
+		}
+	}
+
+	/// `len` elements of type `id`, as hex if they are bytes.
+	fn elements(&self, id: u32, len: u32, input: &mut &[u8]) -> Result<Value> {
+		if let TypeDef::Primitive(TypeDefPrimitive::U8) = self.resolve(id)?.type_def() {
+			let len = len as usize;
+			if input.len() < len {
+				return Err("not enough data to decode a byte string".into())
+			}
+			let (bytes, rest) = input.split_at(len);
+			*input = rest;
+			return Ok(json!(bytes::to_hex(bytes, false)))
+		}
+		(0..len).map(|_| self.decode(id, input)).collect()
+	}
+
+	/// The primitive type `id` wraps, e.g. `u32` for a struct with a single `u32` field.
+	fn primitive(&self, id: u32) -> Result<&TypeDefPrimitive> {
+		let ty = self.resolve(id)?;
+
+		match ty.type_def() {
+			TypeDef::Primitive(primitive) => Ok(primitive),
+			TypeDef::Composite(composite) if composite.fields().len() == 1 =>
+				self.primitive(composite.fields()[0].ty().id()),
+			_ => Err(format!("`{}` can't be compact encoded", path(ty)).into()),
+		}
+	}
+
+	fn resolve(&self, id: u32) -> Result<&Type<PortableForm>> {
+		self.types.resolve(id).ok_or_else(|| format!("unknown type {}", id).into())
+	}
+}
+
+/// The path of `ty` as written in Rust, e.g. `sp_runtime::multiaddress::MultiAddress`.
+fn path(ty: &Type<PortableForm>) -> String {
+	ty.path().segments().join("::")
+}
+
+fn read<T: Decode>(input: &mut &[u8]) -> Result<T> {
+	T::decode(input).map_err(|e| format!("invalid encoding: {}", e).into())
+}
+
+/// Decode a `0x`-prefixed SCALE hex string, rejecting trailing bytes.
+pub fn decode_hex<T: Decode>(hex: &str) -> Result<T> {
+	let encoded = bytes::from_hex(hex).map_err(|e| format!("invalid hex: {}", e))?;
+	let mut input = &encoded[..];
+	let decoded = T::decode(&mut input).map_err(|e| format!("invalid encoding: {}", e))?;
+
+	if !input.is_empty() {
+		return Err(format!("{} trailing bytes after the encoded value", input.len()).into())
+	}
+	Ok(decoded)
+}
+
+fn parse_call(call: &str, args: &[String]) -> Result<RuntimeCall> {
+	let account = |account: &str| {
+		AccountId::from_str(account).map_err(|e| format!("invalid account `{}`: {}", account, e))
+	};
+	let balance = |value: &str| {
+		value
+			.parse::<Balance>()
+			.map_err(|e| format!("invalid amount `{}`: {}", value, e))
+	};
+
+	Ok(match (call, args) {
+		("balances.transfer", [dest, value]) =>
+			BalancesCall::transfer { dest: account(dest)?.into(), value: balance(value)? }.into(),
+		("balances.transfer_keep_alive", [dest, value]) => BalancesCall::transfer_keep_alive {
+			dest: account(dest)?.into(),
+			value: balance(value)?,
+		}
+		.into(),
+		("balances.transfer_all", [dest, keep_alive]) => BalancesCall::transfer_all {
+			dest: account(dest)?.into(),
+			keep_alive: keep_alive
+				.parse()
+				.map_err(|_| format!("expected `true` or `false`, got `{}`", keep_alive))?,
+		}
+		.into(),
+		("system.remark", [remark]) => SystemCall::remark {
+			remark: bytes::from_hex(remark).map_err(|e| format!("invalid remark: {}", e))?,
+		}
+		.into(),
+		(hex, []) if hex.starts_with("0x") => decode_hex(hex)?,
+		_ =>
+			return Err(format!(
+				"unknown call `{}` with {} arguments, see `tx sign --help`",
+				call,
+				args.len()
+			)
+			.into()),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use node_template_runtime::SudoCall;
+
+	fn sign_cmd(call: &str, args: &[&str], era: Mortality, era_hash: Option<Hash>) -> SignCmd {
+		SignCmd {
+			call: call.into(),
+			args: args.iter().map(|arg| arg.to_string()).collect(),
+			suri: Some("//Alice".into()),
+			nonce: 7,
+			era,
+			era_hash,
+			tip: 3,
+			genesis_hash: Hash::repeat_byte(1),
+			spec_version: runtime::VERSION.spec_version,
+			transaction_version: runtime::VERSION.transaction_version,
+		}
+	}
+
+	fn decode_cmd(xt: &runtime::UncheckedExtrinsic, genesis_hash: Option<Hash>) -> DecodeCmd {
+		DecodeCmd {
+			extrinsic: bytes::to_hex(&xt.encode(), false),
+			genesis_hash,
+			era_hash: Some(Hash::repeat_byte(2)),
+			spec_version: runtime::VERSION.spec_version,
+			transaction_version: runtime::VERSION.transaction_version,
+		}
+	}
+
+	fn dest() -> AccountId {
+		AccountId::from([9u8; 32])
+	}
+
+	#[test]
+	fn signed_extrinsics_decode_to_their_signer_and_call() {
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let dest = dest().to_string();
+		let xt = sign_cmd(
+			"balances.transfer",
+			&[&dest, "1000"],
+			Mortality::Mortal { period: 64, current: 100 },
+			Some(Hash::repeat_byte(2)),
+		)
+		.sign()
+		.unwrap();
+
+		let decoded = decode_cmd(&xt, Some(Hash::repeat_byte(1))).decode().unwrap();
+		assert_eq!(decoded["signed"], json!(true));
+		assert_eq!(decoded["signer"], json!(AccountId20::from(alice.public()).to_string()));
+		assert_eq!(
+			decoded["account"],
+			json!(MultiSigner::from(alice.public()).into_account().to_string())
+		);
+		assert_eq!(decoded["public"], json!(bytes::to_hex(alice.public().as_ref(), false)));
+		assert_eq!(decoded["signature_valid"], json!(true));
+		assert_eq!(decoded["era"], json!({ "period": 64, "phase": 36 }));
+		assert_eq!(decoded["nonce"], json!(7));
+		assert_eq!(decoded["tip"], json!("3"));
+		assert_eq!(decoded["sponsor"], Value::Null);
+		assert_eq!(
+			decoded["call"],
+			json!({
+				"pallet": "Balances",
+				"name": "transfer",
+				"args": { "dest": { "Id": dest }, "value": "1000" },
+			})
+		);
+
+		// The signature doesn't check out for another chain.
+		let decoded = decode_cmd(&xt, Some(Hash::repeat_byte(3))).decode().unwrap();
+		assert_eq!(decoded["signature_valid"], json!(false));
+		// Nor can the signer's address be recovered without the chain.
+		assert!(decode_cmd(&xt, None).decode().is_err());
+	}
+
+	#[test]
+	fn decodes_nested_calls_along_the_metadata() {
+		let transfer = BalancesCall::transfer { dest: dest().into(), value: 1 << 100 };
+		let call = RuntimeCall::Sudo(SudoCall::sudo { call: Box::new(transfer.into()) });
+
+		assert_eq!(
+			call_args(&call).unwrap(),
+			json!({
+				"call": {
+					"Balances": {
+						"transfer": {
+							"dest": { "Id": dest().to_string() },
+							// Compact 128-bit integers become strings.
+							"value": (1u128 << 100).to_string(),
+						},
+					},
+				},
+			})
+		);
+		assert_eq!(
+			call_args(&SystemCall::remark { remark: vec![1, 2] }.into()).unwrap(),
+			json!({ "remark": "0x0102" })
+		);
+		assert_eq!(
+			call_args(&SystemCall::set_heap_pages { pages: 64 }.into()).unwrap(),
+			json!({ "pages": 64 })
+		);
+	}
+
+	#[test]
+	fn rejects_malformed_calls() {
+		let dest = dest().to_string();
+		let remark: RuntimeCall = SystemCall::remark { remark: vec![1] }.into();
+		let hex = bytes::to_hex(&remark.encode(), false);
+		assert_eq!(parse_call(&hex, &[]).unwrap(), remark);
+		assert_eq!(parse_call("system.remark", &["0x01".into()]).unwrap(), remark);
+
+		for (call, args) in [
+			("balances.burn", vec![dest.clone(), "1".into()]),
+			("balances.transfer", vec![dest.clone()]),
+			("balances.transfer", vec!["alice".into(), "1".into()]),
+			("balances.transfer", vec![dest.clone(), "-1".into()]),
+			("balances.transfer_all", vec![dest.clone(), "maybe".into()]),
+			("system.remark", vec!["0xzz".into()]),
+			// Trailing bytes after the call.
+			(&*format!("{}00", hex), vec![]),
+			(&*hex, vec!["0x01".into()]),
+		] {
+			assert!(parse_call(call, &args).is_err(), "{} {:?}", call, args);
+		}
+	}
+
+	#[test]
+	fn parses_mortality() {
+		assert_eq!("immortal".parse(), Ok(Mortality::Immortal));
+		assert_eq!("64@100".parse(), Ok(Mortality::Mortal { period: 64, current: 100 }));
+
+		for invalid in ["", "Immortal", "64", "64@", "@100", "x@100", "64@-1", "64@100@1"] {
+			assert!(invalid.parse::<Mortality>().is_err(), "{}", invalid);
+		}
+	}
+
+	#[test]
+	fn mortal_eras_need_their_block_hash() {
+		let cmd = sign_cmd(
+			"system.remark",
+			&["0x01"],
+			Mortality::Mortal { period: 64, current: 100 },
+			None,
+		);
+		assert!(cmd.sign().is_err());
+	}
+}
@@ -0,0 +1,186 @@
+//! In-process node harness for integration tests.
+//!
+//! [`TestNode`] boots the full service on a temporary directory with the development chain spec
+//! and manual sealing. Tests author blocks on demand and talk to the node through its real RPC
+//! module, without any network.
+
+#![allow(dead_code)]
+
+use node_template::{
+	cli::Cli,
+	service::{self, FullClient, NewFullBase},
+	tx::{sign_extrinsic, SigningParams},
+};
+use node_template_runtime::{self as runtime, AccountId, Balance, Hash, Index, RuntimeCall};
+use sc_cli::SubstrateCli;
+use sc_service::{RpcHandlers, TaskManager};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sp_core::{ecdsa, Bytes, Decode, Encode, Pair};
+use sp_runtime::{generic::Era, traits::IdentifyAccount, MultiSigner};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Ethereum-style (secp256k1) development key derived from `seed`, e.g. `"Alice"`.
+pub fn ethereum_pair(seed: &str) -> ecdsa::Pair {
+	ecdsa::Pair::from_string(&format!("//{}", seed), None).expect("static values are valid; qed")
+}
+
+/// Account controlled by `pair`.
+pub fn account_id(pair: &ecdsa::Pair) -> AccountId {
+	MultiSigner::from(pair.public()).into_account()
+}
+
+/// A development node running in-process.
+pub struct TestNode {
+	/// The client of the node, for direct access to chain data.
+	pub client: Arc<FullClient>,
+	/// Port of the node's WebSocket RPC server on localhost.
+	pub ws_port: u16,
+	rpc_handlers: RpcHandlers,
+	task_manager: TaskManager,
+	_base_path: TempDir,
+}
+
+impl TestNode {
+	/// Boot a development node that only seals blocks when asked to.
+	///
+	/// Must be called from within a multi-threaded tokio runtime.
+	pub fn start() -> Self {
+		Self::start_with(&[])
+	}
+
+	/// Boot a development node like [`TestNode::start`], with extra command line `args`.
+	pub fn start_with(args: &[&str]) -> Self {
+		let base_path = tempfile::tempdir().expect("temporary directory can be created");
+		// Reserve a free port for the WebSocket server, whose address isn't reported back.
+		let ws_port = std::net::TcpListener::bind("127.0.0.1:0")
+			.and_then(|listener| listener.local_addr())
+			.expect("a local port is available")
+			.port();
+		let ws_port_arg = ws_port.to_string();
+		let cli = Cli::from_iter(
+			[
+				"node-template",
+				"--dev",
+				"--sealing",
+				"manual",
+				"--base-path",
+				base_path.path().to_str().expect("temporary directory path is UTF-8"),
+				"--port",
+				"0",
+				"--rpc-port",
+				"0",
+				"--ws-port",
+				&ws_port_arg,
+				"--no-prometheus",
+				"--no-telemetry",
+			]
+			.into_iter()
+			.chain(args.iter().copied()),
+		);
+		let config = cli
+			.create_configuration(&cli.run, tokio::runtime::Handle::current())
+			.expect("development configuration is valid");
+		let NewFullBase { task_manager, client, rpc_handlers, .. } =
+			service::new_full_base(config, cli.sealing, cli.siwe_config())
+				.expect("development node starts");
+
+		Self { client, ws_port, rpc_handlers, task_manager, _base_path: base_path }
+	}
+
+	/// URL of the node's WebSocket RPC server.
+	pub fn ws_url(&self) -> String {
+		format!("ws://127.0.0.1:{}", self.ws_port)
+	}
+
+	/// Call `method` with `params` through the node's RPC module, panicking on errors.
+	pub async fn rpc<T: DeserializeOwned>(&self, method: &str, params: Value) -> T {
+		self.try_rpc(method, params)
+			.await
+			.unwrap_or_else(|e| panic!("`{}` failed: {}", method, e))
+	}
+
+	/// Call `method` with `params` through the node's RPC module, returning the error object if
+	/// it fails.
+	pub async fn try_rpc<T: DeserializeOwned>(
+		&self,
+		method: &str,
+		params: Value,
+	) -> Result<T, Value> {
+		let request = json!({ "jsonrpc": "2.0", "id": 0, "method": method, "params": params });
+		let (response, _) = self
+			.rpc_handlers
+			.rpc_query(&request.to_string())
+			.await
+			.unwrap_or_else(|e| panic!("`{}` could not be sent: {}", method, e));
+		let mut response: Value = serde_json::from_str(&response).expect("response is JSON");
+
+		match response.get_mut("result") {
+			Some(result) => Ok(serde_json::from_value(result.take())
+				.unwrap_or_else(|e| panic!("unexpected `{}` result: {}", method, e))),
+			None => Err(response["error"].take()),
+		}
+	}
+
+	/// Author and finalize a block with whatever is in the transaction pool.
+	pub async fn seal_block(&self) -> Hash {
+		let created: Value = self.rpc("engine_createBlock", json!([true, true, null])).await;
+		serde_json::from_value(created["hash"].clone()).expect("created block has a hash")
+	}
+
+	/// Hash of the latest finalized block.
+	pub async fn finalized_head(&self) -> Hash {
+		self.rpc("chain_getFinalizedHead", json!([])).await
+	}
+
+	/// Next transaction index of `account`, including transactions in the pool.
+	pub async fn nonce(&self, account: &AccountId) -> Index {
+		self.rpc("system_accountNextIndex", json!([account.to_string()])).await
+	}
+
+	/// System account information of `account` at the best block.
+	pub async fn account(
+		&self,
+		account: &AccountId,
+	) -> frame_system::AccountInfo<Index, <runtime::Runtime as frame_system::Config>::AccountData>
+	{
+		let key = frame_system::Account::<runtime::Runtime>::hashed_key_for(account);
+		let info: Option<Bytes> = self.rpc("state_getStorage", json!([Bytes(key)])).await;
+
+		info.map(|info| Decode::decode(&mut &info[..]).expect("account info decodes"))
+			.unwrap_or_default()
+	}
+
+	/// Free balance of `account` at the best block.
+	pub async fn free_balance(&self, account: &AccountId) -> Balance {
+		self.account(account).await.data.free
+	}
+
+	/// Sign `call` with `signer` using its next nonce and an immortal era.
+	pub async fn sign(
+		&self,
+		signer: &ecdsa::Pair,
+		call: RuntimeCall,
+	) -> runtime::UncheckedExtrinsic {
+		let nonce = self.nonce(&account_id(signer)).await;
+		let genesis_hash: Hash = self.rpc("chain_getBlockHash", json!([0])).await;
+		let params = SigningParams {
+			nonce,
+			era: Era::Immortal,
+			tip: 0,
+			genesis_hash,
+			birth_hash: genesis_hash,
+			spec_version: runtime::VERSION.spec_version,
+			transaction_version: runtime::VERSION.transaction_version,
+			sponsor: None,
+		};
+
+		sign_extrinsic(signer, call, &params)
+	}
+
+	/// Submit `xt` to the transaction pool, returning its hash.
+	pub async fn submit(&self, xt: runtime::UncheckedExtrinsic) -> Hash {
+		self.rpc("author_submitExtrinsic", json!([Bytes(xt.encode())])).await
+	}
+}
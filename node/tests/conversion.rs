@@ -0,0 +1,92 @@
+mod common;
+
+use account::AccountId20;
+use common::{account_id, ethereum_pair, TestNode};
+use node_template::rpc::conversion::Conversion;
+use node_template_runtime::{AccountId, BalancesCall, PalletId, RuntimeCall, SystemCall};
+use serde_json::{json, Value};
+use sp_core::Pair;
+use sp_runtime::traits::AccountIdConversion;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn converts_addresses_keys_and_accounts() {
+	let node = TestNode::start();
+	let convert = |input: String| node.rpc::<Conversion>("account_convert", json!([input, null]));
+	let alice = ethereum_pair("Alice");
+	let address = AccountId20::from(alice.public());
+	let account = account_id(&alice);
+
+	assert_eq!(
+		convert(format!("0x{}", hex(&alice.public().0))).await,
+		Conversion { account: Some(account.clone()), address: Some(address) }
+	);
+
+	// Until Alice transacts, the chain links neither the address to the account nor back.
+	assert_eq!(
+		convert(address.to_string()).await,
+		Conversion { account: None, address: Some(address) }
+	);
+	assert_eq!(
+		convert(account.to_string()).await,
+		Conversion { account: Some(account.clone()), address: None }
+	);
+
+	let transfer = RuntimeCall::Balances(BalancesCall::transfer {
+		dest: account_id(&ethereum_pair("Bob")).into(),
+		value: 1,
+	});
+	let xt = node.sign(&alice, transfer).await;
+	node.submit(xt).await;
+	node.seal_block().await;
+
+	let linked = Conversion { account: Some(account.clone()), address: Some(address) };
+	assert_eq!(convert(address.to_string()).await, linked);
+	assert_eq!(convert(account.to_string()).await, linked);
+	// Lowercase addresses and hex accounts are accepted too.
+	assert_eq!(convert(address.to_string().to_lowercase()).await, linked);
+	assert_eq!(convert(format!("0x{}", hex(AsRef::<[u8]>::as_ref(&account)))).await, linked);
+
+	// x = 5 has no point on the curve.
+	let not_a_key = format!("0x02{}05", "00".repeat(31));
+	for invalid in ["0x1234", "0x02", not_a_key.as_str()] {
+		let error = node
+			.try_rpc::<Value>("account_convert", json!([invalid, null]))
+			.await
+			.unwrap_err();
+		assert_eq!(error["code"], -32602, "{}", invalid);
+	}
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conversions_round_trip() {
+	let node = TestNode::start();
+	let convert = |input: String| node.rpc::<Conversion>("account_convert", json!([input, null]));
+	let alice = ethereum_pair("Alice");
+	let xt = node
+		.sign(&alice, RuntimeCall::System(SystemCall::remark { remark: vec![] }))
+		.await;
+	node.submit(xt).await;
+	node.seal_block().await;
+
+	// Alice's account is linked, Bob's isn't, and the treasury's has no key at all.
+	let treasury: AccountId = PalletId(*b"py/trsry").into_account_truncating();
+	for (account, linked) in
+		[(account_id(&alice), true), (account_id(&ethereum_pair("Bob")), false), (treasury, false)]
+	{
+		let conversion = convert(account.to_string()).await;
+		assert_eq!(conversion.account.as_ref(), Some(&account));
+		assert_eq!(conversion.address.is_some(), linked);
+		// The address converts back to the same account.
+		if let Some(address) = conversion.address {
+			assert_eq!(convert(address.to_string()).await, conversion);
+		}
+	}
+
+	let address = AccountId20::from(ethereum_pair("Bob").public());
+	let conversion = convert(address.to_string()).await;
+	assert_eq!(conversion, Conversion { account: None, address: Some(address) });
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::TestNode;
+use node_template::rpc::eth::{BlockNumber, CallRequest, GasEstimate};
+use node_template_runtime::{RuntimeCall, SystemCall, WEIGHT_PER_GAS};
+use serde_json::json;
+use sp_core::{Bytes, Encode, H160, U256};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn gas_price_starts_at_weight_fee() {
+	let node = TestNode::start();
+
+	// Empty blocks keep the fee multiplier at its minimum of one.
+	node.seal_block().await;
+	let price: U256 = node.rpc("eth_gasPrice", json!([])).await;
+
+	assert_eq!(price, U256::from(WEIGHT_PER_GAS));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn accepts_block_tags_and_numbers() {
+	let node = TestNode::start();
+	node.seal_block().await;
+	let request = CallRequest { to: Some(H160::repeat_byte(1)), ..Default::default() };
+
+	let latest: U256 = node.rpc("eth_estimateGas", json!([request, "latest"])).await;
+	for block in [BlockNumber::Pending, BlockNumber::Earliest, BlockNumber::Number(1)] {
+		let price: U256 = node.rpc("eth_gasPrice", json!([block])).await;
+		assert_eq!(price, U256::from(WEIGHT_PER_GAS));
+		let gas: U256 = node.rpc("eth_estimateGas", json!([request, block])).await;
+		assert_eq!(gas, latest);
+	}
+	assert_eq!(serde_json::to_value(BlockNumber::Number(1)).unwrap(), json!("0x1"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn estimates_transfers_and_native_calls() {
+	let node = TestNode::start();
+	let estimate = |request: CallRequest| node.rpc::<GasEstimate>("gas_estimate", json!([request]));
+
+	let request = CallRequest {
+		to: Some(H160::repeat_byte(1)),
+		value: Some(U256::exp10(18)),
+		data: Some(Bytes(vec![])),
+	};
+	let transfer = estimate(request.clone()).await;
+	assert!(transfer.gas > U256::zero());
+	assert!(transfer.fee > U256::zero());
+	// `eth_estimateGas` returns the gas alone.
+	let gas: U256 = node.rpc("eth_estimateGas", json!([request])).await;
+	assert_eq!(gas, transfer.gas);
+
+	let remark = |len: usize| CallRequest {
+		data: Some(
+			RuntimeCall::System(SystemCall::remark { remark: vec![0; len] }).encode().into(),
+		),
+		..Default::default()
+	};
+	let short = estimate(remark(10)).await;
+	let long = estimate(remark(1_000)).await;
+	// Longer remarks pay a higher length fee.
+	assert!(short.fee < long.fee);
+}
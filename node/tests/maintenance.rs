@@ -0,0 +1,53 @@
+mod common;
+
+use common::{account_id, ethereum_pair, TestNode};
+use node_template::rpc::maintenance::{MaintenanceStatus, PausedCall};
+use node_template_runtime::{BalancesCall, MaintenanceCall, RuntimeCall, SudoCall};
+use serde_json::json;
+
+/// Have the sudo key dispatch `call`.
+async fn sudo(node: &TestNode, call: MaintenanceCall<node_template_runtime::Runtime>) {
+	let call = RuntimeCall::Sudo(SudoCall::sudo { call: Box::new(call.into()) });
+	let xt = node.sign(&ethereum_pair("Alice"), call).await;
+	node.submit(xt).await;
+	node.seal_block().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_and_enforces_paused_calls() {
+	let node = TestNode::start();
+	let status = || node.rpc::<MaintenanceStatus>("maintenance_status", json!([]));
+	assert_eq!(status().await, MaintenanceStatus { maintenance_mode: false, paused: vec![] });
+
+	sudo(
+		&node,
+		MaintenanceCall::pause_call {
+			pallet: b"Balances".to_vec().try_into().unwrap(),
+			call: b"transfer".to_vec().try_into().unwrap(),
+		},
+	)
+	.await;
+	assert_eq!(
+		status().await,
+		MaintenanceStatus {
+			maintenance_mode: false,
+			paused: vec![PausedCall { pallet: "Balances".into(), call: Some("transfer".into()) }],
+		}
+	);
+
+	// The paused transfer is included, but fails to dispatch.
+	let bob = account_id(&ethereum_pair("Bob"));
+	let bob_balance = node.free_balance(&bob).await;
+	let transfer = node
+		.sign(
+			&ethereum_pair("Alice"),
+			RuntimeCall::Balances(BalancesCall::transfer { dest: bob.clone().into(), value: 1 }),
+		)
+		.await;
+	node.submit(transfer).await;
+	node.seal_block().await;
+	assert_eq!(node.free_balance(&bob).await, bob_balance);
+
+	sudo(&node, MaintenanceCall::enter_maintenance {}).await;
+	assert!(status().await.maintenance_mode);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use account::{eip191_hash, AccountId20, EthereumPair};
+use common::TestNode;
+use node_template::rpc::personal::Verification;
+use serde_json::{json, Value};
+use sp_core::Bytes;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recovers_and_verifies_personal_sign_signatures() {
+	let node = TestNode::start();
+	let alice = EthereumPair::from_string("//Alice", None).unwrap();
+	let message = Bytes(b"Sign in to example.com".to_vec());
+	let mut signature = alice.sign_prehashed(&eip191_hash(&message)).0;
+	// Wallets return Ethereum-style recovery ids.
+	signature[64] += 27;
+	let signature = Bytes(signature.to_vec());
+
+	let signer: String = node.rpc("personal_ecRecover", json!([message, signature])).await;
+	assert_eq!(signer, alice.address().to_string());
+
+	let verify = |address: AccountId20, message: &[u8], signature: &[u8]| {
+		node.rpc::<Verification>(
+			"account_verifyMessage",
+			json!([address, Bytes(message.to_vec()), Bytes(signature.to_vec())]),
+		)
+	};
+	assert_eq!(
+		verify(alice.address(), &message, &signature).await,
+		Verification { valid: true, reason: None }
+	);
+
+	let bob = EthereumPair::from_string("//Bob", None).unwrap().address();
+	let wrong_signer = verify(bob, &message, &signature).await;
+	assert!(!wrong_signer.valid);
+	assert_eq!(wrong_signer.reason, Some(format!("the signature recovers to {}", alice.address())));
+	let truncated = verify(alice.address(), &message, &signature[..64]).await;
+	assert_eq!(truncated.reason, Some("expected a 65-byte signature, got 64 bytes".into()));
+
+	let error = node
+		.try_rpc::<Value>("personal_ecRecover", json!([message, Bytes(vec![0; 65])]))
+		.await
+		.unwrap_err();
+	assert_eq!(error["code"], -32602);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::TestNode;
+use futures::StreamExt;
+use node_template_runtime::{BalancesCall, Hash, RuntimeCall, EXISTENTIAL_DEPOSIT};
+use node_template_sdk::{account_id, Client, EthereumPair, TransactionStatus};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sdk_signed_transfer_is_included() {
+	let node = TestNode::start();
+	let client = Client::ws(&node.ws_url()).await.unwrap();
+	let alice = EthereumPair::from_string("//Alice", None).unwrap();
+	let bob = account_id(&EthereumPair::from_string("//Bob", None).unwrap());
+	let bob_balance = node.free_balance(&bob).await;
+	let value = 10 * EXISTENTIAL_DEPOSIT;
+
+	let call = RuntimeCall::Balances(BalancesCall::transfer { dest: bob.clone().into(), value });
+	let xt = client.sign(&alice, call).await.unwrap();
+	let mut watch = client.submit_and_watch(&xt).await.unwrap();
+	assert_eq!(watch.next().await.unwrap().unwrap(), TransactionStatus::Ready);
+
+	let block = node.seal_block().await;
+
+	assert_eq!(watch.next().await.unwrap().unwrap(), TransactionStatus::InBlock(block));
+	assert_eq!(client.nonce(&account_id(&alice)).await.unwrap(), 1);
+	assert_eq!(node.free_balance(&bob).await, bob_balance + value);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sdk_reads_chain_parameters() {
+	let node = TestNode::start();
+	let client = Client::ws(&node.ws_url()).await.unwrap();
+
+	let version = client.runtime_version().await.unwrap();
+	assert_eq!(version.spec_version, node_template_runtime::VERSION.spec_version);
+	let genesis_hash: Hash = node.rpc("chain_getBlockHash", serde_json::json!([0])).await;
+	assert_eq!(client.genesis_hash().await.unwrap(), genesis_hash);
+}
@@ -0,0 +1,241 @@
+mod common;
+
+use account::{eip191_hash, EthereumPair};
+use common::TestNode;
+use node_template::rpc::siwe::{Message, Session};
+use node_template_runtime::CHAIN_ID;
+use serde_json::{json, Value};
+use sp_core::Bytes;
+use std::{
+	str::FromStr,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+fn operator() -> EthereumPair {
+	EthereumPair::from_string("//Alice", None).unwrap()
+}
+
+/// A node Alice may sign in to as an operator.
+fn start() -> TestNode {
+	TestNode::start_with(&["--rpc-operator", &operator().address().to_string()])
+}
+
+/// A sign-in message of `signer` for `domain` issued now, ending with `extra` lines.
+fn message(signer: &EthereumPair, domain: &str, nonce: &str, extra: &str) -> String {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+	format!(
+		"{} wants you to sign in with your Ethereum account:\n{}\n\nManage the node.\n\n\
+		 URI: https://{}\nVersion: 1\nChain ID: {}\nNonce: {}\nIssued At: {}{}",
+		domain,
+		signer.address(),
+		domain,
+		CHAIN_ID,
+		nonce,
+		rfc3339(now),
+		extra
+	)
+}
+
+/// The RFC 3339 timestamp of `seconds` since the Unix epoch, after Howard Hinnant's
+/// `civil_from_days`.
+fn rfc3339(seconds: u64) -> String {
+	let days = (seconds / 86_400) as i64 + 719_468;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days - era * 146_097;
+	let year_of_era =
+		(day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let march_month = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * march_month + 2) / 5 + 1;
+	let month = if march_month < 10 { march_month + 3 } else { march_month - 9 };
+	let year = era * 400 + year_of_era + i64::from(month <= 2);
+	let time = seconds % 86_400;
+	format!(
+		"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+		year,
+		month,
+		day,
+		time / 3600,
+		time / 60 % 60,
+		time % 60
+	)
+}
+
+fn sign(signer: &EthereumPair, message: &str) -> Bytes {
+	Bytes(signer.sign_prehashed(&eip191_hash(message.as_bytes())).0.to_vec())
+}
+
+async fn sign_in(node: &TestNode, signer: &EthereumPair, message: &str) -> Result<Session, Value> {
+	node.try_rpc("siwe_signIn", json!([message, sign(signer, message)])).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn operators_call_unsafe_methods_in_their_session() {
+	let node = start();
+	let nonce: String = node.rpc("siwe_nonce", json!([])).await;
+	let session = sign_in(&node, &operator(), &message(&operator(), "localhost", &nonce, ""))
+		.await
+		.unwrap();
+	assert_eq!(session.address, operator().address());
+
+	let keys: Bytes =
+		node.rpc("siwe_call", json!([session.token, "author_rotateKeys", null])).await;
+	assert!(!keys.is_empty());
+	let has_keys: bool = node
+		.rpc("siwe_call", json!([session.token, "author_hasSessionKeys", [keys]]))
+		.await;
+	assert!(has_keys);
+
+	// Only the methods `siwe_call` serves are available.
+	let error = node
+		.try_rpc::<Value>("siwe_call", json!([session.token, "eth_chainId", []]))
+		.await
+		.unwrap_err();
+	assert_eq!(error["code"], -32601);
+	// Nor are subscriptions.
+	let error = node
+		.try_rpc::<Value>(
+			"siwe_call",
+			json!([session.token, "author_submitAndWatchExtrinsic", ["0x00"]]),
+		)
+		.await
+		.unwrap_err();
+	assert_eq!(error["code"], -32601);
+	assert!(error["message"].as_str().unwrap().contains("subscription"), "{}", error);
+	// Errors of the methods are passed on.
+	let error = node
+		.try_rpc::<Value>("siwe_call", json!([session.token, "author_hasSessionKeys", ["0x00"]]))
+		.await
+		.unwrap_err();
+	assert_ne!(error["code"], 3);
+
+	assert!(node.rpc::<bool>("siwe_signOut", json!([session.token])).await);
+	let error = node
+		.try_rpc::<Value>("siwe_call", json!([session.token, "author_rotateKeys", null]))
+		.await
+		.unwrap_err();
+	assert_eq!(error["code"], 3);
+	assert!(!node.rpc::<bool>("siwe_signOut", json!([session.token])).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sign_ins_require_an_operator_signature_of_a_fresh_message() {
+	let node = start();
+	let nonce = || node.rpc::<String>("siwe_nonce", json!([]));
+	let rejected = |error: Value| assert_eq!(error["code"], 2, "{}", error);
+
+	// Bob isn't an operator.
+	let bob = EthereumPair::from_string("//Bob", None).unwrap();
+	rejected(
+		sign_in(&node, &bob, &message(&bob, "localhost", &nonce().await, ""))
+			.await
+			.unwrap_err(),
+	);
+	// Nor does Bob's signature of Alice's message count.
+	let alices = message(&operator(), "localhost", &nonce().await, "");
+	rejected(
+		node.try_rpc::<Session>("siwe_signIn", json!([alices, sign(&bob, &alices)]))
+			.await
+			.unwrap_err(),
+	);
+
+	let valid = message(&operator(), "localhost", "NONCE", "");
+	let issued_at = valid.lines().find_map(|line| line.strip_prefix("Issued At: ")).unwrap();
+	let in_an_hour = rfc3339(Message::from_str(&valid).unwrap().issued_at + 3600);
+	for invalid in [
+		// Messages for other domains, or with URIs on other domains,
+		message(&operator(), "example.com", "NONCE", ""),
+		valid.replace("URI: https://localhost", "URI: https://example.com"),
+		// for other chains,
+		valid.replace(&format!("Chain ID: {}", CHAIN_ID), "Chain ID: 1"),
+		// expired ones and ones not yet valid,
+		message(&operator(), "localhost", "NONCE", "\nExpiration Time: 2024-01-01T00:05:00Z"),
+		message(&operator(), "localhost", "NONCE", "\nNot Before: 2999-01-01T00:00:00.000+02:00"),
+		// and ones issued in the future or before their nonce are rejected.
+		valid.replace(issued_at, &in_an_hour),
+		valid.replace(issued_at, "2024-01-01T00:00:00Z"),
+	] {
+		let message = invalid.replace("NONCE", &nonce().await);
+		rejected(sign_in(&node, &operator(), &message).await.unwrap_err());
+	}
+
+	// Nonces are issued by the node, however many others asked for, and used once.
+	rejected(
+		sign_in(&node, &operator(), &message(&operator(), "localhost", "0123456789", ""))
+			.await
+			.unwrap_err(),
+	);
+	let mut forged = nonce().await.into_bytes();
+	forged[0] = if forged[0] == b'f' { b'e' } else { b'f' };
+	let forged = String::from_utf8(forged).unwrap();
+	rejected(
+		sign_in(&node, &operator(), &message(&operator(), "localhost", &forged, ""))
+			.await
+			.unwrap_err(),
+	);
+	for _ in 0..2048 {
+		nonce().await;
+	}
+	let message = message(&operator(), "localhost", &nonce().await, "");
+	sign_in(&node, &operator(), &message).await.unwrap();
+	rejected(sign_in(&node, &operator(), &message).await.unwrap_err());
+}
+
+#[test]
+fn parses_erc_4361_messages() {
+	let address = operator().address();
+	let text = format!(
+		"service.org wants you to sign in with your Ethereum account:\n{}\n\n\
+		 I accept the ServiceOrg Terms of Service: https://service.org/tos\n\n\
+		 URI: https://service.org/login\nVersion: 1\nChain ID: 1\nNonce: 32891756\n\
+		 Issued At: 2021-09-30T16:25:24Z\nExpiration Time: 2021-09-30T18:25:24.000+02:00\n\
+		 Request ID: 7\nResources:\n- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/\n\
+		 - https://example.com/my-web2-claim.json",
+		address
+	);
+	assert_eq!(
+		Message::from_str(&text),
+		Ok(Message {
+			domain: "service.org".into(),
+			address,
+			statement: Some(
+				"I accept the ServiceOrg Terms of Service: https://service.org/tos".into()
+			),
+			uri: "https://service.org/login".into(),
+			chain_id: 1,
+			nonce: "32891756".into(),
+			issued_at: 1_633_019_124,
+			// The same instant, in another time zone.
+			expiration_time: Some(1_633_019_124),
+			not_before: None,
+			request_id: Some("7".into()),
+			resources: vec![
+				"ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/".into(),
+				"https://example.com/my-web2-claim.json".into(),
+			],
+		})
+	);
+
+	// Without a statement.
+	let minimal = format!(
+		"localhost:9944 wants you to sign in with your Ethereum account:\n{}\n\n\n\
+		 URI: http://localhost:9944\nVersion: 1\nChain ID: 42\nNonce: abcdefgh\n\
+		 Issued At: 2000-02-29T00:00:00Z",
+		address
+	);
+	let parsed = Message::from_str(&minimal).unwrap();
+	assert_eq!((parsed.statement, parsed.issued_at), (None, 951_782_400));
+
+	for invalid in [
+		// The address lacks its checksum.
+		minimal.replace(&address.to_string(), &address.to_string().to_lowercase()),
+		minimal.replace("Version: 1", "Version: 2"),
+		minimal.replace("abcdefgh", "abc"),
+		minimal.replace("2000-02-29T00:00:00Z", "2000-02-29 00:00:00"),
+		minimal.replace("2000-02-29T00:00:00Z", "2000-13-01T00:00:00Z"),
+		minimal.replace("\n\n\n", "\n\n"),
+		format!("{}\n", minimal),
+	] {
+		assert!(Message::from_str(&invalid).is_err(), "{}", invalid);
+	}
+}
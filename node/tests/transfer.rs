@@ -0,0 +1,36 @@
+mod common;
+
+use common::{account_id, ethereum_pair, TestNode};
+use node_template_runtime::{BalancesCall, RuntimeCall, EXISTENTIAL_DEPOSIT};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ethereum_keyed_transfer_is_included() {
+	let node = TestNode::start();
+	let (alice, bob) = (account_id(&ethereum_pair("Alice")), account_id(&ethereum_pair("Bob")));
+	let alice_balance = node.free_balance(&alice).await;
+	let bob_balance = node.free_balance(&bob).await;
+	let value = 10 * EXISTENTIAL_DEPOSIT;
+
+	let transfer = node
+		.sign(
+			&ethereum_pair("Alice"),
+			RuntimeCall::Balances(BalancesCall::transfer { dest: bob.clone().into(), value }),
+		)
+		.await;
+	node.submit(transfer).await;
+	node.seal_block().await;
+
+	assert_eq!(node.nonce(&alice).await, 1);
+	assert_eq!(node.free_balance(&bob).await, bob_balance + value);
+	// Alice paid the transfer plus fees.
+	assert!(node.free_balance(&alice).await < alice_balance - value);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sealed_blocks_are_finalized() {
+	let node = TestNode::start();
+
+	let hash = node.seal_block().await;
+
+	assert_eq!(node.finalized_head().await, hash);
+}
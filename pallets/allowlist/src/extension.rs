@@ -0,0 +1,93 @@
+//! The signed extension rejecting transactions of signers that are not allowlisted.
+
+use crate::{Config, Pallet, NOT_ALLOWLISTED};
+use account::AccountId20;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::ecdsa;
+use sp_runtime::{
+	traits::{Convert, DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::marker::PhantomData;
+
+/// Only lets allowlisted signers submit transactions, and links the address and account of each
+/// signer whose transaction is dispatched.
+///
+/// Linking is weighed on top of the transaction's weight when it is dispatched.
+///
+/// Holds the public key of the signer, if it signs with a secp256k1 key. Signers with other keys
+/// have no Ethereum address and are always rejected.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckAllowlist<T: Config + Send + Sync>(Option<ecdsa::Public>, PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckAllowlist<T> {
+	/// Check the signer with the public key `signer`.
+	pub fn new(signer: Option<ecdsa::Public>) -> Self {
+		Self(signer, PhantomData)
+	}
+
+	/// The public key of the signer.
+	pub fn signer(&self) -> Option<&ecdsa::Public> {
+		self.0.as_ref()
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckAllowlist<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "CheckAllowlist({:?})", self.0)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckAllowlist<T> {
+	const IDENTIFIER: &'static str = "CheckAllowlist";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		let public = self.0.clone().ok_or(InvalidTransaction::Custom(NOT_ALLOWLISTED))?;
+		// Only a key matching the account, which the signature was checked against, is a valid
+		// secp256k1 key that has an address.
+		if T::SignerToAccountId::convert(public.clone()) != *who {
+			return Err(InvalidTransaction::BadSigner.into())
+		}
+
+		Pallet::<T>::check(AccountId20::from(public), call)?;
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		self.validate(who, call, info, len)?;
+		if let Some(public) = self.0 {
+			let weight = Pallet::<T>::link(AccountId20::from(public), who);
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, info.class);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,185 @@
+//! # Allowlist Pallet
+//!
+//! Restricts a permissioned chain to approved Ethereum addresses. [`Config::AdminOrigin`] manages
+//! the [`Members`] allowlist, and the [`CheckAllowlist`] signed extension rejects transactions of
+//! everyone else when they are validated for the pool, with [`NOT_ALLOWLISTED`] as the custom
+//! `InvalidTransaction` code.
+//!
+//! Accounts are hashes of public keys, so the signer's address can't be derived from its account.
+//! [`CheckAllowlist`] therefore carries the signer's public key, which it checks against the
+//! account before looking up the key's address.
+//!
+//! Members have a [`Role`]: only operators may submit [`Config::OperatorCalls`].
+//!
+//! Calls that reach the runtime without a signed transaction of their signer, such as relayed
+//! meta-transactions, bypass [`CheckAllowlist`]. Whatever dispatches them has to apply
+//! [`Pallet::check`] itself.
+//!
+//! Once a member's transaction is dispatched, its address and account are linked in [`Accounts`]
+//! and [`Addresses`], so that either can be looked up from the other. Whatever dispatches calls
+//! of members without a transaction has to [`Pallet::link`] them itself.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use extension::CheckAllowlist;
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+mod extension;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+
+/// `InvalidTransaction::Custom` code of a signer that is not on the allowlist.
+pub const NOT_ALLOWLISTED: u8 = 10;
+/// `InvalidTransaction::Custom` code of a call the signer's [`Role`] may not submit.
+pub const INSUFFICIENT_ROLE: u8 = 11;
+
+/// What an allowlisted address may do.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Role {
+	/// Submits any call but [`Config::OperatorCalls`].
+	User,
+	/// Submits any call.
+	Operator,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::{Role, INSUFFICIENT_ROLE, NOT_ALLOWLISTED};
+	use account::AccountId20;
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use frame_system::pallet_prelude::*;
+	use sp_core::ecdsa;
+	use sp_runtime::traits::Convert;
+	use sp_std::prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to change the allowlist.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The account controlled by a secp256k1 public key.
+		type SignerToAccountId: Convert<ecdsa::Public, Self::AccountId>;
+
+		/// Calls only [`Role::Operator`]s may submit.
+		type OperatorCalls: Contains<<Self as frame_system::Config>::RuntimeCall>;
+	}
+
+	/// The allowlisted addresses and their roles.
+	#[pallet::storage]
+	#[pallet::getter(fn member)]
+	pub type Members<T> = StorageMap<_, Blake2_128Concat, AccountId20, Role>;
+
+	/// The accounts of addresses whose keys signed a dispatched transaction.
+	#[pallet::storage]
+	#[pallet::getter(fn account)]
+	pub type Accounts<T: Config> = StorageMap<_, Blake2_128Concat, AccountId20, T::AccountId>;
+
+	/// The addresses of the keys of accounts that signed a dispatched transaction.
+	#[pallet::storage]
+	#[pallet::getter(fn address)]
+	pub type Addresses<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, AccountId20>;
+
+	#[pallet::genesis_config]
+	#[cfg_attr(feature = "std", derive(Default))]
+	pub struct GenesisConfig {
+		pub members: Vec<(AccountId20, Role)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			for (address, role) in &self.members {
+				Members::<T>::insert(address, role);
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `address` was allowlisted with `role`, or its role changed to `role`.
+		MemberAdded { address: AccountId20, role: Role },
+		/// `address` was removed from the allowlist.
+		MemberRemoved { address: AccountId20 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The address is not on the allowlist.
+		NotMember,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allowlist `address` with `role`, replacing its role if it is already a member.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn add_member(
+			origin: OriginFor<T>,
+			address: AccountId20,
+			role: Role,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Members::<T>::insert(address, role);
+
+			Self::deposit_event(Event::MemberAdded { address, role });
+			Ok(())
+		}
+
+		/// Remove `address` from the allowlist.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn remove_member(origin: OriginFor<T>, address: AccountId20) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Members::<T>::contains_key(address), Error::<T>::NotMember);
+
+			Members::<T>::remove(address);
+
+			Self::deposit_event(Event::MemberRemoved { address });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Check that `address` is allowlisted with a role that may submit `call`.
+		pub fn check(
+			address: AccountId20,
+			call: &<T as frame_system::Config>::RuntimeCall,
+		) -> Result<(), InvalidTransaction> {
+			let role =
+				Members::<T>::get(address).ok_or(InvalidTransaction::Custom(NOT_ALLOWLISTED))?;
+			if role != Role::Operator && T::OperatorCalls::contains(call) {
+				return Err(InvalidTransaction::Custom(INSUFFICIENT_ROLE))
+			}
+			Ok(())
+		}
+
+		/// Link `address` and `account`, the account of the address's key, unless they are already,
+		/// returning the weight of the storage accessed.
+		pub fn link(address: AccountId20, account: &T::AccountId) -> Weight {
+			if Addresses::<T>::contains_key(account) {
+				return T::DbWeight::get().reads(1)
+			}
+			Accounts::<T>::insert(address, account);
+			Addresses::<T>::insert(account, address);
+			T::DbWeight::get().reads_writes(1, 2)
+		}
+	}
+}
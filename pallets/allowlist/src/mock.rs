@@ -0,0 +1,92 @@
+use crate as pallet_allowlist;
+use frame_support::{
+	traits::{ConstU16, ConstU64, Contains, GenesisBuild},
+	weights::constants::RocksDbWeight,
+};
+use frame_system::{self as system, EnsureRoot};
+use sp_core::{ecdsa, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, IdentifyAccount, IdentityLookup},
+	AccountId32, MultiSigner,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Allowlist: pallet_allowlist,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = RocksDbWeight;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+pub struct EcdsaAccount;
+impl Convert<ecdsa::Public, AccountId32> for EcdsaAccount {
+	fn convert(public: ecdsa::Public) -> AccountId32 {
+		MultiSigner::from(public).into_account()
+	}
+}
+
+/// Changing the allowlist is for operators only.
+pub struct AllowlistCalls;
+impl Contains<RuntimeCall> for AllowlistCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::Allowlist(_))
+	}
+}
+
+impl pallet_allowlist::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = EnsureRoot<AccountId32>;
+	type SignerToAccountId = EcdsaAccount;
+	type OperatorCalls = AllowlistCalls;
+}
+
+// Build genesis storage according to the mock runtime, allowlisting `members`.
+pub fn new_test_ext(
+	members: Vec<(account::AccountId20, pallet_allowlist::Role)>,
+) -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&pallet_allowlist::GenesisConfig { members },
+		&mut storage,
+	)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,138 @@
+use crate::{
+	mock::*, Accounts, Addresses, CheckAllowlist, Error, Event, Members, Role, INSUFFICIENT_ROLE,
+	NOT_ALLOWLISTED,
+};
+use account::AccountId20;
+use frame_support::{
+	assert_noop, assert_ok, dispatch::DispatchInfo, traits::Get, weights::constants::RocksDbWeight,
+};
+use sp_core::{ecdsa, Pair};
+use sp_runtime::{
+	traits::{IdentifyAccount, SignedExtension},
+	transaction_validity::{InvalidTransaction, TransactionValidity},
+	DispatchError, MultiSigner,
+};
+
+fn pair(seed: &str) -> ecdsa::Pair {
+	ecdsa::Pair::from_string(&format!("//{}", seed), None).unwrap()
+}
+
+fn address(seed: &str) -> AccountId20 {
+	pair(seed).public().into()
+}
+
+fn remark() -> RuntimeCall {
+	frame_system::Call::remark { remark: vec![] }.into()
+}
+
+fn validate(seed: &str, call: &RuntimeCall) -> TransactionValidity {
+	let public = pair(seed).public();
+	let who = MultiSigner::from(public.clone()).into_account();
+	CheckAllowlist::<Test>::new(Some(public)).validate(&who, call, &DispatchInfo::default(), 0)
+}
+
+#[test]
+fn admits_members_only() {
+	new_test_ext(vec![(address("Alice"), Role::User)]).execute_with(|| {
+		assert_ok!(validate("Alice", &remark()));
+		assert_eq!(
+			validate("Bob", &remark()),
+			Err(InvalidTransaction::Custom(NOT_ALLOWLISTED).into())
+		);
+	});
+}
+
+#[test]
+fn rejects_key_of_other_account() {
+	new_test_ext(vec![(address("Alice"), Role::User)]).execute_with(|| {
+		let bob = MultiSigner::from(pair("Bob").public()).into_account();
+		let info = DispatchInfo::default();
+
+		assert_eq!(
+			CheckAllowlist::<Test>::new(Some(pair("Alice").public())).validate(
+				&bob,
+				&remark(),
+				&info,
+				0
+			),
+			Err(InvalidTransaction::BadSigner.into())
+		);
+		assert_eq!(
+			CheckAllowlist::<Test>::new(None).pre_dispatch(&bob, &remark(), &info, 0),
+			Err(InvalidTransaction::Custom(NOT_ALLOWLISTED).into())
+		);
+	});
+}
+
+#[test]
+fn links_addresses_and_accounts_of_dispatched_signers() {
+	new_test_ext(vec![(address("Alice"), Role::User)]).execute_with(|| {
+		let alice = MultiSigner::from(pair("Alice").public()).into_account();
+		let check = || CheckAllowlist::<Test>::new(Some(pair("Alice").public()));
+		let info = DispatchInfo::default();
+
+		// Validating for the pool doesn't link them.
+		assert_ok!(check().validate(&alice, &remark(), &info, 0));
+		assert_eq!(Allowlist::account(address("Alice")), None);
+
+		assert_ok!(check().pre_dispatch(&alice, &remark(), &info, 0));
+		assert_eq!(Allowlist::account(address("Alice")), Some(alice.clone()));
+		assert_eq!(Allowlist::address(&alice), Some(address("Alice")));
+
+		// Linking is weighed, and only writes the first time.
+		let weight = || System::block_weight().total();
+		assert_eq!(weight(), RocksDbWeight::get().reads_writes(1, 2));
+		assert_ok!(check().pre_dispatch(&alice, &remark(), &info, 0));
+		assert_eq!(weight(), RocksDbWeight::get().reads_writes(2, 2));
+
+		// Rejected signers aren't linked.
+		let bob = MultiSigner::from(pair("Bob").public()).into_account();
+		assert!(CheckAllowlist::<Test>::new(Some(pair("Bob").public()))
+			.pre_dispatch(&bob, &remark(), &info, 0)
+			.is_err());
+		assert!(!Accounts::<Test>::contains_key(address("Bob")));
+		assert!(!Addresses::<Test>::contains_key(&bob));
+	});
+}
+
+#[test]
+fn operator_calls_need_operator_role() {
+	new_test_ext(vec![(address("Alice"), Role::Operator), (address("Bob"), Role::User)])
+		.execute_with(|| {
+			let call: RuntimeCall = crate::Call::remove_member { address: address("Alice") }.into();
+
+			assert_ok!(validate("Alice", &call));
+			assert_eq!(
+				validate("Bob", &call),
+				Err(InvalidTransaction::Custom(INSUFFICIENT_ROLE).into())
+			);
+		});
+}
+
+#[test]
+fn admin_manages_members() {
+	new_test_ext(vec![]).execute_with(|| {
+		let alice = address("Alice");
+		assert_noop!(
+			Allowlist::add_member(
+				RuntimeOrigin::signed(MultiSigner::from(pair("Alice").public()).into_account()),
+				alice,
+				Role::Operator
+			),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Allowlist::add_member(RuntimeOrigin::root(), alice, Role::User));
+		System::assert_last_event(Event::MemberAdded { address: alice, role: Role::User }.into());
+		assert_ok!(Allowlist::add_member(RuntimeOrigin::root(), alice, Role::Operator));
+		assert_eq!(Members::<Test>::get(alice), Some(Role::Operator));
+
+		assert_ok!(Allowlist::remove_member(RuntimeOrigin::root(), alice));
+		System::assert_last_event(Event::MemberRemoved { address: alice }.into());
+		assert_eq!(Allowlist::member(alice), None);
+		assert_noop!(
+			Allowlist::remove_member(RuntimeOrigin::root(), alice),
+			Error::<Test>::NotMember
+		);
+	});
+}
@@ -0,0 +1,89 @@
+//! The offence of sealing two blocks for the same slot, and the check of its proof.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_consensus_slots::EquivocationProof;
+use sp_runtime::{
+	traits::{Header as HeaderT, Zero},
+	Perbill, RuntimeAppPublic, RuntimeDebug,
+};
+use sp_staking::{
+	offence::{Kind, Offence},
+	SessionIndex,
+};
+use sp_std::prelude::*;
+
+/// An Aura authority sealed two blocks for the same slot.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct AuraEquivocationOffence<FullIdentification> {
+	/// The slot of both blocks.
+	pub slot: Slot,
+	/// The session of the key ownership proof.
+	pub session_index: SessionIndex,
+	/// The number of validators in that session.
+	pub validator_set_count: u32,
+	/// The authority that sealed both blocks.
+	pub offender: FullIdentification,
+}
+
+impl<FullIdentification: Clone> Offence<FullIdentification>
+	for AuraEquivocationOffence<FullIdentification>
+{
+	const ID: Kind = *b"aura:equivocatio";
+	type TimeSlot = Slot;
+
+	fn offenders(&self) -> Vec<FullIdentification> {
+		vec![self.offender.clone()]
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.slot
+	}
+
+	fn slash_fraction(&self, offenders_count: u32) -> Perbill {
+		if self.validator_set_count.is_zero() {
+			return Perbill::zero()
+		}
+		// The fraction of BABE and GRANDPA equivocations: min(3k / n, 1)^2 for k offenders of the
+		// same slot among n validators.
+		Perbill::from_rational(3 * offenders_count, self.validator_set_count).square()
+	}
+}
+
+/// Whether `proof` holds two different headers of `proof.slot`, both sealed by `proof.offender`.
+pub fn check_equivocation_proof<H, P>(proof: &EquivocationProof<H, P>) -> bool
+where
+	H: HeaderT,
+	P: RuntimeAppPublic,
+{
+	let sealed_by_offender = |header: &H| {
+		// Aura seals the hash of the header without the seal.
+		let mut header = header.clone();
+		let signature = match header
+			.digest_mut()
+			.pop()
+			.and_then(|seal| seal.seal_try_to::<P::Signature>(&AURA_ENGINE_ID))
+		{
+			Some(signature) => signature,
+			None => return false,
+		};
+		let slot = header
+			.digest()
+			.convert_first(|log| log.pre_runtime_try_to::<Slot>(&AURA_ENGINE_ID));
+
+		slot == Some(proof.slot) && proof.offender.verify(&header.hash(), &signature)
+	};
+
+	proof.first_header.hash() != proof.second_header.hash() &&
+		sealed_by_offender(&proof.first_header) &&
+		sealed_by_offender(&proof.second_header)
+}
@@ -0,0 +1,195 @@
+//! # Aura Equivocation Pallet
+//!
+//! Reports Aura authorities that seal two blocks for the same slot. The node detects such
+//! equivocations while importing blocks and hands the two conflicting headers, along with a proof
+//! that the offender's key belongs to a validator, to
+//! [`Pallet::submit_unsigned_equivocation_report`]. The resulting unsigned
+//! [`Call::report_equivocation_unsigned`] checks both and reports the offence to
+//! [`Config::ReportOffence`], e.g. `pallet_offences`, which decides the punishment.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use equivocation::{check_equivocation_proof, AuraEquivocationOffence};
+pub use pallet::*;
+
+mod equivocation;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::KeyOwnerProofSystem;
+use frame_system::offchain::SubmitTransaction;
+use sp_consensus_slots::EquivocationProof;
+use sp_runtime::{
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	KeyTypeId, RuntimeAppPublic,
+};
+use sp_session::{GetSessionNumber, GetValidatorCount};
+use sp_staking::offence::ReportOffence;
+use sp_std::prelude::*;
+
+/// Proof that an Aura authority of the runtime `T` sealed two blocks for the same slot.
+pub type EquivocationProofOf<T> =
+	EquivocationProof<<T as frame_system::Config>::Header, <T as pallet_aura::Config>::AuthorityId>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{dispatch::Pays, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config
+		+ pallet_aura::Config
+		+ frame_system::offchain::SendTransactionTypes<Call<Self>>
+	{
+		/// Proves that an Aura key belongs to a validator, e.g. `pallet_session::historical`.
+		type KeyOwnerProofSystem: KeyOwnerProofSystem<
+			(KeyTypeId, Self::AuthorityId),
+			Proof = Self::KeyOwnerProof,
+			IdentificationTuple = Self::KeyOwnerIdentification,
+		>;
+
+		/// A proof of key ownership, which tells the session it was made in.
+		type KeyOwnerProof: Parameter + GetSessionNumber + GetValidatorCount;
+
+		/// The identification of an offender.
+		type KeyOwnerIdentification: Parameter;
+
+		/// Receives the proven equivocations.
+		type ReportOffence: ReportOffence<
+			Self::AccountId,
+			Self::KeyOwnerIdentification,
+			AuraEquivocationOffence<Self::KeyOwnerIdentification>,
+		>;
+
+		/// Number of blocks a report stays valid in the transaction pool.
+		#[pallet::constant]
+		type ReportLongevity: Get<u64>;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The key ownership proof is invalid.
+		InvalidKeyOwnershipProof,
+		/// The headers aren't two blocks of the same slot sealed by the offender.
+		InvalidEquivocationProof,
+		/// The equivocation was already reported.
+		DuplicateOffenceReport,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Report that the offender of `equivocation_proof` sealed two blocks for the same slot.
+		///
+		/// Only the block author includes these reports, which its node submits when it imports
+		/// both blocks.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 4).ref_time())]
+		pub fn report_equivocation_unsigned(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProofOf<T>>,
+			key_owner_proof: T::KeyOwnerProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let session_index = key_owner_proof.session();
+			let validator_set_count = key_owner_proof.validator_count();
+			let offender = T::KeyOwnerProofSystem::check_proof(
+				Self::key(&equivocation_proof),
+				key_owner_proof,
+			)
+			.ok_or(Error::<T>::InvalidKeyOwnershipProof)?;
+			ensure!(
+				check_equivocation_proof(&equivocation_proof),
+				Error::<T>::InvalidEquivocationProof
+			);
+
+			let offence = AuraEquivocationOffence {
+				slot: equivocation_proof.slot,
+				session_index,
+				validator_set_count,
+				offender,
+			};
+			T::ReportOffence::report_offence(Vec::new(), offence)
+				.map_err(|_| Error::<T>::DuplicateOffenceReport)?;
+
+			// Valid reports are free.
+			Ok(Pays::No.into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (equivocation_proof, key_owner_proof) = match call {
+				Call::report_equivocation_unsigned { equivocation_proof, key_owner_proof } =>
+					(equivocation_proof, key_owner_proof),
+				_ => return InvalidTransaction::Call.into(),
+			};
+			// Reports are only submitted by the local node, for its own blocks.
+			if !matches!(source, TransactionSource::Local | TransactionSource::InBlock) {
+				return InvalidTransaction::Call.into()
+			}
+			Self::check_unknown_offence(equivocation_proof, key_owner_proof)?;
+
+			ValidTransaction::with_tag_prefix("AuraEquivocation")
+				.priority(TransactionPriority::max_value())
+				.and_provides((equivocation_proof.offender.clone(), equivocation_proof.slot))
+				.longevity(T::ReportLongevity::get())
+				.propagate(false)
+				.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			match call {
+				Call::report_equivocation_unsigned { equivocation_proof, key_owner_proof } =>
+					Self::check_unknown_offence(equivocation_proof, key_owner_proof),
+				_ => Err(InvalidTransaction::Call.into()),
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Submit a report of `equivocation_proof` to the transaction pool. Only callable from the
+	/// node, e.g. through a runtime API.
+	pub fn submit_unsigned_equivocation_report(
+		equivocation_proof: EquivocationProofOf<T>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> Option<()> {
+		let call = Call::report_equivocation_unsigned {
+			equivocation_proof: Box::new(equivocation_proof),
+			key_owner_proof,
+		};
+		SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).ok()
+	}
+
+	/// The key of the offender of `equivocation_proof`, as known to the key owner proof system.
+	fn key(equivocation_proof: &EquivocationProofOf<T>) -> (KeyTypeId, T::AuthorityId) {
+		(<T::AuthorityId as RuntimeAppPublic>::ID, equivocation_proof.offender.clone())
+	}
+
+	/// Fail unless the key ownership proof is valid and the equivocation wasn't reported yet.
+	fn check_unknown_offence(
+		equivocation_proof: &EquivocationProofOf<T>,
+		key_owner_proof: &T::KeyOwnerProof,
+	) -> Result<(), TransactionValidityError> {
+		let offender = T::KeyOwnerProofSystem::check_proof(
+			Self::key(equivocation_proof),
+			key_owner_proof.clone(),
+		)
+		.ok_or(InvalidTransaction::BadProof)?;
+
+		if T::ReportOffence::is_known_offence(&[offender], &equivocation_proof.slot) {
+			return Err(InvalidTransaction::Stale.into())
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,55 @@
+use crate::check_equivocation_proof;
+use codec::Encode;
+use sp_consensus_aura::{sr25519::AuthorityId, Slot, AURA_ENGINE_ID};
+use sp_consensus_slots::EquivocationProof;
+use sp_core::{sr25519, Pair};
+use sp_runtime::{testing::Header, traits::Header as _, DigestItem};
+
+fn pair(seed: &str) -> sr25519::Pair {
+	sr25519::Pair::from_string(&format!("//{}", seed), None).unwrap()
+}
+
+/// The header of block `number` of `slot`, sealed by `author`.
+fn sealed_header(author: &sr25519::Pair, slot: u64, number: u64) -> Header {
+	let mut header = Header::new_from_number(number);
+	header
+		.digest_mut()
+		.push(DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(slot).encode()));
+	let signature = author.sign(header.hash().as_ref());
+	header.digest_mut().push(DigestItem::Seal(AURA_ENGINE_ID, signature.encode()));
+	header
+}
+
+fn proof(offender: &sr25519::Pair, first: Header, second: Header) -> bool {
+	let proof: EquivocationProof<Header, AuthorityId> = EquivocationProof {
+		offender: offender.public().into(),
+		slot: Slot::from(1),
+		first_header: first,
+		second_header: second,
+	};
+	sp_io::TestExternalities::default().execute_with(|| check_equivocation_proof(&proof))
+}
+
+#[test]
+fn two_blocks_sealed_for_one_slot_are_an_equivocation() {
+	let alice = pair("Alice");
+
+	assert!(proof(&alice, sealed_header(&alice, 1, 1), sealed_header(&alice, 1, 2)));
+}
+
+#[test]
+fn other_pairs_of_blocks_are_not() {
+	let alice = pair("Alice");
+	let bob = pair("Bob");
+
+	// The same block twice.
+	assert!(!proof(&alice, sealed_header(&alice, 1, 1), sealed_header(&alice, 1, 1)));
+	// Blocks of different slots.
+	assert!(!proof(&alice, sealed_header(&alice, 1, 1), sealed_header(&alice, 2, 2)));
+	// A block sealed by someone else.
+	assert!(!proof(&alice, sealed_header(&alice, 1, 1), sealed_header(&bob, 1, 2)));
+	// A block without seal.
+	let mut unsealed = sealed_header(&alice, 1, 2);
+	unsealed.digest_mut().pop();
+	assert!(!proof(&alice, sealed_header(&alice, 1, 1), unsealed));
+}
@@ -0,0 +1,243 @@
+//! # Author VRF Pallet
+//!
+//! On-chain randomness from a verifiable random function (VRF) that the author of every block
+//! evaluates with its VRF session key. The mandatory [`Call::set_vrf`] inherent carries the
+//! output, which is the VRF of the previous randomness and the block number, along with its proof.
+//! The pallet checks the proof against the key of the slot's Aura author and stores the output as
+//! the new randomness, which it exposes through the [`Randomness`] trait.
+//!
+//! ## Security
+//!
+//! - Nobody but the author can predict the randomness of a block before the block is published, and
+//!   everyone can verify it afterwards.
+//! - The author can't choose the randomness: the VRF has exactly one output for its key and input,
+//!   and the input is fixed by the parent block.
+//! - The author learns the randomness before anyone else and may withhold the block to discard it.
+//!   The author of the next slot then evaluates the VRF of the block instead, so withholding only
+//!   trades one outcome for another the author can't predict, at the cost of its slot. Consumers
+//!   should fix their inputs, e.g. close a lottery, before the block that decides it.
+//!
+//! [`Randomness::random`] returns the block at which the randomness became known to the author.
+//! It is the current block once the inherent has been applied, and the parent block before, e.g.
+//! in `on_initialize`.
+//!
+//! The VRF keys are session keys, which the pallet receives from `pallet_session` in the same order
+//! as Aura receives the authorities. A new set of keys only takes effect at the end of the block
+//! the session changes in, as the author of that block was chosen from the previous authorities.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use author_vrf::{AuthorityId, Randomness as RandomnessOutput};
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use author_vrf::{InherentType, VRFOutput, VRFProof, INHERENT_IDENTIFIER};
+use codec::Decode;
+use frame_support::{
+	traits::{OneSessionHandler, Randomness},
+	WeakBoundedVec,
+};
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_runtime::traits::{Hash, One, SaturatedConversion, Saturating};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_inherents::InherentIdentifier;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Maximum number of VRF keys, at least the maximum number of Aura authorities.
+		#[pallet::constant]
+		type MaxAuthorities: Get<u32>;
+	}
+
+	/// The VRF keys of the validators, in the order of the Aura authorities.
+	#[pallet::storage]
+	#[pallet::getter(fn authorities)]
+	pub type Authorities<T: Config> =
+		StorageValue<_, WeakBoundedVec<AuthorityId, T::MaxAuthorities>, ValueQuery>;
+
+	/// The VRF keys of a new session, which replace [`Authorities`] at the end of the block.
+	#[pallet::storage]
+	pub type NextAuthorities<T: Config> =
+		StorageValue<_, WeakBoundedVec<AuthorityId, T::MaxAuthorities>>;
+
+	/// The output of the latest author VRF.
+	#[pallet::storage]
+	#[pallet::getter(fn randomness)]
+	pub type LatestRandomness<T> = StorageValue<_, RandomnessOutput, ValueQuery>;
+
+	/// Whether the VRF of the current block has been set.
+	#[pallet::storage]
+	pub type DidSet<T> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		/// VRF keys of the initial authorities, unless `pallet_session` provides them.
+		pub authorities: Vec<AuthorityId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self { authorities: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			Pallet::<T>::initialize_authorities(self.authorities.clone());
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The VRF of the block has already been set.
+		AlreadySet,
+		/// The proof doesn't show that the block's author evaluated the VRF.
+		InvalidVrf,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: T::BlockNumber) -> Weight {
+			// Reads and writes of `on_finalize`.
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+
+		fn on_finalize(_: T::BlockNumber) {
+			assert!(DidSet::<T>::take(), "The author VRF must be set once in the block");
+
+			if let Some(next) = NextAuthorities::<T>::take() {
+				Authorities::<T>::put(next);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the randomness of the block to the `output` of the author's VRF.
+		///
+		/// The dispatch origin for this call must be `Inherent`.
+		#[pallet::weight((
+			10_000 + T::DbWeight::get().reads_writes(5, 2).ref_time(),
+			DispatchClass::Mandatory
+		))]
+		pub fn set_vrf(origin: OriginFor<T>, output: VRFOutput, proof: VRFProof) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(!DidSet::<T>::exists(), Error::<T>::AlreadySet);
+
+			let randomness = Self::verify(&output, &proof).ok_or(Error::<T>::InvalidVrf)?;
+			LatestRandomness::<T>::put(randomness);
+			DidSet::<T>::put(true);
+
+			Ok(())
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = sp_inherents::MakeFatalError<()>;
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			let (output, proof) = data.get_data::<InherentType>(&INHERENT_IDENTIFIER).ok()??;
+			Some(Call::set_vrf { output, proof })
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			matches!(call, Call::set_vrf { .. })
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The VRF key of the author of the current block, i.e. the key at the index of the Aura slot
+	/// modulo the number of keys.
+	pub fn author() -> Option<AuthorityId> {
+		let digest = frame_system::Pallet::<T>::digest();
+		let slot = digest.logs.iter().filter_map(|log| log.as_pre_runtime()).find_map(
+			|(id, mut data)| if id == AURA_ENGINE_ID { Slot::decode(&mut data).ok() } else { None },
+		)?;
+
+		let authorities = Authorities::<T>::get();
+		if authorities.is_empty() {
+			return None
+		}
+		let index = *slot % authorities.len() as u64;
+		authorities.get(index as usize).cloned()
+	}
+
+	/// The randomness of the current block, if `proof` shows that `output` is its author's VRF.
+	pub fn verify(output: &VRFOutput, proof: &VRFProof) -> Option<RandomnessOutput> {
+		let number = frame_system::Pallet::<T>::block_number().saturated_into::<u64>();
+		author_vrf::verify(&Self::author()?, &LatestRandomness::<T>::get(), number, output, proof)
+	}
+
+	fn initialize_authorities(authorities: Vec<AuthorityId>) {
+		if authorities.is_empty() {
+			return
+		}
+		assert!(Authorities::<T>::get().is_empty(), "Authorities are already initialized!");
+		Authorities::<T>::put(Self::bound(authorities));
+	}
+
+	fn bound(authorities: Vec<AuthorityId>) -> WeakBoundedVec<AuthorityId, T::MaxAuthorities> {
+		WeakBoundedVec::force_from(
+			authorities,
+			Some("Author VRF new session has more keys than `MaxAuthorities`"),
+		)
+	}
+}
+
+/// Randomness of the latest author VRF, mixed with the subject.
+impl<T: Config> Randomness<T::Hash, T::BlockNumber> for Pallet<T> {
+	fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+		let randomness = LatestRandomness::<T>::get();
+		let now = frame_system::Pallet::<T>::block_number();
+		let known_since = if DidSet::<T>::get() { now } else { now.saturating_sub(One::one()) };
+
+		(T::Hashing::hash(&[subject, &randomness[..]].concat()), known_since)
+	}
+}
+
+impl<T: Config> sp_runtime::BoundToRuntimeAppPublic for Pallet<T> {
+	type Public = AuthorityId;
+}
+
+impl<T: Config> OneSessionHandler<T::AccountId> for Pallet<T> {
+	type Key = AuthorityId;
+
+	fn on_genesis_session<'a, I: 'a>(validators: I)
+	where
+		I: Iterator<Item = (&'a T::AccountId, AuthorityId)>,
+	{
+		Self::initialize_authorities(validators.map(|(_, key)| key).collect());
+	}
+
+	fn on_new_session<'a, I: 'a>(changed: bool, validators: I, _queued_validators: I)
+	where
+		I: Iterator<Item = (&'a T::AccountId, AuthorityId)>,
+	{
+		if changed {
+			NextAuthorities::<T>::put(Self::bound(validators.map(|(_, key)| key).collect()));
+		}
+	}
+
+	fn on_disabled(_: u32) {}
+}
@@ -0,0 +1,98 @@
+use crate as pallet_author_vrf;
+use author_vrf::{AuthorityPair, InherentDataProvider, InherentType, KEY_TYPE};
+use codec::Encode;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, GenesisBuild};
+use frame_system as system;
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_core::{Pair, H256};
+use sp_keystore::{testing::KeyStore, SyncCryptoStore};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Digest, DigestItem,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		AuthorVrf: pallet_author_vrf,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_author_vrf::Config for Test {
+	type MaxAuthorities = ConstU32<4>;
+}
+
+/// The VRF key of `seed`, e.g. `"Alice"`.
+pub fn key(seed: &str) -> author_vrf::AuthorityId {
+	AuthorityPair::from_string(&format!("//{}", seed), None).unwrap().public()
+}
+
+/// The VRF output and proof of `seed` for the current block.
+pub fn vrf(seed: &str) -> InherentType {
+	let keystore = KeyStore::new();
+	SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, Some(&format!("//{}", seed)))
+		.unwrap();
+	InherentDataProvider::new(
+		&keystore,
+		&key(seed),
+		&AuthorVrf::randomness(),
+		System::block_number(),
+	)
+	.vrf()
+	.cloned()
+	.unwrap()
+}
+
+/// Start block `number`, authored in Aura `slot`.
+pub fn start_block(number: u64, slot: u64) {
+	let digest =
+		Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(slot).encode())] };
+	System::initialize(&number, &Default::default(), &digest);
+}
+
+// Build genesis storage according to the mock runtime, with the VRF keys of Alice and Bob.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&pallet_author_vrf::GenesisConfig { authorities: vec![key("Alice"), key("Bob")] },
+		&mut storage,
+	)
+	.unwrap();
+	storage.into()
+}
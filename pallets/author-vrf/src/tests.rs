@@ -0,0 +1,108 @@
+use crate::{mock::*, Authorities, Error};
+use author_vrf::INHERENT_IDENTIFIER;
+use frame_support::{
+	assert_noop, assert_ok,
+	inherent::{InherentData, ProvideInherent},
+	traits::{OnFinalize, OneSessionHandler, Randomness, UnfilteredDispatchable},
+};
+
+#[test]
+fn the_slot_author_sets_the_randomness() {
+	new_test_ext().execute_with(|| {
+		// Bob authors slot 1 of two authorities.
+		start_block(1, 1);
+		let mut data = InherentData::new();
+		data.put_data(INHERENT_IDENTIFIER, &vrf("Bob")).unwrap();
+		let call = AuthorVrf::create_inherent(&data).expect("the VRF was provided");
+		assert!(AuthorVrf::is_inherent(&call));
+		assert_ok!(call.dispatch_bypass_filter(RuntimeOrigin::none()));
+		let first = AuthorVrf::randomness();
+		assert_ne!(first, [0; 32]);
+		AuthorVrf::on_finalize(1);
+
+		// Alice authors slot 2, with the previous randomness as input.
+		start_block(2, 2);
+		let (output, proof) = vrf("Alice");
+		assert_ok!(AuthorVrf::set_vrf(RuntimeOrigin::none(), output, proof));
+		assert_ne!(AuthorVrf::randomness(), first);
+	});
+}
+
+#[test]
+fn only_the_authors_vrf_of_the_block_is_accepted() {
+	new_test_ext().execute_with(|| {
+		start_block(1, 0);
+		let (output, proof) = vrf("Bob");
+		assert_noop!(
+			AuthorVrf::set_vrf(RuntimeOrigin::none(), output, proof),
+			Error::<Test>::InvalidVrf
+		);
+
+		let (output, proof) = vrf("Alice");
+		assert_noop!(
+			AuthorVrf::set_vrf(RuntimeOrigin::signed(1), output.clone(), proof.clone()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(AuthorVrf::set_vrf(RuntimeOrigin::none(), output.clone(), proof.clone()));
+		assert_noop!(
+			AuthorVrf::set_vrf(RuntimeOrigin::none(), output.clone(), proof.clone()),
+			Error::<Test>::AlreadySet
+		);
+		AuthorVrf::on_finalize(1);
+
+		// The VRF of a block can't be replayed in a later one.
+		start_block(2, 2);
+		assert_noop!(
+			AuthorVrf::set_vrf(RuntimeOrigin::none(), output, proof),
+			Error::<Test>::InvalidVrf
+		);
+	});
+}
+
+#[test]
+fn random_mixes_the_subject_and_tells_when_the_randomness_was_known() {
+	new_test_ext().execute_with(|| {
+		start_block(5, 0);
+		let (before, known_since) = AuthorVrf::random(b"lottery");
+		assert_eq!(known_since, 4);
+
+		let (output, proof) = vrf("Alice");
+		assert_ok!(AuthorVrf::set_vrf(RuntimeOrigin::none(), output, proof));
+		let (after, known_since) = AuthorVrf::random(b"lottery");
+		assert_eq!(known_since, 5);
+		assert_ne!(after, before);
+		assert_ne!(AuthorVrf::random(b"raffle").0, after);
+	});
+}
+
+#[test]
+#[should_panic(expected = "The author VRF must be set once in the block")]
+fn blocks_without_the_vrf_are_invalid() {
+	new_test_ext().execute_with(|| {
+		start_block(1, 0);
+		AuthorVrf::on_finalize(1);
+	});
+}
+
+#[test]
+fn keys_of_a_new_session_take_effect_at_the_end_of_the_block() {
+	new_test_ext().execute_with(|| {
+		start_block(1, 0);
+		let validators = [(1, key("Charlie"))];
+		AuthorVrf::on_new_session(
+			true,
+			validators.iter().map(|(id, key)| (id, key.clone())),
+			validators.iter().map(|(id, key)| (id, key.clone())),
+		);
+
+		// Alice was chosen from the previous authorities to author this block.
+		let (output, proof) = vrf("Alice");
+		assert_ok!(AuthorVrf::set_vrf(RuntimeOrigin::none(), output, proof));
+		AuthorVrf::on_finalize(1);
+		assert_eq!(Authorities::<Test>::get().to_vec(), vec![key("Charlie")]);
+
+		start_block(2, 0);
+		let (output, proof) = vrf("Charlie");
+		assert_ok!(AuthorVrf::set_vrf(RuntimeOrigin::none(), output, proof));
+	});
+}
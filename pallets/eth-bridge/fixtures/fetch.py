@@ -0,0 +1,120 @@
+#!/usr/bin/env python3
+"""Builds a fixture like `deposit.json` from a real block, fetched from an Ethereum JSON-RPC node.
+
+The node has to serve `debug_getRawReceipts`, as geth, reth and erigon do. The script rebuilds
+the RLP header from `eth_getBlockByNumber` and the receipts trie from the raw receipts, and
+checks them against the block hash and the header's receipts root before printing anything:
+
+    python3 fetch.py <RPC_URL> <BLOCK_NUMBER> <INDEX> <OTHER_INDEX> > mainnet.json
+
+`INDEX` is the transaction whose receipt holds the WETH `Deposit` log the tests prove.
+`OTHER_INDEX` is a transaction without one, which yields `other_proof`.
+"""
+import json
+import sys
+import urllib.request
+
+from generate import DEPOSIT, WETH, keccak, prefixed, rlp, trie
+
+# Header fields in RLP order, each with whether it is a quantity rather than data. Forks append
+# fields, which blocks before them lack.
+HEADER_FIELDS = [
+    ('parentHash', False), ('sha3Uncles', False), ('miner', False), ('stateRoot', False),
+    ('transactionsRoot', False), ('receiptsRoot', False), ('logsBloom', False),
+    ('difficulty', True), ('number', True), ('gasLimit', True), ('gasUsed', True),
+    ('timestamp', True), ('extraData', False), ('mixHash', False), ('nonce', False),
+    ('baseFeePerGas', True), ('withdrawalsRoot', False), ('blobGasUsed', True),
+    ('excessBlobGas', True), ('parentBeaconBlockRoot', False), ('requestsHash', False),
+]
+
+
+def request(url, method, *params):
+    body = json.dumps({'jsonrpc': '2.0', 'id': 1, 'method': method, 'params': params}).encode()
+    headers = {'Content-Type': 'application/json'}
+    with urllib.request.urlopen(urllib.request.Request(url, body, headers)) as response:
+        reply = json.load(response)
+    if 'error' in reply:
+        sys.exit(f'{method} failed: {reply["error"]}')
+    return reply['result']
+
+
+def data(value):
+    return bytes.fromhex(value[2:])
+
+
+def header_rlp(block):
+    fields = []
+    for name, quantity in HEADER_FIELDS:
+        if name not in block:
+            break
+        fields.append(int(block[name], 16) if quantity else data(block[name]))
+    return rlp(fields)
+
+
+def decode(item):
+    """Decode an RLP item into byte strings and lists, returning it and the rest of the input."""
+    prefix = item[0]
+    if prefix < 0x80:
+        return item[:1], item[1:]
+    if prefix < 0xc0:
+        short = prefix < 0xb8
+        offset, length = (1, prefix - 0x80) if short else \
+            (1 + prefix - 0xb7, int.from_bytes(item[1:1 + prefix - 0xb7], 'big'))
+        return item[offset:offset + length], item[offset + length:]
+    short = prefix < 0xf8
+    offset, length = (1, prefix - 0xc0) if short else \
+        (1 + prefix - 0xf7, int.from_bytes(item[1:1 + prefix - 0xf7], 'big'))
+    payload, rest, items = item[offset:offset + length], item[offset + length:], []
+    while payload:
+        decoded, payload = decode(payload)
+        items.append(decoded)
+    return items, rest
+
+
+def logs(receipt):
+    """The logs of a raw receipt, typed or legacy, as (address, topics, data) triples."""
+    if receipt[0] < 0x80:
+        receipt = receipt[1:]
+    return decode(receipt)[0][3]
+
+
+def main():
+    url, number, index, other_index = sys.argv[1], int(sys.argv[2]), int(sys.argv[3]), \
+        int(sys.argv[4])
+    block = request(url, 'eth_getBlockByNumber', hex(number), False)
+    header = header_rlp(block)
+    if prefixed(keccak(header)) != block['hash']:
+        sys.exit('the rebuilt header does not hash to the block hash')
+
+    receipts = [data(receipt) for receipt in request(url, 'debug_getRawReceipts', hex(number))]
+    pairs = [(rlp(i), receipt) for i, receipt in enumerate(receipts)]
+    receipts_root, proof = trie(pairs, rlp(index))
+    if prefixed(receipts_root) != block['receiptsRoot']:
+        sys.exit('the rebuilt receipts trie does not match the receipts root')
+
+    def deposits(receipt):
+        return [log for log in logs(receipt) if log[0] == WETH and log[1][:1] == [DEPOSIT]]
+
+    if not deposits(receipts[index]):
+        sys.exit(f'transaction {index} emits no WETH deposit')
+    if deposits(receipts[other_index]):
+        sys.exit(f'transaction {other_index} emits a WETH deposit')
+    _, topics, amount = deposits(receipts[index])[0]
+
+    print(json.dumps({
+        'header': prefixed(header),
+        'hash': block['hash'],
+        'number': number,
+        'receipts_root': block['receiptsRoot'],
+        'contract': prefixed(WETH),
+        'topic': prefixed(DEPOSIT),
+        'recipient': prefixed(topics[1][12:]),
+        'amount': str(int.from_bytes(amount, 'big')),
+        'index': index,
+        'proof': [prefixed(node) for node in proof],
+        'other_proof': [prefixed(node) for node in trie(pairs, rlp(other_index))[1]],
+    }, indent=2))
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,242 @@
+#!/usr/bin/env python3
+"""Generates `deposit.json`, a synthetic block, receipts and proof the pallet's tests import.
+
+None of it is from a real chain: the header's hashes other than the receipts root are keccak
+hashes of placeholder strings, and the receipts are made up. Only the formats match mainnet's: a
+Shanghai header with 17 fields, and a receipts trie mixing legacy and EIP-1559 receipts, keyed by
+the RLP of the transaction index. Transaction 17 deposits 1.5 ether into WETH (0xC02a…6Cc2),
+whose `Deposit(address indexed dst, uint wad)` log is the event the tests configure. Transaction
+18 emits the same event from another contract, for the negative cases. `fetch.py` builds the
+same fixture from a real block instead.
+
+The helpers reproduce the receipts root and hash of a recorded header, e.g. of the block in
+ethers-rs' `serde_block` test (`0xda53…3972`), with the pure Python keccak-256 below:
+
+    python3 generate.py > deposit.json
+"""
+import json
+
+# Keccak-256, i.e. SHA-3 with the original padding.
+ROUND_CONSTANTS = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000,
+    0x000000000000808B, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
+    0x000000008000808B, 0x800000000000008B, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800A, 0x800000008000000A,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+]
+ROTATIONS = [
+    [0, 36, 3, 41, 18], [1, 44, 10, 45, 2], [62, 6, 43, 15, 61], [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+]
+MASK = (1 << 64) - 1
+RATE = 136
+
+
+def rotate(x, n):
+    return ((x << n) | (x >> (64 - n))) & MASK if n else x
+
+
+def permute(a):
+    for constant in ROUND_CONSTANTS:
+        c = [a[x][0] ^ a[x][1] ^ a[x][2] ^ a[x][3] ^ a[x][4] for x in range(5)]
+        d = [c[(x - 1) % 5] ^ rotate(c[(x + 1) % 5], 1) for x in range(5)]
+        a = [[a[x][y] ^ d[x] for y in range(5)] for x in range(5)]
+        b = [[0] * 5 for _ in range(5)]
+        for x in range(5):
+            for y in range(5):
+                b[y][(2 * x + 3 * y) % 5] = rotate(a[x][y], ROTATIONS[x][y])
+        a = [[b[x][y] ^ (~b[(x + 1) % 5][y] & b[(x + 2) % 5][y]) for y in range(5)]
+             for x in range(5)]
+        a[0][0] ^= constant
+    return a
+
+
+def keccak(data):
+    data = bytearray(data) + b'\x01'
+    data += bytes(-len(data) % RATE)
+    data[-1] |= 0x80
+    state = [[0] * 5 for _ in range(5)]
+    for offset in range(0, len(data), RATE):
+        for i in range(RATE // 8):
+            word = data[offset + 8 * i:offset + 8 * i + 8]
+            state[i % 5][i // 5] ^= int.from_bytes(word, 'little')
+        state = permute(state)
+    return b''.join(state[i % 5][i // 5].to_bytes(8, 'little') for i in range(4))
+
+
+# RLP. Items are integers, byte strings, lists, or already encoded `Encoded` nodes.
+class Encoded(bytes):
+    pass
+
+
+def rlp(item):
+    if isinstance(item, Encoded):
+        return bytes(item)
+    if isinstance(item, list):
+        payload = b''.join(rlp(i) for i in item)
+        return length_prefix(len(payload), 0xc0) + payload
+    if isinstance(item, int):
+        item = item.to_bytes((item.bit_length() + 7) // 8, 'big')
+    if len(item) == 1 and item[0] < 0x80:
+        return bytes(item)
+    return length_prefix(len(item), 0x80) + bytes(item)
+
+
+def length_prefix(length, offset):
+    if length < 56:
+        return bytes([offset + length])
+    length = length.to_bytes((length.bit_length() + 7) // 8, 'big')
+    return bytes([offset + 55 + len(length)]) + length
+
+
+# Merkle-Patricia tries.
+def nibbles(key):
+    return [n for byte in key for n in (byte >> 4, byte & 0x0f)]
+
+
+def hex_prefix(path, leaf):
+    flags = 2 if leaf else 0
+    if len(path) % 2:
+        return bytes([(flags + 1) << 4 | path[0]]) + pack(path[1:])
+    return bytes([flags << 4]) + pack(path)
+
+
+def pack(path):
+    return bytes(path[i] << 4 | path[i + 1] for i in range(0, len(path), 2))
+
+
+def reference(node):
+    """Nodes shorter than a hash are embedded in their parents."""
+    return Encoded(node) if len(node) < 32 else keccak(node)
+
+
+def encode(items, key, proof):
+    """Encode the trie of `items`, pairs of paths and values, collecting the nodes on the path
+    to `key` in `proof`, from the leaf up."""
+    if len(items) == 1:
+        path, value = items[0]
+        node = rlp([hex_prefix(path, True), value])
+    else:
+        shared = 0
+        while all(len(path) > shared for path, _ in items) and \
+                len({path[shared] for path, _ in items}) == 1:
+            shared += 1
+        if shared:
+            child = encode([(path[shared:], value) for path, value in items],
+                           key[shared:] if key is not None else None, proof)
+            node = rlp([hex_prefix(items[0][0][:shared], False), reference(child)])
+        else:
+            children = []
+            for nibble in range(16):
+                branch = [(path[1:], value) for path, value in items if path and path[0] == nibble]
+                on_path = key is not None and key[:1] == [nibble]
+                children.append(reference(encode(branch, key[1:] if on_path else None, proof))
+                                if branch else b'')
+            values = [value for path, value in items if not path]
+            node = rlp(children + [values[0] if values else b''])
+    if key is not None and len(node) >= 32:
+        proof.append(node)
+    return node
+
+
+def trie(pairs, key):
+    """The root of the trie of `pairs` and the proof of `key`, from the root down."""
+    proof = []
+    root = encode([(nibbles(k), v) for k, v in pairs], nibbles(key), proof)
+    return keccak(root), proof[::-1]
+
+
+# secp256k1, to derive the recipient's address from its private key.
+P = 2**256 - 2**32 - 977
+G = (0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798,
+     0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8)
+
+
+def add(a, b):
+    if a is None:
+        return b
+    if b is None:
+        return a
+    if a[0] == b[0] and (a[1] + b[1]) % P == 0:
+        return None
+    if a == b:
+        slope = 3 * a[0] * a[0] * pow(2 * a[1], -1, P) % P
+    else:
+        slope = (b[1] - a[1]) * pow(b[0] - a[0], -1, P) % P
+    x = (slope * slope - a[0] - b[0]) % P
+    return (x, (slope * (a[0] - x) - a[1]) % P)
+
+
+def address(secret):
+    point, base = None, G
+    while secret:
+        if secret & 1:
+            point = add(point, base)
+        base, secret = add(base, base), secret >> 1
+    return keccak(point[0].to_bytes(32, 'big') + point[1].to_bytes(32, 'big'))[12:]
+
+
+WETH = bytes.fromhex('c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2')
+OTHER = bytes.fromhex('dac17f958d2ee523a2206206994597c13d831ec7')
+DEPOSIT = keccak(b'Deposit(address,uint256)')
+TRANSFER = keccak(b'Transfer(address,address,uint256)')
+# The first development account of Hardhat and Anvil.
+RECIPIENT = address(0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80)
+AMOUNT = 1_500_000_000_000_000_000
+INDEX = 17
+
+
+def word(n):
+    return n.to_bytes(32, 'big')
+
+
+def topic(address):
+    return bytes(12) + address
+
+
+def receipt(i, cumulative_gas):
+    logs = []
+    if i == INDEX:
+        logs = [[WETH, [DEPOSIT, topic(RECIPIENT)], word(AMOUNT)]]
+    elif i == INDEX + 1:
+        logs = [[OTHER, [DEPOSIT, topic(RECIPIENT)], word(AMOUNT)]]
+    elif i % 3 == 0:
+        sender = keccak(bytes([i]))[:20]
+        logs = [[OTHER, [TRANSFER, topic(sender), topic(RECIPIENT)], word(1000 * i)]]
+    body = rlp([1, cumulative_gas, bytes(256), logs])
+    # Odd transactions are EIP-1559 ones, whose receipts are prefixed with the type.
+    return b'\x02' + body if i % 2 else body
+
+
+def prefixed(data):
+    return '0x' + data.hex()
+
+
+def main():
+    receipts = [(rlp(i), receipt(i, 21000 * (i + 1))) for i in range(40)]
+    receipts_root, proof = trie(receipts, rlp(INDEX))
+    header = [
+        keccak(b'parent'), keccak(rlp([])),
+        bytes.fromhex('95222290dd7278aa3ddd389cc1e1d165cc4bafe5'), keccak(b'state'),
+        keccak(b'transactions'), receipts_root, bytes(256), 0, 17_034_870, 30_000_000, 21000 * 40,
+        1_681_338_479, b'beaverbuild.org', keccak(b'mix'), bytes(8), 17_000_000_000,
+        keccak(b'withdrawals'),
+    ]
+    print(json.dumps({
+        'header': prefixed(rlp(header)),
+        'hash': prefixed(keccak(rlp(header))),
+        'number': header[8],
+        'receipts_root': prefixed(receipts_root),
+        'contract': prefixed(WETH),
+        'topic': prefixed(DEPOSIT),
+        'recipient': prefixed(RECIPIENT),
+        'amount': str(AMOUNT),
+        'index': INDEX,
+        'proof': [prefixed(node) for node in proof],
+        'other_proof': [prefixed(node) for node in trie(receipts, rlp(INDEX + 1))[1]],
+    }, indent=2))
+
+
+if __name__ == '__main__':
+    main()
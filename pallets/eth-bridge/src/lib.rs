@@ -0,0 +1,363 @@
+//! # Ethereum Bridge Pallet
+//!
+//! Imports deposits made on Ethereum, e.g. to a bridge contract, by proving the receipts of the
+//! deposit transactions against relayed Ethereum headers.
+//!
+//! Permissioned relayers submit RLP-encoded headers of finalized Ethereum blocks with
+//! [`Pallet::submit_header`]. A header is imported once [`Config::RequiredConfirmations`] relayers
+//! have submitted it, so a single faulty relayer can't forge one. Anyone can then prove a receipt
+//! of an imported block with [`Pallet::prove_deposit`]: the pallet checks the Merkle-Patricia
+//! proof against the header's `receiptsRoot` and credits the deposits in the receipt's logs.
+//!
+//! Deposits are the logs of the [`DepositEvent`], which [`Config::AdminOrigin`] configures as a
+//! contract address and an event signature. The event must have the recipient's address as its
+//! first indexed parameter and the amount as its first unindexed one, like WETH's
+//! `Deposit(address indexed dst, uint wad)`. Amounts are credited one to one.
+//!
+//! Accounts are hashes of public keys, so the pallet can't derive the recipient's account from
+//! its address. Deposits are therefore credited to the address in [`Claimable`], and the owner of
+//! the address mints them into its account with [`Pallet::claim`], which takes the public key
+//! behind the address.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub mod proof;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::Currency;
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::RuntimeDebug;
+
+/// Balance of the [`Config::Currency`].
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// What the pallet keeps of an imported Ethereum header.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ImportedHeader {
+	/// The block number.
+	pub number: u64,
+	/// The root of the block's receipts trie.
+	pub receipts_root: H256,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::{proof, BalanceOf, ImportedHeader};
+	use account::{rlp, AccountId20};
+	use frame_support::{pallet_prelude::*, traits::Currency};
+	use frame_system::pallet_prelude::*;
+	use sp_core::{ecdsa, H160, H256, U256};
+	use sp_runtime::traits::{Convert, Saturating, Zero};
+	use sp_std::prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency deposits are minted in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The origin allowed to manage the relayers and the deposit event.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The account controlled by a secp256k1 public key.
+		type SignerToAccountId: Convert<ecdsa::Public, Self::AccountId>;
+
+		/// Maximum number of relayers.
+		#[pallet::constant]
+		type MaxRelayers: Get<u32>;
+
+		/// Number of relayers that must submit a header before it is imported.
+		#[pallet::constant]
+		type RequiredConfirmations: Get<u32>;
+	}
+
+	/// The relayers allowed to submit headers.
+	#[pallet::storage]
+	#[pallet::getter(fn relayers)]
+	pub type Relayers<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxRelayers>, ValueQuery>;
+
+	/// The relayers that submitted a header which is not imported yet, by block hash.
+	#[pallet::storage]
+	pub type Confirmations<T: Config> =
+		StorageMap<_, Identity, H256, BoundedVec<T::AccountId, T::MaxRelayers>, ValueQuery>;
+
+	/// The imported headers, by block hash.
+	#[pallet::storage]
+	#[pallet::getter(fn header)]
+	pub type Headers<T> = StorageMap<_, Identity, H256, ImportedHeader>;
+
+	/// The contract and the event signature, i.e. the first topic, of deposit logs.
+	#[pallet::storage]
+	pub type DepositEvent<T> = StorageValue<_, (H160, H256)>;
+
+	/// The receipts whose deposits have been credited, by block hash and transaction index.
+	#[pallet::storage]
+	pub type ProcessedReceipts<T> =
+		StorageDoubleMap<_, Identity, H256, Twox64Concat, u32, (), OptionQuery>;
+
+	/// Deposits that the owners of the addresses haven't claimed yet.
+	#[pallet::storage]
+	#[pallet::getter(fn claimable)]
+	pub type Claimable<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountId20, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub relayers: Vec<T::AccountId>,
+		pub deposit_event: Option<(H160, H256)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { relayers: Vec::new(), deposit_event: None }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let relayers = BoundedVec::<_, T::MaxRelayers>::try_from(self.relayers.clone())
+				.expect("Too many genesis relayers");
+			Relayers::<T>::put(relayers);
+			if let Some(deposit_event) = self.deposit_event {
+				DepositEvent::<T>::put(deposit_event);
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `relayer` may submit headers.
+		RelayerAdded { relayer: T::AccountId },
+		/// `relayer` may no longer submit headers.
+		RelayerRemoved { relayer: T::AccountId },
+		/// Deposits are the logs of `contract` with the first topic `topic`.
+		DepositEventSet { contract: H160, topic: H256 },
+		/// `relayer` submitted the header with `hash`, which awaits more confirmations.
+		HeaderConfirmed { hash: H256, relayer: T::AccountId },
+		/// The header of the block with `hash` and `number` was imported.
+		HeaderImported { hash: H256, number: u64 },
+		/// A log of transaction `index` of the block with `hash` deposited `amount` to `address`.
+		Deposited { address: AccountId20, amount: BalanceOf<T>, hash: H256, index: u32 },
+		/// The owner of `address` minted its deposits of `amount` into `who`.
+		Claimed { address: AccountId20, who: T::AccountId, amount: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already a relayer.
+		AlreadyRelayer,
+		/// The account is not a relayer.
+		NotRelayer,
+		/// There are already `MaxRelayers` relayers.
+		TooManyRelayers,
+		/// The header is not an RLP-encoded Ethereum header.
+		InvalidHeader,
+		/// The header has already been imported.
+		AlreadyImported,
+		/// The relayer has already submitted the header.
+		AlreadyConfirmed,
+		/// No deposit event is configured.
+		NoDepositEvent,
+		/// The header of the block has not been imported.
+		UnknownHeader,
+		/// The receipt's deposits have already been credited.
+		AlreadyProcessed,
+		/// The proof doesn't show a receipt at the index in the block's receipts trie.
+		InvalidProof,
+		/// The proven value is not a receipt.
+		InvalidReceipt,
+		/// A deposit log lacks the recipient or the amount, or the amount exceeds the balance
+		/// type.
+		InvalidDeposit,
+		/// The receipt has no deposit logs.
+		NoDeposit,
+		/// The public key doesn't control the signer's account.
+		NotSigner,
+		/// The address has no deposits to claim.
+		NothingToClaim,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allow `relayer` to submit headers.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn add_relayer(origin: OriginFor<T>, relayer: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Relayers::<T>::try_mutate(|relayers| {
+				ensure!(!relayers.contains(&relayer), Error::<T>::AlreadyRelayer);
+				relayers.try_push(relayer.clone()).map_err(|_| Error::<T>::TooManyRelayers)
+			})?;
+
+			Self::deposit_event(Event::RelayerAdded { relayer });
+			Ok(())
+		}
+
+		/// Stop `relayer` from submitting headers. Its confirmations of pending headers remain.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn remove_relayer(origin: OriginFor<T>, relayer: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Relayers::<T>::try_mutate(|relayers| {
+				let position =
+					relayers.iter().position(|r| r == &relayer).ok_or(Error::<T>::NotRelayer)?;
+				relayers.remove(position);
+				Ok::<_, Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::RelayerRemoved { relayer });
+			Ok(())
+		}
+
+		/// Credit the logs of `contract` whose first topic is `topic` as deposits.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_deposit_event(
+			origin: OriginFor<T>,
+			contract: H160,
+			topic: H256,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			DepositEvent::<T>::put((contract, topic));
+
+			Self::deposit_event(Event::DepositEventSet { contract, topic });
+			Ok(())
+		}
+
+		/// Submit the RLP-encoded `header` of a finalized Ethereum block, which is imported once
+		/// `RequiredConfirmations` relayers have submitted it.
+		///
+		/// The dispatch origin for this call must be a relayer.
+		#[pallet::weight(
+			10_000 + 10 * header.len() as u64 + T::DbWeight::get().reads_writes(3, 2).ref_time()
+		)]
+		pub fn submit_header(origin: OriginFor<T>, header: Vec<u8>) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			ensure!(Relayers::<T>::get().contains(&relayer), Error::<T>::NotRelayer);
+
+			let header = proof::Header::decode(&header).ok_or(Error::<T>::InvalidHeader)?;
+			ensure!(!Headers::<T>::contains_key(header.hash), Error::<T>::AlreadyImported);
+
+			let mut confirmations = Confirmations::<T>::get(header.hash);
+			ensure!(!confirmations.contains(&relayer), Error::<T>::AlreadyConfirmed);
+			confirmations
+				.try_push(relayer.clone())
+				.map_err(|_| Error::<T>::TooManyRelayers)?;
+
+			if confirmations.len() as u32 >= T::RequiredConfirmations::get() {
+				Confirmations::<T>::remove(header.hash);
+				Headers::<T>::insert(
+					header.hash,
+					ImportedHeader { number: header.number, receipts_root: header.receipts_root },
+				);
+				Self::deposit_event(Event::HeaderImported {
+					hash: header.hash,
+					number: header.number,
+				});
+			} else {
+				Confirmations::<T>::insert(header.hash, confirmations);
+				Self::deposit_event(Event::HeaderConfirmed { hash: header.hash, relayer });
+			}
+
+			Ok(())
+		}
+
+		/// Credit the deposits in the receipt of transaction `index` of the block with `hash`.
+		///
+		/// `proof` lists the nodes of the block's receipts trie on the path to the receipt, from
+		/// the root down.
+		#[pallet::weight(
+			10_000 +
+				10 * proof.iter().map(|node| node.len() as u64).sum::<u64>() +
+				T::DbWeight::get().reads_writes(4, 2).ref_time()
+		)]
+		pub fn prove_deposit(
+			origin: OriginFor<T>,
+			hash: H256,
+			index: u32,
+			proof: Vec<Vec<u8>>,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let (contract, topic) = DepositEvent::<T>::get().ok_or(Error::<T>::NoDepositEvent)?;
+			let header = Headers::<T>::get(hash).ok_or(Error::<T>::UnknownHeader)?;
+			ensure!(
+				!ProcessedReceipts::<T>::contains_key(hash, index),
+				Error::<T>::AlreadyProcessed
+			);
+
+			let receipt = proof::verify_proof(&header.receipts_root, &rlp::encode(&index), &proof)
+				.ok_or(Error::<T>::InvalidProof)?;
+			let deposits = proof::decode_logs(&receipt)
+				.ok_or(Error::<T>::InvalidReceipt)?
+				.into_iter()
+				.filter(|log| log.address == contract && log.topics.first() == Some(&topic))
+				.map(|log| Self::decode_deposit(&log).ok_or(Error::<T>::InvalidDeposit))
+				.collect::<Result<Vec<_>, _>>()?;
+			ensure!(!deposits.is_empty(), Error::<T>::NoDeposit);
+
+			ProcessedReceipts::<T>::insert(hash, index, ());
+			for (address, amount) in deposits {
+				Claimable::<T>::mutate(address, |claimable| {
+					*claimable = claimable.saturating_add(amount)
+				});
+				Self::deposit_event(Event::Deposited { address, amount, hash, index });
+			}
+
+			Ok(())
+		}
+
+		/// Mint the deposits to the address of `public` into the signer's account.
+		///
+		/// The dispatch origin for this call must be the account of `public`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
+		pub fn claim(origin: OriginFor<T>, public: ecdsa::Public) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(T::SignerToAccountId::convert(public.clone()) == who, Error::<T>::NotSigner);
+
+			let address = AccountId20::from(public);
+			let amount = Claimable::<T>::take(address);
+			ensure!(!amount.is_zero(), Error::<T>::NothingToClaim);
+
+			T::Currency::deposit_creating(&who, amount);
+
+			Self::deposit_event(Event::Claimed { address, who, amount });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The recipient and amount of a deposit log: the last 20 bytes of the second topic, and
+		/// the first 32 bytes of the data.
+		fn decode_deposit(log: &proof::Log) -> Option<(AccountId20, BalanceOf<T>)> {
+			let recipient = log.topics.get(1)?;
+			// ABI-encoded addresses are left-padded with zeros.
+			if recipient[..12].iter().any(|byte| *byte != 0) {
+				return None
+			}
+			let amount = U256::from_big_endian(log.data.get(..32)?);
+			let amount = u128::try_from(amount).ok()?.try_into().ok()?;
+
+			Some((AccountId20(recipient[12..].try_into().ok()?), amount))
+		}
+	}
+}
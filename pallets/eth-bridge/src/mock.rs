@@ -0,0 +1,99 @@
+use crate as pallet_eth_bridge;
+use frame_support::traits::{ConstU128, ConstU16, ConstU32, ConstU64, GenesisBuild};
+use frame_system::{self as system, EnsureRoot};
+use sp_core::{ecdsa, H160, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, IdentifyAccount, IdentityLookup},
+	AccountId32, MultiSigner,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		EthBridge: pallet_eth_bridge,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u128;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+pub struct EcdsaAccount;
+impl Convert<ecdsa::Public, AccountId32> for EcdsaAccount {
+	fn convert(public: ecdsa::Public) -> AccountId32 {
+		MultiSigner::from(public).into_account()
+	}
+}
+
+impl pallet_eth_bridge::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type AdminOrigin = EnsureRoot<AccountId32>;
+	type SignerToAccountId = EcdsaAccount;
+	type MaxRelayers = ConstU32<4>;
+	type RequiredConfirmations = ConstU32<2>;
+}
+
+pub const ALICE: AccountId32 = AccountId32::new([1; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2; 32]);
+pub const CHARLIE: AccountId32 = AccountId32::new([3; 32]);
+
+// Build genesis storage according to the mock runtime, with Alice and Bob as relayers and
+// `deposit_event` as the deposit event.
+pub fn new_test_ext(deposit_event: Option<(H160, H256)>) -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&pallet_eth_bridge::GenesisConfig { relayers: vec![ALICE, BOB], deposit_event },
+		&mut storage,
+	)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,161 @@
+//! Decoding of Ethereum headers and receipts, and Merkle-Patricia proofs of inclusion.
+
+use account::rlp::Rlp;
+use sp_core::{H160, H256};
+use sp_io::hashing::keccak_256;
+use sp_std::prelude::*;
+
+/// The fields of an Ethereum header that receipts are proven against.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Header {
+	/// The keccak-256 hash of the RLP-encoded header, i.e. the block hash.
+	pub hash: H256,
+	/// The block number.
+	pub number: u64,
+	/// The root of the block's receipts trie.
+	pub receipts_root: H256,
+}
+
+impl Header {
+	/// Decode an RLP-encoded header of any fork, which all start with the 15 fields of the
+	/// original header.
+	pub fn decode(rlp: &[u8]) -> Option<Self> {
+		let fields = Rlp::new(rlp).ok()?.items().ok()?;
+		if fields.len() < 15 {
+			return None
+		}
+
+		Some(Self {
+			hash: keccak_256(rlp).into(),
+			number: fields[8].as_val().ok()?,
+			receipts_root: fields[5].as_val().ok()?,
+		})
+	}
+}
+
+/// A log of a transaction receipt.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Log {
+	/// The contract that emitted the log.
+	pub address: H160,
+	/// The topics, the first of which is the event signature for Solidity events.
+	pub topics: Vec<H256>,
+	/// The ABI-encoded unindexed parameters.
+	pub data: Vec<u8>,
+}
+
+/// Decode the logs of a receipt as stored in the receipts trie: the RLP list of a legacy
+/// receipt, or the type of a typed receipt followed by the list.
+pub fn decode_logs(receipt: &[u8]) -> Option<Vec<Log>> {
+	let receipt = match receipt.first()? {
+		// EIP-2718 transaction types range from 0 to 0x7f.
+		0..=0x7f => &receipt[1..],
+		_ => receipt,
+	};
+	let fields = Rlp::new(receipt).ok()?.items().ok()?;
+	// Status or post-state, cumulative gas used, logs bloom and logs.
+	if fields.len() != 4 {
+		return None
+	}
+
+	fields[3]
+		.items()
+		.ok()?
+		.iter()
+		.map(|log| {
+			let fields = log.items().ok()?;
+			if fields.len() != 3 {
+				return None
+			}
+			Some(Log {
+				address: fields[0].as_val().ok()?,
+				topics: fields[1].as_list().ok()?,
+				data: fields[2].as_val().ok()?,
+			})
+		})
+		.collect()
+}
+
+/// The value at `key` in the trie with `root`, if `proof` contains the nodes on the path to it.
+///
+/// The proof lists the nodes on the path from the root down. Nodes shorter than 32 bytes are
+/// embedded in their parents rather than listed.
+pub fn verify_proof(root: &H256, key: &[u8], proof: &[Vec<u8>]) -> Option<Vec<u8>> {
+	let nibbles: Vec<u8> = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect();
+	let mut path = &nibbles[..];
+	let mut proof = proof.iter();
+	let mut next = Child::Hash(*root);
+
+	loop {
+		let node = match next {
+			Child::Hash(hash) => {
+				let node = proof.next()?;
+				if keccak_256(node) != hash.0 {
+					return None
+				}
+				Rlp::new(node).ok()?
+			},
+			Child::Inline(node) => node,
+		};
+
+		let items = node.items().ok()?;
+		match items.len() {
+			// A branch: one child per nibble, and the value of the key ending here.
+			17 => match path.split_first() {
+				Some((nibble, rest)) => {
+					next = Child::decode(items[*nibble as usize])?;
+					path = rest;
+				},
+				None => return items[16].as_val::<Vec<u8>>().ok().filter(|value| !value.is_empty()),
+			},
+			// A leaf or an extension, with the hex-prefix encoded part of the path it covers.
+			2 => {
+				let (partial, is_leaf) = decode_path(items[0].data().ok()?)?;
+				path = path.strip_prefix(&partial[..])?;
+				if is_leaf {
+					if !path.is_empty() {
+						return None
+					}
+					return items[1].as_val().ok()
+				}
+				next = Child::decode(items[1])?;
+			},
+			_ => return None,
+		}
+	}
+}
+
+/// A reference to a trie node: its hash, or the node itself if it is shorter than a hash.
+enum Child<'a> {
+	Hash(H256),
+	Inline(Rlp<'a>),
+}
+
+impl<'a> Child<'a> {
+	/// `None` for an empty reference, i.e. a missing child.
+	fn decode(child: Rlp<'a>) -> Option<Self> {
+		if child.is_list() {
+			Some(Self::Inline(child))
+		} else {
+			child.as_val().ok().map(Self::Hash)
+		}
+	}
+}
+
+/// Decode a hex-prefix encoded path into its nibbles and whether it ends in a leaf.
+fn decode_path(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
+	let (flags, rest) = encoded.split_first()?;
+	let is_leaf = match flags >> 4 {
+		0 | 1 => false,
+		2 | 3 => true,
+		_ => return None,
+	};
+	let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+	// An odd number of nibbles starts in the flags byte.
+	if flags & 0x10 != 0 {
+		nibbles.push(flags & 0x0f);
+	}
+	nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+
+	Some((nibbles, is_leaf))
+}
@@ -0,0 +1,292 @@
+use crate::{mock::*, proof, Claimable, Confirmations, DepositEvent, Error, Event, ImportedHeader};
+use account::{rlp, AccountId20, EthereumPair};
+use frame_support::{assert_noop, assert_ok};
+use serde::Deserialize;
+use sp_core::{Bytes, H160, H256};
+use sp_runtime::{traits::IdentifyAccount, DispatchError, MultiSigner};
+
+/// A synthetic block in mainnet's formats whose transaction 17 deposits into WETH, generated by
+/// `fixtures/generate.py`. Its hashes and receipts are made up rather than taken from mainnet.
+#[derive(Deserialize)]
+struct Fixture {
+	header: Bytes,
+	hash: H256,
+	number: u64,
+	receipts_root: H256,
+	contract: H160,
+	topic: H256,
+	recipient: H160,
+	amount: String,
+	index: u32,
+	proof: Vec<Bytes>,
+	other_proof: Vec<Bytes>,
+}
+
+impl Fixture {
+	fn amount(&self) -> u128 {
+		self.amount.parse().unwrap()
+	}
+
+	fn proof(&self) -> Vec<Vec<u8>> {
+		self.proof.iter().map(|node| node.to_vec()).collect()
+	}
+}
+
+fn fixture() -> Fixture {
+	serde_json::from_str(include_str!("../fixtures/deposit.json")).unwrap()
+}
+
+/// A real mainnet block built by `fixtures/fetch.py`, which isn't checked in.
+fn mainnet_fixture() -> Fixture {
+	let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/mainnet.json");
+	let json = std::fs::read_to_string(path).unwrap_or_else(|e| {
+		panic!("{} is missing, build it with `fixtures/fetch.py`: {}", path, e)
+	});
+	serde_json::from_str(&json).unwrap()
+}
+
+/// The key of the fixture's recipient, the first development account of Hardhat and Anvil.
+fn recipient() -> EthereumPair {
+	EthereumPair::from_private_key(&hex_literal::hex!(
+		"ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+	))
+}
+
+/// Externalities with WETH deposits configured and the fixture's header imported.
+fn imported() -> (sp_io::TestExternalities, Fixture) {
+	let fixture = fixture();
+	let mut ext = new_test_ext(Some((fixture.contract, fixture.topic)));
+	ext.execute_with(|| {
+		for relayer in [ALICE, BOB] {
+			assert_ok!(EthBridge::submit_header(
+				RuntimeOrigin::signed(relayer),
+				fixture.header.to_vec()
+			));
+		}
+	});
+	(ext, fixture)
+}
+
+#[test]
+fn decodes_header() {
+	let fixture = fixture();
+	assert_eq!(
+		proof::Header::decode(&fixture.header),
+		Some(proof::Header {
+			hash: fixture.hash,
+			number: fixture.number,
+			receipts_root: fixture.receipts_root
+		})
+	);
+
+	let mut trailing = fixture.header.to_vec();
+	trailing.push(0);
+	assert_eq!(proof::Header::decode(&trailing), None);
+	assert_eq!(proof::Header::decode(&fixture.header[..100]), None);
+}
+
+#[test]
+fn verifies_receipt_proofs() {
+	let fixture = fixture();
+	let key = rlp::encode(&fixture.index);
+
+	let receipt = proof::verify_proof(&fixture.receipts_root, &key, &fixture.proof()).unwrap();
+	// An EIP-1559 receipt.
+	assert_eq!(receipt[0], 2);
+	assert_eq!(
+		proof::decode_logs(&receipt),
+		Some(vec![proof::Log {
+			address: fixture.contract,
+			topics: vec![fixture.topic, H256::from(fixture.recipient)],
+			data: [[0; 16], fixture.amount().to_be_bytes()].concat(),
+		}])
+	);
+
+	// The proof doesn't cover other keys.
+	assert_eq!(
+		proof::verify_proof(&fixture.receipts_root, &rlp::encode(&18u32), &fixture.proof()),
+		None
+	);
+	// Nor other roots.
+	assert_eq!(proof::verify_proof(&fixture.hash, &key, &fixture.proof()), None);
+	// Nor tampered nodes.
+	let mut tampered = fixture.proof();
+	let last = tampered.last_mut().unwrap();
+	let byte = last.len() - 1;
+	last[byte] ^= 1;
+	assert_eq!(proof::verify_proof(&fixture.receipts_root, &key, &tampered), None);
+	// Nor missing ones.
+	assert_eq!(proof::verify_proof(&fixture.receipts_root, &key, &fixture.proof()[..2]), None);
+}
+
+#[test]
+fn headers_are_imported_once_enough_relayers_submit_them() {
+	let fixture = fixture();
+	new_test_ext(None).execute_with(|| {
+		let submit = |relayer| {
+			EthBridge::submit_header(RuntimeOrigin::signed(relayer), fixture.header.to_vec())
+		};
+
+		assert_noop!(submit(CHARLIE), Error::<Test>::NotRelayer);
+
+		assert_ok!(submit(ALICE));
+		assert_eq!(EthBridge::header(fixture.hash), None);
+		assert_eq!(Confirmations::<Test>::get(fixture.hash).into_inner(), vec![ALICE]);
+		System::assert_last_event(
+			Event::HeaderConfirmed { hash: fixture.hash, relayer: ALICE }.into(),
+		);
+		assert_noop!(submit(ALICE), Error::<Test>::AlreadyConfirmed);
+
+		assert_ok!(submit(BOB));
+		assert_eq!(
+			EthBridge::header(fixture.hash),
+			Some(ImportedHeader { number: fixture.number, receipts_root: fixture.receipts_root })
+		);
+		assert!(!Confirmations::<Test>::contains_key(fixture.hash));
+		System::assert_last_event(
+			Event::HeaderImported { hash: fixture.hash, number: fixture.number }.into(),
+		);
+		assert_noop!(submit(ALICE), Error::<Test>::AlreadyImported);
+
+		assert_noop!(
+			EthBridge::submit_header(RuntimeOrigin::signed(ALICE), vec![0xc0]),
+			Error::<Test>::InvalidHeader
+		);
+	});
+}
+
+#[test]
+fn admin_manages_relayers_and_deposit_event() {
+	new_test_ext(None).execute_with(|| {
+		assert_noop!(
+			EthBridge::add_relayer(RuntimeOrigin::signed(ALICE), CHARLIE),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EthBridge::add_relayer(RuntimeOrigin::root(), ALICE),
+			Error::<Test>::AlreadyRelayer
+		);
+
+		assert_ok!(EthBridge::add_relayer(RuntimeOrigin::root(), CHARLIE));
+		assert_ok!(EthBridge::remove_relayer(RuntimeOrigin::root(), ALICE));
+		assert_eq!(EthBridge::relayers().into_inner(), vec![BOB, CHARLIE]);
+		assert_noop!(
+			EthBridge::remove_relayer(RuntimeOrigin::root(), ALICE),
+			Error::<Test>::NotRelayer
+		);
+
+		let (contract, topic) = (H160::repeat_byte(1), H256::repeat_byte(2));
+		assert_ok!(EthBridge::set_deposit_event(RuntimeOrigin::root(), contract, topic));
+		assert_eq!(DepositEvent::<Test>::get(), Some((contract, topic)));
+		System::assert_last_event(Event::DepositEventSet { contract, topic }.into());
+	});
+}
+
+#[test]
+fn proven_deposits_are_claimed_by_the_recipient() {
+	let (mut ext, fixture) = imported();
+	ext.execute_with(|| {
+		let recipient = recipient();
+		let address = AccountId20::from(fixture.recipient);
+		assert_eq!(recipient.address(), address);
+		let who = MultiSigner::from(recipient.public()).into_account();
+
+		assert_ok!(EthBridge::prove_deposit(
+			RuntimeOrigin::signed(CHARLIE),
+			fixture.hash,
+			fixture.index,
+			fixture.proof()
+		));
+		assert_eq!(EthBridge::claimable(address), fixture.amount());
+		System::assert_last_event(
+			Event::Deposited {
+				address,
+				amount: fixture.amount(),
+				hash: fixture.hash,
+				index: fixture.index,
+			}
+			.into(),
+		);
+		assert_noop!(
+			EthBridge::prove_deposit(
+				RuntimeOrigin::signed(CHARLIE),
+				fixture.hash,
+				fixture.index,
+				fixture.proof()
+			),
+			Error::<Test>::AlreadyProcessed
+		);
+
+		// Only the owner of the address claims its deposits.
+		assert_noop!(
+			EthBridge::claim(RuntimeOrigin::signed(CHARLIE), recipient.public()),
+			Error::<Test>::NotSigner
+		);
+		assert_ok!(EthBridge::claim(RuntimeOrigin::signed(who.clone()), recipient.public()));
+		assert_eq!(Balances::free_balance(&who), fixture.amount());
+		assert!(!Claimable::<Test>::contains_key(address));
+		System::assert_last_event(
+			Event::Claimed { address, who: who.clone(), amount: fixture.amount() }.into(),
+		);
+		assert_noop!(
+			EthBridge::claim(RuntimeOrigin::signed(who), recipient.public()),
+			Error::<Test>::NothingToClaim
+		);
+	});
+}
+
+#[test]
+fn deposits_require_the_configured_event_of_an_imported_block() {
+	let (mut ext, fixture) = imported();
+	ext.execute_with(|| {
+		let prove = |hash, index, proof: &[Bytes]| {
+			EthBridge::prove_deposit(
+				RuntimeOrigin::signed(CHARLIE),
+				hash,
+				index,
+				proof.iter().map(|node| node.to_vec()).collect(),
+			)
+		};
+
+		// Transaction 18 emits the deposit event from another contract.
+		assert_noop!(prove(fixture.hash, 18, &fixture.other_proof), Error::<Test>::NoDeposit);
+		assert_noop!(prove(fixture.hash, 18, &fixture.proof), Error::<Test>::InvalidProof);
+		assert_noop!(
+			prove(H256::repeat_byte(1), fixture.index, &fixture.proof),
+			Error::<Test>::UnknownHeader
+		);
+
+		DepositEvent::<Test>::kill();
+		assert_noop!(
+			prove(fixture.hash, fixture.index, &fixture.proof),
+			Error::<Test>::NoDepositEvent
+		);
+	});
+}
+
+#[test]
+#[ignore = "needs `fixtures/mainnet.json`, which `fixtures/fetch.py` builds from an Ethereum node"]
+fn proves_mainnet_deposits() {
+	let fixture = mainnet_fixture();
+	assert_eq!(
+		proof::Header::decode(&fixture.header).map(|header| header.hash),
+		Some(fixture.hash)
+	);
+
+	new_test_ext(Some((fixture.contract, fixture.topic))).execute_with(|| {
+		for relayer in [ALICE, BOB] {
+			assert_ok!(EthBridge::submit_header(
+				RuntimeOrigin::signed(relayer),
+				fixture.header.to_vec()
+			));
+		}
+		assert_ok!(EthBridge::prove_deposit(
+			RuntimeOrigin::signed(CHARLIE),
+			fixture.hash,
+			fixture.index,
+			fixture.proof()
+		));
+		let address = AccountId20::from(fixture.recipient);
+		assert_eq!(EthBridge::claimable(address), fixture.amount());
+	});
+}
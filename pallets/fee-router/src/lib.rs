@@ -0,0 +1,230 @@
+//! # Fee Router Pallet
+//!
+//! Pays transaction fees and tips out instead of burning them. [`DealWithFees`] is the
+//! `OnUnbalanced` handler of `pallet_transaction_payment::CurrencyAdapter`: it gives the block
+//! author [`FeeAuthorShare`] of every fee and [`TipAuthorShare`] of every tip, and the treasury
+//! account the rest. The author is taken from the pre-runtime digests of the block, e.g. Aura's;
+//! when there is none, everything goes to the treasury. [`ToTreasury`] credits any other imbalance,
+//! such as dust removed by `pallet_balances`, to the treasury as well.
+//!
+//! Both shares are set by [`Config::SharesOrigin`] through [`Pallet::set_shares`].
+//!
+//! Credits below the existential deposit would be burnt if the treasury account didn't exist, so
+//! it is endowed with the existential deposit at genesis, or by the runtime upgrade that brings
+//! the pallet's storage to version 1 on chains that started without it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use frame_support::traits::{Currency, Get, Imbalance, OnUnbalanced};
+use sp_runtime::{traits::Zero, Perbill};
+
+/// Balance of the [`Config::Currency`].
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+/// Imbalance of the [`Config::Currency`] that fees and tips are withdrawn as.
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::BalanceOf;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, FindAuthor},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::Perbill;
+
+	/// The storage version, 1 once the treasury account is endowed.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency fees are paid in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// Finds the author of the current block in its pre-runtime digests.
+		type FindAuthor: FindAuthor<Self::AccountId>;
+
+		/// The account receiving everything that doesn't go to the block author.
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The origin allowed to change the shares.
+		type SharesOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+	}
+
+	/// Share of transaction fees paid to the block author.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_author_share)]
+	pub type FeeAuthorShare<T> = StorageValue<_, Perbill, ValueQuery>;
+
+	/// Share of tips paid to the block author.
+	#[pallet::storage]
+	#[pallet::getter(fn tip_author_share)]
+	pub type TipAuthorShare<T> = StorageValue<_, Perbill, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		pub fee_author_share: Perbill,
+		pub tip_author_share: Perbill,
+	}
+
+	/// A fifth of the fees and all tips to the block author.
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self {
+				fee_author_share: Perbill::from_percent(20),
+				tip_author_share: Perbill::from_percent(100),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			FeeAuthorShare::<T>::put(self.fee_author_share);
+			TipAuthorShare::<T>::put(self.tip_author_share);
+			Pallet::<T>::endow_treasury();
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Self>() >= STORAGE_VERSION {
+				return T::DbWeight::get().reads(1)
+			}
+
+			Self::endow_treasury();
+			STORAGE_VERSION.put::<Self>();
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The shares of fees and tips paid to block authors changed.
+		SharesSet { fee_author_share: Perbill, tip_author_share: Perbill },
+		/// The fees and tips of a transaction were paid out.
+		FeesDistributed {
+			author: Option<T::AccountId>,
+			to_author: BalanceOf<T>,
+			to_treasury: BalanceOf<T>,
+		},
+		/// An imbalance other than fees, e.g. removed dust, was credited to the treasury.
+		TreasuryCredited { amount: BalanceOf<T> },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the shares of fees and tips paid to block authors.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn set_shares(
+			origin: OriginFor<T>,
+			fee_author_share: Perbill,
+			tip_author_share: Perbill,
+		) -> DispatchResult {
+			T::SharesOrigin::ensure_origin(origin)?;
+
+			FeeAuthorShare::<T>::put(fee_author_share);
+			TipAuthorShare::<T>::put(tip_author_share);
+
+			Self::deposit_event(Event::SharesSet { fee_author_share, tip_author_share });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Give the treasury account the existential deposit unless it has that much already.
+		pub fn endow_treasury() {
+			let treasury = T::TreasuryAccount::get();
+			let minimum = T::Currency::minimum_balance();
+			if T::Currency::free_balance(&treasury) < minimum {
+				let _ = T::Currency::make_free_balance_be(&treasury, minimum);
+			}
+		}
+
+		/// Author of the current block, if its digests name one.
+		pub fn author() -> Option<T::AccountId> {
+			let digest = frame_system::Pallet::<T>::digest();
+			T::FindAuthor::find_author(digest.logs().iter().filter_map(|d| d.as_pre_runtime()))
+		}
+	}
+}
+
+/// Pays fees and tips to the block author and the treasury, see the [crate docs](crate).
+pub struct DealWithFees<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for DealWithFees<T> {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalanceOf<T>>) {
+		let fees = match fees_then_tips.next() {
+			Some(fees) => fees,
+			None => return,
+		};
+		let split = |imbalance: NegativeImbalanceOf<T>, share: Perbill| {
+			let to_author = share * imbalance.peek();
+			imbalance.split(to_author)
+		};
+
+		let (mut to_author, mut to_treasury) = split(fees, FeeAuthorShare::<T>::get());
+		if let Some(tips) = fees_then_tips.next() {
+			let (tips_to_author, tips_to_treasury) = split(tips, TipAuthorShare::<T>::get());
+			to_author.subsume(tips_to_author);
+			to_treasury.subsume(tips_to_treasury);
+		}
+
+		let author = Pallet::<T>::author();
+		let to_author = match &author {
+			Some(author) => {
+				let amount = to_author.peek();
+				T::Currency::resolve_creating(author, to_author);
+				amount
+			},
+			None => {
+				to_treasury.subsume(to_author);
+				Zero::zero()
+			},
+		};
+		let amount = to_treasury.peek();
+		T::Currency::resolve_creating(&T::TreasuryAccount::get(), to_treasury);
+
+		Pallet::<T>::deposit_event(Event::FeesDistributed {
+			author,
+			to_author,
+			to_treasury: amount,
+		});
+	}
+}
+
+/// Credits imbalances to the treasury account.
+pub struct ToTreasury<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for ToTreasury<T> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
+		let credited = amount.peek();
+		T::Currency::resolve_creating(&T::TreasuryAccount::get(), amount);
+
+		Pallet::<T>::deposit_event(Event::TreasuryCredited { amount: credited });
+	}
+}
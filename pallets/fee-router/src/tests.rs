@@ -0,0 +1,131 @@
+use crate::{mock::*, DealWithFees, Event, ToTreasury};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, ExistenceRequirement, OnRuntimeUpgrade, OnUnbalanced, StorageVersion},
+};
+use sp_runtime::{DigestItem, DispatchError, Perbill};
+
+const AUTHOR: u64 = 7;
+
+fn author_block() {
+	System::deposit_log(DigestItem::PreRuntime(ENGINE_ID, AUTHOR.encode()));
+}
+
+fn pay(fees: u64, tips: u64) {
+	DealWithFees::<Test>::on_unbalanceds::<u64>(
+		vec![Balances::issue(fees), Balances::issue(tips)].into_iter(),
+	);
+}
+
+#[test]
+fn splits_fees_and_tips_between_author_and_treasury() {
+	new_test_ext().execute_with(|| {
+		author_block();
+
+		pay(1000, 100);
+
+		// A fifth of the fees and all tips.
+		assert_eq!(Balances::free_balance(AUTHOR), 300);
+		// The treasury was endowed with the existential deposit of 10 at genesis.
+		assert_eq!(Balances::free_balance(TREASURY), 810);
+		assert_eq!(Balances::total_issuance(), 1110);
+		System::assert_last_event(
+			Event::FeesDistributed { author: Some(AUTHOR), to_author: 300, to_treasury: 800 }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn treasury_gets_everything_without_author() {
+	new_test_ext().execute_with(|| {
+		pay(1000, 100);
+
+		assert_eq!(Balances::free_balance(TREASURY), 1110);
+		assert_eq!(Balances::total_issuance(), 1110);
+		System::assert_last_event(
+			Event::FeesDistributed { author: None, to_author: 0, to_treasury: 1100 }.into(),
+		);
+	});
+}
+
+#[test]
+fn root_sets_shares() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			FeeRouter::set_shares(RuntimeOrigin::signed(AUTHOR), Perbill::one(), Perbill::one()),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(FeeRouter::set_shares(
+			RuntimeOrigin::root(),
+			Perbill::from_percent(50),
+			Perbill::from_percent(10)
+		));
+		System::assert_last_event(
+			Event::SharesSet {
+				fee_author_share: Perbill::from_percent(50),
+				tip_author_share: Perbill::from_percent(10),
+			}
+			.into(),
+		);
+		author_block();
+
+		pay(1000, 100);
+
+		assert_eq!(Balances::free_balance(AUTHOR), 510);
+		assert_eq!(Balances::free_balance(TREASURY), 600);
+	});
+}
+
+#[test]
+fn treasury_keeps_fees_below_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Balances::free_balance(TREASURY), 10);
+
+		pay(3, 1);
+
+		assert_eq!(Balances::free_balance(TREASURY), 14);
+		assert_eq!(Balances::total_issuance(), 14);
+	});
+}
+
+#[test]
+fn upgrade_endows_treasury_once() {
+	new_test_ext().execute_with(|| {
+		// A chain that started before the treasury was endowed.
+		Balances::make_free_balance_be(&TREASURY, 0);
+		StorageVersion::new(0).put::<FeeRouter>();
+
+		FeeRouter::on_runtime_upgrade();
+		assert_eq!(Balances::free_balance(TREASURY), 10);
+		assert_eq!(StorageVersion::get::<FeeRouter>(), 1);
+
+		Balances::make_free_balance_be(&TREASURY, 0);
+		FeeRouter::on_runtime_upgrade();
+		assert_eq!(Balances::free_balance(TREASURY), 0);
+	});
+}
+
+#[test]
+fn dust_goes_to_treasury() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&TREASURY, 100);
+		Balances::make_free_balance_be(&1, 100);
+
+		// Leaves 5 behind, below the existential deposit of 10.
+		assert_ok!(<Balances as Currency<u64>>::transfer(
+			&1,
+			&2,
+			95,
+			ExistenceRequirement::AllowDeath
+		));
+
+		assert_eq!(Balances::free_balance(TREASURY), 105);
+		assert_eq!(Balances::total_issuance(), 200);
+		System::assert_has_event(Event::TreasuryCredited { amount: 5 }.into());
+
+		ToTreasury::<Test>::on_unbalanced(Balances::issue(50));
+		assert_eq!(Balances::free_balance(TREASURY), 155);
+	});
+}
@@ -0,0 +1,222 @@
+//! # Maintenance Pallet
+//!
+//! Pauses calls without a runtime upgrade. [`Config::MaintenanceOrigin`] either puts the whole
+//! chain into maintenance mode, which pauses every call, or pauses whole pallets and single calls,
+//! which are identified by their names in the call metadata.
+//!
+//! The pallet is the runtime's `BaseCallFilter`: it implements `Contains` for the runtime call,
+//! admitting every call that isn't paused. Its own calls and [`Config::WhitelistedCalls`] are never
+//! paused, so that the chain can always leave maintenance. Calls of inherents must be whitelisted,
+//! or blocks can't be authored during maintenance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::{
+	dispatch::{CallMetadata, GetCallMetadata},
+	traits::{Contains, PalletInfoAccess},
+	BoundedVec,
+};
+use sp_std::prelude::*;
+
+/// Name of a pallet or call, as in [`CallMetadata`].
+pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::NameOf;
+	use frame_support::{dispatch::GetCallMetadata, pallet_prelude::*, traits::Contains};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The overarching call type, whose calls can be paused.
+		type RuntimeCall: GetCallMetadata;
+
+		/// Calls that are never paused, besides the ones of this pallet.
+		type WhitelistedCalls: Contains<<Self as Config>::RuntimeCall>;
+
+		/// The origin allowed to pause and unpause calls.
+		type MaintenanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Maximum length of pallet and call names.
+		#[pallet::constant]
+		type MaxNameLen: Get<u32>;
+	}
+
+	/// Whether every call but the whitelisted ones is paused.
+	#[pallet::storage]
+	#[pallet::getter(fn maintenance_mode)]
+	pub type MaintenanceMode<T> = StorageValue<_, bool, ValueQuery>;
+
+	/// Pallets all of whose calls are paused.
+	#[pallet::storage]
+	pub type PausedPallets<T: Config> = StorageMap<_, Blake2_128Concat, NameOf<T>, ()>;
+
+	/// Paused calls, by pallet and call name.
+	#[pallet::storage]
+	pub type PausedCalls<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, NameOf<T>, Blake2_128Concat, NameOf<T>, ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The chain entered maintenance mode.
+		MaintenanceModeEntered,
+		/// The chain left maintenance mode.
+		MaintenanceModeExited,
+		/// All calls of `pallet` were paused.
+		PalletPaused { pallet: NameOf<T> },
+		/// The calls of `pallet` were unpaused, except for the ones paused one by one.
+		PalletUnpaused { pallet: NameOf<T> },
+		/// The call `call` of `pallet` was paused.
+		CallPaused { pallet: NameOf<T>, call: NameOf<T> },
+		/// The call `call` of `pallet` was unpaused.
+		CallUnpaused { pallet: NameOf<T>, call: NameOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The chain is already in maintenance mode.
+		AlreadyInMaintenance,
+		/// The chain is not in maintenance mode.
+		NotInMaintenance,
+		/// The pallet or call is already paused.
+		AlreadyPaused,
+		/// The pallet or call is not paused.
+		NotPaused,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause every call but the whitelisted ones.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn enter_maintenance(origin: OriginFor<T>) -> DispatchResult {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(!MaintenanceMode::<T>::get(), Error::<T>::AlreadyInMaintenance);
+
+			MaintenanceMode::<T>::put(true);
+
+			Self::deposit_event(Event::MaintenanceModeEntered);
+			Ok(())
+		}
+
+		/// Leave maintenance mode. Paused pallets and calls stay paused.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn exit_maintenance(origin: OriginFor<T>) -> DispatchResult {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(MaintenanceMode::<T>::get(), Error::<T>::NotInMaintenance);
+
+			MaintenanceMode::<T>::kill();
+
+			Self::deposit_event(Event::MaintenanceModeExited);
+			Ok(())
+		}
+
+		/// Pause all calls of `pallet`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn pause_pallet(origin: OriginFor<T>, pallet: NameOf<T>) -> DispatchResult {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(!PausedPallets::<T>::contains_key(&pallet), Error::<T>::AlreadyPaused);
+
+			PausedPallets::<T>::insert(&pallet, ());
+
+			Self::deposit_event(Event::PalletPaused { pallet });
+			Ok(())
+		}
+
+		/// Unpause the calls of `pallet` paused by [`Pallet::pause_pallet`].
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn unpause_pallet(origin: OriginFor<T>, pallet: NameOf<T>) -> DispatchResult {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(PausedPallets::<T>::contains_key(&pallet), Error::<T>::NotPaused);
+
+			PausedPallets::<T>::remove(&pallet);
+
+			Self::deposit_event(Event::PalletUnpaused { pallet });
+			Ok(())
+		}
+
+		/// Pause the call `call` of `pallet`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn pause_call(
+			origin: OriginFor<T>,
+			pallet: NameOf<T>,
+			call: NameOf<T>,
+		) -> DispatchResult {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(!PausedCalls::<T>::contains_key(&pallet, &call), Error::<T>::AlreadyPaused);
+
+			PausedCalls::<T>::insert(&pallet, &call, ());
+
+			Self::deposit_event(Event::CallPaused { pallet, call });
+			Ok(())
+		}
+
+		/// Unpause the call `call` of `pallet`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn unpause_call(
+			origin: OriginFor<T>,
+			pallet: NameOf<T>,
+			call: NameOf<T>,
+		) -> DispatchResult {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(PausedCalls::<T>::contains_key(&pallet, &call), Error::<T>::NotPaused);
+
+			PausedCalls::<T>::remove(&pallet, &call);
+
+			Self::deposit_event(Event::CallUnpaused { pallet, call });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether the call `call` of `pallet` is paused, ignoring maintenance mode and the whitelist.
+	pub fn is_paused(pallet: &str, call: &str) -> bool {
+		let name = |name: &str| NameOf::<T>::try_from(name.as_bytes().to_vec()).ok();
+
+		// Names too long to store can't have been paused.
+		match (name(pallet), name(call)) {
+			(Some(pallet), _) if PausedPallets::<T>::contains_key(&pallet) => true,
+			(Some(pallet), Some(call)) => PausedCalls::<T>::contains_key(pallet, call),
+			_ => false,
+		}
+	}
+
+	/// The paused pallets, with no call name, and the paused calls, by pallet and call name.
+	pub fn paused() -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+		PausedPallets::<T>::iter_keys()
+			.map(|pallet| (pallet.into_inner(), None))
+			.chain(
+				PausedCalls::<T>::iter_keys()
+					.map(|(pallet, call)| (pallet.into_inner(), Some(call.into_inner()))),
+			)
+			.collect()
+	}
+}
+
+impl<T: Config> Contains<<T as Config>::RuntimeCall> for Pallet<T> {
+	fn contains(call: &<T as Config>::RuntimeCall) -> bool {
+		let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+		if pallet_name == <Self as PalletInfoAccess>::name() || T::WhitelistedCalls::contains(call)
+		{
+			return true
+		}
+		!MaintenanceMode::<T>::get() && !Self::is_paused(pallet_name, function_name)
+	}
+}
@@ -0,0 +1,120 @@
+use crate::{mock::*, Error, Event, NameOf};
+use frame_support::{assert_noop, assert_ok, dispatch::Dispatchable, traits::Contains};
+use sp_runtime::DispatchError;
+
+fn name(name: &str) -> NameOf<Test> {
+	name.as_bytes().to_vec().try_into().unwrap()
+}
+
+fn remark() -> RuntimeCall {
+	frame_system::Call::remark { remark: vec![] }.into()
+}
+
+fn set_heap_pages() -> RuntimeCall {
+	frame_system::Call::set_heap_pages { pages: 1 }.into()
+}
+
+fn remark_with_event() -> RuntimeCall {
+	frame_system::Call::remark_with_event { remark: vec![] }.into()
+}
+
+#[test]
+fn maintenance_mode_pauses_all_but_whitelisted_calls() {
+	new_test_ext().execute_with(|| {
+		assert!(Maintenance::contains(&remark()));
+
+		assert_ok!(Maintenance::enter_maintenance(RuntimeOrigin::root()));
+		System::assert_last_event(Event::MaintenanceModeEntered.into());
+		assert!(Maintenance::maintenance_mode());
+		assert!(!Maintenance::contains(&remark()));
+		assert!(Maintenance::contains(&remark_with_event()));
+		assert!(Maintenance::contains(&crate::Call::exit_maintenance {}.into()));
+		assert_noop!(
+			Maintenance::enter_maintenance(RuntimeOrigin::root()),
+			Error::<Test>::AlreadyInMaintenance
+		);
+
+		assert_ok!(Maintenance::exit_maintenance(RuntimeOrigin::root()));
+		System::assert_last_event(Event::MaintenanceModeExited.into());
+		assert!(Maintenance::contains(&remark()));
+		assert_noop!(
+			Maintenance::exit_maintenance(RuntimeOrigin::root()),
+			Error::<Test>::NotInMaintenance
+		);
+	});
+}
+
+#[test]
+fn pauses_pallets_and_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Maintenance::pause_call(RuntimeOrigin::root(), name("System"), name("remark")));
+		System::assert_last_event(
+			Event::CallPaused { pallet: name("System"), call: name("remark") }.into(),
+		);
+		assert!(!Maintenance::contains(&remark()));
+		assert!(Maintenance::contains(&set_heap_pages()));
+
+		assert_ok!(Maintenance::pause_pallet(RuntimeOrigin::root(), name("System")));
+		assert!(!Maintenance::contains(&set_heap_pages()));
+		assert!(Maintenance::contains(&remark_with_event()));
+		assert_eq!(
+			Maintenance::paused(),
+			vec![(b"System".to_vec(), None), (b"System".to_vec(), Some(b"remark".to_vec()))]
+		);
+
+		// Calls paused one by one stay paused with their pallet unpaused.
+		assert_ok!(Maintenance::unpause_pallet(RuntimeOrigin::root(), name("System")));
+		System::assert_last_event(Event::PalletUnpaused { pallet: name("System") }.into());
+		assert!(Maintenance::contains(&set_heap_pages()));
+		assert!(!Maintenance::contains(&remark()));
+
+		assert_ok!(Maintenance::unpause_call(
+			RuntimeOrigin::root(),
+			name("System"),
+			name("remark")
+		));
+		assert!(Maintenance::contains(&remark()));
+		assert!(Maintenance::paused().is_empty());
+	});
+}
+
+#[test]
+fn paused_calls_are_filtered_on_dispatch() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Maintenance::pause_call(RuntimeOrigin::root(), name("System"), name("remark")));
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		assert_ok!(set_heap_pages().dispatch(RuntimeOrigin::root()));
+	});
+}
+
+#[test]
+fn rejects_redundant_changes_and_other_origins() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Maintenance::enter_maintenance(RuntimeOrigin::signed(1)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Maintenance::pause_pallet(RuntimeOrigin::signed(1), name("System")),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Maintenance::unpause_pallet(RuntimeOrigin::root(), name("System")),
+			Error::<Test>::NotPaused
+		);
+		assert_noop!(
+			Maintenance::unpause_call(RuntimeOrigin::root(), name("System"), name("remark")),
+			Error::<Test>::NotPaused
+		);
+
+		assert_ok!(Maintenance::pause_pallet(RuntimeOrigin::root(), name("System")));
+		assert_noop!(
+			Maintenance::pause_pallet(RuntimeOrigin::root(), name("System")),
+			Error::<Test>::AlreadyPaused
+		);
+	});
+}
@@ -0,0 +1,47 @@
+//! Benchmarking setup for pallet-relayer
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Relayer;
+use codec::Encode;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_core::{ecdsa, H160};
+use sp_runtime::traits::Convert;
+use sp_std::{boxed::Box, vec};
+
+benchmarks! {
+	relay {
+		let n in 0 .. 16 * 1024;
+		let caller: T::AccountId = whitelisted_caller();
+		let call: <T as Config>::RuntimeCall =
+			frame_system::Call::<T>::remark { remark: vec![0; n as usize] }.into();
+
+		let secret = libsecp256k1::SecretKey::parse(&[1; 32]).unwrap();
+		let public = libsecp256k1::PublicKey::from_secret_key(&secret);
+		let mut uncompressed = [0; 64];
+		uncompressed.copy_from_slice(&public.serialize()[1..]);
+		let signer: H160 = account::AccountId20::from_uncompressed(&uncompressed).into();
+		let public = ecdsa::Public::from_raw(public.serialize_compressed());
+		let account = T::SignerToAccountId::convert(public.clone());
+		T::SignerFilter::admit_for_benchmark(&public);
+
+		let deadline = frame_system::Pallet::<T>::block_number();
+		let hash = Relayer::<T>::signing_hash(&call.encode(), 0, deadline);
+		let (signature, recovery_id) =
+			libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), &secret);
+		let mut rsv = [0; 65];
+		rsv[..64].copy_from_slice(&signature.serialize());
+		rsv[64] = recovery_id.serialize();
+	}: _(RawOrigin::Signed(caller), Box::new(call), signer, 0, deadline, rsv)
+	verify {
+		assert_eq!(Nonces::<T>::get(signer), 1);
+		// The signature was recovered and the call dispatched, so both are part of the measurement.
+		frame_system::Pallet::<T>::assert_last_event(
+			Event::<T>::Relayed { signer, account, nonce: 0, result: Ok(()) }.into(),
+		);
+	}
+
+	impl_benchmark_test_suite!(Relayer, crate::mock::new_test_ext(), crate::mock::Test);
+}
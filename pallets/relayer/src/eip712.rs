@@ -0,0 +1,60 @@
+//! EIP-712 hashing of relayed calls.
+//!
+//! Signers sign the typed-data hash of
+//!
+//! ```text
+//! EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)
+//! RelayedCall(bytes call,uint256 nonce,uint256 deadline)
+//! ```
+//!
+//! where `name` is [`DOMAIN_NAME`], `version` is [`DOMAIN_VERSION`], `salt` is the genesis hash of
+//! the chain, `call` is the SCALE-encoded runtime call and `deadline` is a block number. Wallets
+//! supporting `eth_signTypedData_v4` can produce these signatures directly.
+
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
+use sp_std::vec::Vec;
+
+/// `name` of the EIP-712 domain.
+pub const DOMAIN_NAME: &str = "Substrate Relayer";
+/// `version` of the EIP-712 domain.
+pub const DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)";
+const RELAYED_CALL_TYPE: &str = "RelayedCall(bytes call,uint256 nonce,uint256 deadline)";
+
+fn uint256(value: u64) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[24..].copy_from_slice(&value.to_be_bytes());
+	word
+}
+
+/// `hashStruct` of the EIP-712 domain of the chain with `chain_id` and `genesis_hash`.
+pub fn domain_separator(chain_id: u64, genesis_hash: H256) -> [u8; 32] {
+	let mut encoded = Vec::with_capacity(5 * 32);
+	encoded.extend_from_slice(&keccak_256(DOMAIN_TYPE.as_bytes()));
+	encoded.extend_from_slice(&keccak_256(DOMAIN_NAME.as_bytes()));
+	encoded.extend_from_slice(&keccak_256(DOMAIN_VERSION.as_bytes()));
+	encoded.extend_from_slice(&uint256(chain_id));
+	encoded.extend_from_slice(genesis_hash.as_bytes());
+	keccak_256(&encoded)
+}
+
+/// `hashStruct` of a `RelayedCall`.
+pub fn relayed_call_hash(call: &[u8], nonce: u64, deadline: u64) -> [u8; 32] {
+	let mut encoded = Vec::with_capacity(4 * 32);
+	encoded.extend_from_slice(&keccak_256(RELAYED_CALL_TYPE.as_bytes()));
+	encoded.extend_from_slice(&keccak_256(call));
+	encoded.extend_from_slice(&uint256(nonce));
+	encoded.extend_from_slice(&uint256(deadline));
+	keccak_256(&encoded)
+}
+
+/// The hash that gets signed: `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+pub fn signing_hash(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+	let mut encoded = [0u8; 66];
+	encoded[..2].copy_from_slice(b"\x19\x01");
+	encoded[2..34].copy_from_slice(domain_separator);
+	encoded[34..].copy_from_slice(struct_hash);
+	keccak_256(&encoded)
+}
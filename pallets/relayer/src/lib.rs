@@ -0,0 +1,207 @@
+//! # Relayer Pallet
+//!
+//! Gasless meta-transactions: a user signs a call off-chain with their Ethereum key, and a funded
+//! relayer submits it through [`Pallet::relay`] and pays the transaction fee. The pallet checks the
+//! user's EIP-712 signature (see [`eip712`]) over the call, their nonce and a deadline, and then
+//! dispatches the call with the user's signed origin.
+//!
+//! Users never need a balance, as long as the relayed call itself doesn't cost them anything.
+//!
+//! Relaying bypasses the runtime's signed extensions, so checks they apply to signers, like an
+//! allowlist, have to be repeated in [`Config::SignerFilter`], and so does bookkeeping, like
+//! linking signers' addresses and accounts, in [`SignerFilter::admitted`].
+//!
+//! ## Replay protection
+//!
+//! Every signer has a nonce in [`Nonces`] that must match the signed one and is incremented once
+//! the call is dispatched, whether the call itself succeeds or not. Signatures also commit to the
+//! chain's genesis hash and [`Config::ChainId`], and expire after the signed deadline block.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod eip712;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+use frame_support::weights::Weight;
+use sp_core::ecdsa;
+
+/// Decides whose calls may be relayed.
+pub trait SignerFilter<AccountId, Call> {
+	/// Whether `account`, the account of the secp256k1 key `public`, may have `call` dispatched.
+	fn admit(public: &ecdsa::Public, account: &AccountId, call: &Call) -> bool;
+
+	/// Note that a call of `account`, the account of `public`, is about to be dispatched, returning
+	/// the weight of doing so.
+	fn admitted(_public: &ecdsa::Public, _account: &AccountId) -> Weight {
+		Weight::zero()
+	}
+
+	/// Have `public` admitted, so that benchmarks can relay its calls.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn admit_for_benchmark(_public: &ecdsa::Public) {}
+}
+
+/// Admits every signer.
+impl<AccountId, Call> SignerFilter<AccountId, Call> for () {
+	fn admit(_: &ecdsa::Public, _: &AccountId, _: &Call) -> bool {
+		true
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::{eip712, SignerFilter, WeightInfo};
+	use frame_support::{
+		dispatch::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo},
+		pallet_prelude::*,
+		traits::Contains,
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_core::{ecdsa, H160, H256};
+	use sp_runtime::traits::{Convert, Dispatchable, UniqueSaturatedInto, Zero};
+	use sp_std::prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config<Hash = H256> {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The overarching call type.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>;
+
+		/// Calls that may be relayed.
+		type CallFilter: Contains<<Self as Config>::RuntimeCall>;
+
+		/// The account controlled by a secp256k1 public key.
+		type SignerToAccountId: Convert<ecdsa::Public, Self::AccountId>;
+
+		/// Signers who may have calls relayed.
+		type SignerFilter: SignerFilter<Self::AccountId, <Self as Config>::RuntimeCall>;
+
+		/// `chainId` of the EIP-712 domain.
+		#[pallet::constant]
+		type ChainId: Get<u64>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Next nonce of every signer that has had a call relayed.
+	#[pallet::storage]
+	#[pallet::getter(fn nonce)]
+	pub type Nonces<T> = StorageMap<_, Blake2_128Concat, H160, u64, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A call signed by `signer` was dispatched with the origin of `account`.
+		Relayed { signer: H160, account: T::AccountId, nonce: u64, result: DispatchResult },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The deadline block of the signature has passed.
+		DeadlinePassed,
+		/// The nonce is not the signer's next one.
+		InvalidNonce,
+		/// The signature is not the signer's signature of the call, nonce and deadline.
+		InvalidSignature,
+		/// The call may not be relayed.
+		CallFiltered,
+		/// The signer may not have the call relayed.
+		SignerFiltered,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Dispatch `call` on behalf of `signer`, who signed it with `nonce` and `deadline`.
+		///
+		/// The origin pays the fee. `signature` is the 65-byte `r ‖ s ‖ v` EIP-712 signature of a
+		/// `RelayedCall` holding the SCALE encoding of `call`.
+		#[pallet::weight({
+			let info = call.get_dispatch_info();
+			(
+				T::WeightInfo::relay(call.encoded_size() as u32).saturating_add(info.weight),
+				info.class,
+			)
+		})]
+		pub fn relay(
+			origin: OriginFor<T>,
+			call: Box<<T as Config>::RuntimeCall>,
+			signer: H160,
+			nonce: u64,
+			deadline: T::BlockNumber,
+			signature: [u8; 65],
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::DeadlinePassed
+			);
+			ensure!(T::CallFilter::contains(&call), Error::<T>::CallFiltered);
+			ensure!(Nonces::<T>::get(signer) == nonce, Error::<T>::InvalidNonce);
+
+			let encoded_call = call.encode();
+			let hash = Self::signing_hash(&encoded_call, nonce, deadline);
+			let public = match account::ecrecover(&signature, &hash) {
+				Some((address, public)) if H160::from(address) == signer => public,
+				_ => return Err(Error::<T>::InvalidSignature.into()),
+			};
+
+			let account = T::SignerToAccountId::convert(public.clone());
+			ensure!(T::SignerFilter::admit(&public, &account, &call), Error::<T>::SignerFiltered);
+
+			Nonces::<T>::insert(signer, nonce.saturating_add(1));
+			let weight = T::SignerFilter::admitted(&public, &account);
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				weight,
+				DispatchClass::Normal,
+			);
+
+			let info = call.get_dispatch_info();
+			let result = (*call).dispatch(frame_system::RawOrigin::Signed(account.clone()).into());
+
+			Self::deposit_event(Event::Relayed {
+				signer,
+				account,
+				nonce,
+				result: result.as_ref().map(|_| ()).map_err(|e| e.error),
+			});
+
+			let overhead = T::WeightInfo::relay(encoded_call.len() as u32);
+			Ok(Some(overhead.saturating_add(extract_actual_weight(&result, &info))).into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The EIP-712 hash `signer` has to sign to have the SCALE-encoded `call` relayed with
+		/// `nonce` before `deadline`.
+		pub fn signing_hash(call: &[u8], nonce: u64, deadline: T::BlockNumber) -> [u8; 32] {
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			let domain = eip712::domain_separator(T::ChainId::get(), genesis_hash);
+			let message = eip712::relayed_call_hash(call, nonce, deadline.unique_saturated_into());
+
+			eip712::signing_hash(&domain, &message)
+		}
+	}
+}
@@ -0,0 +1,92 @@
+use crate as pallet_relayer;
+use frame_support::traits::{ConstU16, ConstU64, Contains};
+use frame_system as system;
+use sp_core::{ecdsa, Pair, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, IdentifyAccount, IdentityLookup},
+	AccountId32, MultiSigner,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Relayer: pallet_relayer,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+/// Everything but nested relaying.
+pub struct NotRelay;
+impl Contains<RuntimeCall> for NotRelay {
+	fn contains(call: &RuntimeCall) -> bool {
+		!matches!(call, RuntimeCall::Relayer(_))
+	}
+}
+
+pub struct EcdsaAccount;
+impl Convert<ecdsa::Public, AccountId32> for EcdsaAccount {
+	fn convert(public: ecdsa::Public) -> AccountId32 {
+		MultiSigner::from(public).into_account()
+	}
+}
+
+/// Everyone but Charlie.
+pub struct NotCharlie;
+impl pallet_relayer::SignerFilter<AccountId32, RuntimeCall> for NotCharlie {
+	fn admit(public: &ecdsa::Public, _: &AccountId32, _: &RuntimeCall) -> bool {
+		*public != ecdsa::Pair::from_string("//Charlie", None).unwrap().public()
+	}
+}
+
+impl pallet_relayer::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type CallFilter = NotRelay;
+	type SignerToAccountId = EcdsaAccount;
+	type SignerFilter = NotCharlie;
+	type ChainId = ConstU64<42>;
+	type WeightInfo = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
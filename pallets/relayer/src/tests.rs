@@ -0,0 +1,195 @@
+use crate::{mock::*, Error, Event, Nonces};
+use account::AccountId20;
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok};
+use sp_core::{ecdsa, Pair, H160};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash, IdentifyAccount},
+	AccountId32, MultiSigner,
+};
+
+fn signer(seed: &str) -> (ecdsa::Pair, H160) {
+	let pair = ecdsa::Pair::from_string(&format!("//{}", seed), None).unwrap();
+	let address = AccountId20::from(pair.public()).into();
+	(pair, address)
+}
+
+fn account_of(pair: &ecdsa::Pair) -> AccountId32 {
+	MultiSigner::from(pair.public()).into_account()
+}
+
+fn relayer() -> RuntimeOrigin {
+	RuntimeOrigin::signed(AccountId32::new([1; 32]))
+}
+
+fn remark() -> RuntimeCall {
+	frame_system::Call::remark_with_event { remark: b"gasless".to_vec() }.into()
+}
+
+fn sign(pair: &ecdsa::Pair, call: &RuntimeCall, nonce: u64, deadline: u64) -> [u8; 65] {
+	pair.sign_prehashed(&Relayer::signing_hash(&call.encode(), nonce, deadline)).0
+}
+
+#[test]
+fn dispatches_call_with_signer_origin() {
+	new_test_ext().execute_with(|| {
+		let (alice, address) = signer("Alice");
+		let signature = sign(&alice, &remark(), 0, 10);
+
+		assert_ok!(Relayer::relay(relayer(), Box::new(remark()), address, 0, 10, signature));
+
+		assert_eq!(Nonces::<Test>::get(address), 1);
+		System::assert_has_event(
+			frame_system::Event::Remarked {
+				sender: account_of(&alice),
+				hash: BlakeTwo256::hash(b"gasless"),
+			}
+			.into(),
+		);
+		System::assert_last_event(
+			Event::Relayed {
+				signer: address,
+				account: account_of(&alice),
+				nonce: 0,
+				result: Ok(()),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn rejects_replayed_signature() {
+	new_test_ext().execute_with(|| {
+		let (alice, address) = signer("Alice");
+		let signature = sign(&alice, &remark(), 0, 10);
+		assert_ok!(Relayer::relay(relayer(), Box::new(remark()), address, 0, 10, signature));
+
+		assert_noop!(
+			Relayer::relay(relayer(), Box::new(remark()), address, 0, 10, signature),
+			Error::<Test>::InvalidNonce
+		);
+		// Claiming the next nonce doesn't help, as it is part of the signed message.
+		assert_noop!(
+			Relayer::relay(relayer(), Box::new(remark()), address, 1, 10, signature),
+			Error::<Test>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn rejects_expired_signature() {
+	new_test_ext().execute_with(|| {
+		let (alice, address) = signer("Alice");
+		let signature = sign(&alice, &remark(), 0, 10);
+		System::set_block_number(11);
+
+		assert_noop!(
+			Relayer::relay(relayer(), Box::new(remark()), address, 0, 10, signature),
+			Error::<Test>::DeadlinePassed
+		);
+	});
+}
+
+#[test]
+fn rejects_signature_of_other_signer_or_call() {
+	new_test_ext().execute_with(|| {
+		let (alice, _) = signer("Alice");
+		let (_, bob) = signer("Bob");
+		let (_, alice_address) = signer("Alice");
+
+		assert_noop!(
+			Relayer::relay(
+				relayer(),
+				Box::new(remark()),
+				bob,
+				0,
+				10,
+				sign(&alice, &remark(), 0, 10)
+			),
+			Error::<Test>::InvalidSignature
+		);
+
+		let other_call: RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+		assert_noop!(
+			Relayer::relay(
+				relayer(),
+				Box::new(other_call),
+				alice_address,
+				0,
+				10,
+				sign(&alice, &remark(), 0, 10)
+			),
+			Error::<Test>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn rejects_filtered_call() {
+	new_test_ext().execute_with(|| {
+		let (alice, address) = signer("Alice");
+		let nested: RuntimeCall = crate::Call::relay {
+			call: Box::new(remark()),
+			signer: address,
+			nonce: 0,
+			deadline: 10,
+			signature: sign(&alice, &remark(), 0, 10),
+		}
+		.into();
+
+		assert_noop!(
+			Relayer::relay(
+				relayer(),
+				Box::new(nested.clone()),
+				address,
+				0,
+				10,
+				sign(&alice, &nested, 0, 10)
+			),
+			Error::<Test>::CallFiltered
+		);
+	});
+}
+
+#[test]
+fn rejects_filtered_signer() {
+	new_test_ext().execute_with(|| {
+		let (charlie, address) = signer("Charlie");
+		let signature = sign(&charlie, &remark(), 0, 10);
+
+		assert_noop!(
+			Relayer::relay(relayer(), Box::new(remark()), address, 0, 10, signature),
+			Error::<Test>::SignerFiltered
+		);
+	});
+}
+
+#[test]
+fn failing_call_still_uses_nonce() {
+	new_test_ext().execute_with(|| {
+		let (alice, address) = signer("Alice");
+		// `set_heap_pages` requires root.
+		let call: RuntimeCall = frame_system::Call::set_heap_pages { pages: 1 }.into();
+
+		assert_ok!(Relayer::relay(
+			relayer(),
+			Box::new(call.clone()),
+			address,
+			0,
+			10,
+			sign(&alice, &call, 0, 10)
+		));
+
+		assert_eq!(Nonces::<Test>::get(address), 1);
+		System::assert_last_event(
+			Event::Relayed {
+				signer: address,
+				account: account_of(&alice),
+				nonce: 0,
+				result: Err(sp_runtime::DispatchError::BadOrigin),
+			}
+			.into(),
+		);
+	});
+}
@@ -0,0 +1,34 @@
+//! Weights for pallet-relayer.
+//!
+//! NOT GENERATED: conservative estimates until `./scripts/benchmark_weights.sh relayer` replaces
+//! this file with the results of the benchmarks in `benchmarking.rs` on reference hardware.
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet-relayer.
+pub trait WeightInfo {
+	/// Overhead of `relay` with a call of `n` encoded bytes, excluding the call itself.
+	fn relay(n: u32) -> Weight;
+}
+
+/// Weights for pallet-relayer using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: System BlockHash (r:1 w:0)
+	// Storage: Relayer Nonces (r:1 w:1)
+	fn relay(n: u32) -> Weight {
+		Weight::from_ref_time(60_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(2_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn relay(n: u32) -> Weight {
+		Weight::from_ref_time(60_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(2_000 as u64).saturating_mul(n as u64))
+	}
+}
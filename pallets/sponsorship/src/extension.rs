@@ -0,0 +1,243 @@
+//! The signed extension charging fees to either the signer or its sponsor.
+
+use crate::{
+	Allowances, BalanceOf, Config, Event, Nonces, Pallet, ALLOWANCE_EXCEEDED,
+	INVALID_SPONSOR_SIGNATURE,
+};
+use codec::{Decode, Encode};
+use frame_support::dispatch::{DispatchInfo, PostDispatchInfo};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use scale_info::TypeInfo;
+use sp_core::H160;
+use sp_runtime::{
+	traits::{
+		Convert, DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension,
+		UniqueSaturatedInto,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	DispatchResult, FixedPointOperand, RuntimeDebug, Saturating,
+};
+use sp_std::{vec, vec::Vec};
+
+/// A sponsor's consent to pay the fee of a transaction.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct Sponsor {
+	/// Ethereum address of the sponsor.
+	pub address: H160,
+	/// The sponsor's next nonce in [`Nonces`].
+	pub nonce: u64,
+	/// The nonce of the sponsored transaction.
+	pub signer_nonce: u64,
+	/// The sponsor's 65-byte `r ‖ s ‖ v` signature of [`crate::sponsorship_hash`].
+	pub signature: [u8; 65],
+}
+
+/// Charges the transaction fee and tip to the [`Sponsor`], if any, and to the signer otherwise.
+///
+/// Apart from who pays, this behaves exactly like [`ChargeTransactionPayment`], which it delegates
+/// to.
+///
+/// Must come after `frame_system::CheckNonce` in the runtime's `SignedExtra`: a sponsored
+/// transaction is only dispatched if [`Sponsor::signer_nonce`] is the nonce `CheckNonce` just used.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ChargeSponsoredTransactionPayment<T: Config> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	sponsor: Option<Sponsor>,
+}
+
+impl<T: Config> ChargeSponsoredTransactionPayment<T> {
+	/// Pay `tip` and the fee from the signer's account.
+	pub fn new(tip: BalanceOf<T>) -> Self {
+		Self { tip, sponsor: None }
+	}
+
+	/// Have `sponsor` pay `tip` and the fee.
+	pub fn sponsored(tip: BalanceOf<T>, sponsor: Sponsor) -> Self {
+		Self { tip, sponsor: Some(sponsor) }
+	}
+
+	/// The tip for the block author.
+	pub fn tip(&self) -> BalanceOf<T> {
+		self.tip
+	}
+
+	/// The sponsor paying the fee, if any.
+	pub fn sponsor(&self) -> Option<&Sponsor> {
+		self.sponsor.as_ref()
+	}
+}
+
+impl<T: Config> From<BalanceOf<T>> for ChargeSponsoredTransactionPayment<T> {
+	fn from(tip: BalanceOf<T>) -> Self {
+		Self::new(tip)
+	}
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeSponsoredTransactionPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeSponsoredTransactionPayment<{:?}, {:?}>", self.tip, self.sponsor)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config> ChargeSponsoredTransactionPayment<T>
+where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	<T as frame_system::Config>::RuntimeCall:
+		Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	/// The account of `sponsor`, if it signed off on paying for this transaction and its
+	/// allowance covers the fee.
+	fn sponsor_account(
+		&self,
+		sponsor: &Sponsor,
+		who: &T::AccountId,
+		call: &<T as frame_system::Config>::RuntimeCall,
+		info: &DispatchInfoOf<<T as frame_system::Config>::RuntimeCall>,
+		len: usize,
+	) -> Result<T::AccountId, TransactionValidityError> {
+		let hash =
+			Pallet::<T>::sponsorship_hash(who, sponsor.signer_nonce, call, self.tip, sponsor.nonce);
+		let account = match account::ecrecover(&sponsor.signature, &hash) {
+			Some((address, public)) if H160::from(address) == sponsor.address =>
+				T::SignerToAccountId::convert(public),
+			_ => return Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into()),
+		};
+
+		let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, self.tip);
+		if Allowances::<T>::get(&account) < fee {
+			return Err(InvalidTransaction::Custom(ALLOWANCE_EXCEEDED).into())
+		}
+		Ok(account)
+	}
+}
+
+/// The next nonce of `who`.
+fn signer_nonce<T: Config>(who: &T::AccountId) -> u64 {
+	frame_system::Pallet::<T>::account_nonce(who).unique_saturated_into()
+}
+
+impl<T: Config> SignedExtension for ChargeSponsoredTransactionPayment<T>
+where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	<T as frame_system::Config>::RuntimeCall:
+		Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeSponsoredTransactionPayment";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	/// What [`ChargeTransactionPayment`] needs after dispatch, and the sponsor and the sponsored
+	/// account of sponsored transactions.
+	type Pre = (
+		<ChargeTransactionPayment<T> as SignedExtension>::Pre,
+		Option<(Self::AccountId, Self::AccountId)>,
+	);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let charge = ChargeTransactionPayment::<T>::from(self.tip);
+		let sponsor = match &self.sponsor {
+			Some(sponsor) => sponsor,
+			None => return charge.validate(who, call, info, len),
+		};
+
+		let next_nonce = Nonces::<T>::get(sponsor.address);
+		if sponsor.nonce < next_nonce {
+			return Err(InvalidTransaction::Stale.into())
+		}
+		// The transaction's own nonce isn't known here, only that it isn't below the signer's next.
+		if sponsor.signer_nonce < signer_nonce::<T>(who) {
+			return Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		}
+		let payer = self.sponsor_account(sponsor, who, call, info, len)?;
+
+		let tag = |nonce: u64| (b"sponsor", sponsor.address, nonce).encode();
+		let sponsorship = ValidTransaction {
+			requires: if sponsor.nonce > next_nonce {
+				vec![tag(sponsor.nonce - 1)]
+			} else {
+				Vec::new()
+			},
+			provides: vec![tag(sponsor.nonce)],
+			..Default::default()
+		};
+		Ok(charge.validate(&payer, call, info, len)?.combine_with(sponsorship))
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let charge = ChargeTransactionPayment::<T>::from(self.tip);
+		let sponsor = match &self.sponsor {
+			Some(sponsor) => sponsor,
+			None => return Ok((charge.pre_dispatch(who, call, info, len)?, None)),
+		};
+
+		let next_nonce = Nonces::<T>::get(sponsor.address);
+		if sponsor.nonce != next_nonce {
+			return Err(if sponsor.nonce < next_nonce {
+				InvalidTransaction::Stale
+			} else {
+				InvalidTransaction::Future
+			}
+			.into())
+		}
+		// `CheckNonce` already moved the signer past the transaction's nonce.
+		if sponsor.signer_nonce.checked_add(1) != Some(signer_nonce::<T>(who)) {
+			return Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		}
+		let payer = self.sponsor_account(sponsor, who, call, info, len)?;
+
+		let pre = charge.pre_dispatch(&payer, call, info, len)?;
+		Nonces::<T>::insert(sponsor.address, next_nonce.saturating_add(1));
+		Ok((pre, Some((payer, who.clone()))))
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (pre, sponsorship) = match pre {
+			Some(pre) => pre,
+			None => return Ok(()),
+		};
+		let tip = pre.0;
+		ChargeTransactionPayment::<T>::post_dispatch(Some(pre), info, post_info, len, result)?;
+
+		if let Some((sponsor, who)) = sponsorship {
+			let fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+				len as u32, info, post_info, tip,
+			);
+			Allowances::<T>::mutate(&sponsor, |allowance| {
+				*allowance = allowance.saturating_sub(fee)
+			});
+			Pallet::<T>::deposit_event(Event::FeeSponsored { sponsor, who, fee });
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,137 @@
+//! # Sponsorship Pallet
+//!
+//! Lets sponsor accounts pay the transaction fees of other accounts, e.g. an enterprise covering
+//! the fees of its users.
+//!
+//! A sponsored transaction carries a [`Sponsor`] in its [`ChargeSponsoredTransactionPayment`]
+//! signed extension, which replaces `pallet_transaction_payment::ChargeTransactionPayment` in the
+//! runtime's `SignedExtra` after `frame_system::CheckNonce`. The sponsor signs [`sponsorship_hash`]
+//! of the transaction with their Ethereum key, and the fee is then withdrawn from the sponsor
+//! instead of the signer. Sponsors cap what they pay for others with [`Pallet::set_allowance`];
+//! every sponsored fee is deducted from the allowance, and transactions whose fee exceeds what is
+//! left are rejected from the pool.
+//!
+//! The transaction's signing payload contains the extension, and with it the sponsor's own
+//! signature, and a signed extension only sees the signer, the call and itself of it. So sponsors
+//! sign [`sponsorship_hash`] of what the extension can check instead: the chain, the signer and its
+//! nonce, the call and the tip. As the signer's nonce is used once, a sponsorship pays for exactly
+//! one transaction, whose era is all the sponsor doesn't commit to. The sponsor's own nonce in
+//! [`Nonces`] orders its sponsorships, and the EIP-191 hash can be signed with any Ethereum wallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use extension::{ChargeSponsoredTransactionPayment, Sponsor};
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+mod extension;
+
+use codec::Encode;
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
+
+/// Balance type of the fees.
+pub type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as pallet_transaction_payment::OnChargeTransaction<T>>::Balance;
+
+/// `InvalidTransaction::Custom` code of a sponsor signature that doesn't match the sponsor.
+pub const INVALID_SPONSOR_SIGNATURE: u8 = 1;
+/// `InvalidTransaction::Custom` code of a fee exceeding the sponsor's allowance.
+pub const ALLOWANCE_EXCEEDED: u8 = 2;
+
+/// The hash a sponsor signs to pay the fee of `who` submitting `call` with `tip` and the nonce
+/// `signer_nonce`.
+///
+/// This is the EIP-191 `personal_sign` hash of `keccak256(SCALE("sponsor", genesis_hash, who,
+/// signer_nonce, call, tip, nonce))`, so that any Ethereum wallet can sign it. `nonce` is the
+/// sponsor's next nonce in [`Nonces`].
+pub fn sponsorship_hash<AccountId: Encode, Call: Encode, Balance: Encode>(
+	genesis_hash: H256,
+	who: &AccountId,
+	signer_nonce: u64,
+	call: &Call,
+	tip: Balance,
+	nonce: u64,
+) -> [u8; 32] {
+	let message =
+		(b"sponsor", genesis_hash, who, signer_nonce, call, tip, nonce).using_encoded(keccak_256);
+	account::eip191_hash(&message)
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::BalanceOf;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_core::{ecdsa, H160, H256};
+	use sp_runtime::traits::{Convert, Zero};
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config<Hash = H256> + pallet_transaction_payment::Config
+	{
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The account controlled by a secp256k1 public key.
+		type SignerToAccountId: Convert<ecdsa::Public, Self::AccountId>;
+	}
+
+	/// Fees every sponsor is still willing to pay for others.
+	#[pallet::storage]
+	#[pallet::getter(fn allowance)]
+	pub type Allowances<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Next nonce of every sponsor address, protecting sponsor signatures against replay.
+	#[pallet::storage]
+	#[pallet::getter(fn nonce)]
+	pub type Nonces<T> = StorageMap<_, Blake2_128Concat, H160, u64, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `sponsor` is willing to pay up to `allowance` in fees for others.
+		AllowanceSet { sponsor: T::AccountId, allowance: BalanceOf<T> },
+		/// `sponsor` paid `fee` for a transaction of `who`.
+		FeeSponsored { sponsor: T::AccountId, who: T::AccountId, fee: BalanceOf<T> },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set how much the origin is willing to pay in fees for others, replacing what is left of
+		/// its previous allowance.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_allowance(origin: OriginFor<T>, allowance: BalanceOf<T>) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+
+			Allowances::<T>::insert(&sponsor, allowance);
+
+			Self::deposit_event(Event::AllowanceSet { sponsor, allowance });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// [`super::sponsorship_hash`] on this chain.
+		pub fn sponsorship_hash(
+			who: &T::AccountId,
+			signer_nonce: u64,
+			call: &<T as frame_system::Config>::RuntimeCall,
+			tip: BalanceOf<T>,
+			nonce: u64,
+		) -> [u8; 32] {
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			super::sponsorship_hash(genesis_hash, who, signer_nonce, call, tip, nonce)
+		}
+	}
+}
@@ -0,0 +1,209 @@
+use crate::{
+	mock::*, ChargeSponsoredTransactionPayment, Event, Nonces, Sponsor, ALLOWANCE_EXCEEDED,
+	INVALID_SPONSOR_SIGNATURE,
+};
+use account::AccountId20;
+use frame_support::{
+	assert_ok,
+	dispatch::{DispatchInfo, PostDispatchInfo},
+	weights::Weight,
+};
+use sp_core::{ecdsa, Pair, H160};
+use sp_runtime::{
+	traits::{IdentifyAccount, SignedExtension},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	AccountId32, MultiSigner,
+};
+
+const LEN: usize = 10;
+
+fn pair(seed: &str) -> ecdsa::Pair {
+	ecdsa::Pair::from_string(&format!("//{}", seed), None).unwrap()
+}
+
+fn account_of(pair: &ecdsa::Pair) -> AccountId32 {
+	MultiSigner::from(pair.public()).into_account()
+}
+
+fn remark() -> RuntimeCall {
+	frame_system::Call::remark { remark: b"sponsored".to_vec() }.into()
+}
+
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: Weight::from_ref_time(100), ..Default::default() }
+}
+
+fn fee() -> u64 {
+	TransactionPayment::compute_fee(LEN as u32, &info(), 0)
+}
+
+/// `sponsor`'s consent to pay for the next transaction of `who`.
+fn sponsor(sponsor: &ecdsa::Pair, who: &AccountId32, call: &RuntimeCall, nonce: u64) -> Sponsor {
+	let signer_nonce = System::account_nonce(who);
+	let hash = Sponsorship::sponsorship_hash(who, signer_nonce, call, 0, nonce);
+	Sponsor {
+		address: AccountId20::from(sponsor.public()).into(),
+		nonce,
+		signer_nonce,
+		signature: sponsor.sign_prehashed(&hash).0,
+	}
+}
+
+/// Dispatch the next transaction of `who`, after `CheckNonce` used its nonce.
+fn charge(
+	extension: ChargeSponsoredTransactionPayment<Test>,
+	who: &AccountId32,
+) -> Result<(), TransactionValidityError> {
+	System::inc_account_nonce(who);
+	let pre = extension.pre_dispatch(who, &remark(), &info(), LEN)?;
+	ChargeSponsoredTransactionPayment::<Test>::post_dispatch(
+		Some(pre),
+		&info(),
+		&PostDispatchInfo::default(),
+		LEN,
+		&Ok(()),
+	)
+}
+
+#[test]
+fn signer_pays_without_sponsor() {
+	let alice = account_of(&pair("Alice"));
+	new_test_ext(vec![alice.clone()]).execute_with(|| {
+		assert_ok!(charge(ChargeSponsoredTransactionPayment::new(0), &alice));
+
+		assert_eq!(Balances::free_balance(&alice), INITIAL_BALANCE - fee());
+	});
+}
+
+#[test]
+fn sponsor_pays_from_allowance() {
+	let (alice, bob) = (pair("Alice"), pair("Bob"));
+	let sponsor_account = account_of(&bob);
+	new_test_ext(vec![sponsor_account.clone()]).execute_with(|| {
+		assert_ok!(Sponsorship::set_allowance(
+			RuntimeOrigin::signed(sponsor_account.clone()),
+			3 * fee()
+		));
+		let who = account_of(&alice);
+		let extension =
+			ChargeSponsoredTransactionPayment::sponsored(0, sponsor(&bob, &who, &remark(), 0));
+
+		assert_ok!(extension.validate(&who, &remark(), &info(), LEN));
+		assert_ok!(charge(extension, &who));
+
+		assert_eq!(Balances::free_balance(&who), 0);
+		assert_eq!(Balances::free_balance(&sponsor_account), INITIAL_BALANCE - fee());
+		assert_eq!(Sponsorship::allowance(&sponsor_account), 2 * fee());
+		assert_eq!(Nonces::<Test>::get(H160::from(AccountId20::from(bob.public()))), 1);
+		System::assert_last_event(
+			Event::FeeSponsored { sponsor: sponsor_account, who, fee: fee() }.into(),
+		);
+	});
+}
+
+#[test]
+fn rejects_signature_of_other_transaction() {
+	let (alice, bob) = (pair("Alice"), pair("Bob"));
+	new_test_ext(vec![account_of(&bob)]).execute_with(|| {
+		assert_ok!(Sponsorship::set_allowance(RuntimeOrigin::signed(account_of(&bob)), fee()));
+		let who = account_of(&alice);
+		let other_call: RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+
+		let extension =
+			ChargeSponsoredTransactionPayment::sponsored(0, sponsor(&bob, &who, &other_call, 0));
+		assert_eq!(
+			extension.validate(&who, &remark(), &info(), LEN),
+			Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		);
+
+		// Signed by Alice, claiming to be Bob.
+		let mut forged = sponsor(&alice, &who, &remark(), 0);
+		forged.address = AccountId20::from(bob.public()).into();
+		assert_eq!(
+			charge(ChargeSponsoredTransactionPayment::sponsored(0, forged), &who),
+			Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		);
+	});
+}
+
+#[test]
+fn sponsorships_pay_for_one_transaction() {
+	let (alice, bob) = (pair("Alice"), pair("Bob"));
+	new_test_ext(vec![account_of(&bob)]).execute_with(|| {
+		assert_ok!(Sponsorship::set_allowance(RuntimeOrigin::signed(account_of(&bob)), 3 * fee()));
+		let who = account_of(&alice);
+		let extension =
+			ChargeSponsoredTransactionPayment::sponsored(0, sponsor(&bob, &who, &remark(), 0));
+
+		// Not for another call.
+		let other_call: RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+		assert_eq!(
+			extension.validate(&who, &other_call, &info(), LEN),
+			Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		);
+		// Nor for another transaction with the same call.
+		System::inc_account_nonce(&who);
+		assert_eq!(
+			extension.validate(&who, &remark(), &info(), LEN),
+			Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		);
+		assert_eq!(
+			charge(extension, &who),
+			Err(InvalidTransaction::Custom(INVALID_SPONSOR_SIGNATURE).into())
+		);
+		assert_eq!(Sponsorship::allowance(&account_of(&bob)), 3 * fee());
+	});
+}
+
+#[test]
+fn rejects_fee_exceeding_allowance() {
+	let (alice, bob) = (pair("Alice"), pair("Bob"));
+	new_test_ext(vec![account_of(&bob)]).execute_with(|| {
+		assert_ok!(Sponsorship::set_allowance(RuntimeOrigin::signed(account_of(&bob)), fee() - 1));
+		let who = account_of(&alice);
+		let extension =
+			ChargeSponsoredTransactionPayment::sponsored(0, sponsor(&bob, &who, &remark(), 0));
+
+		assert_eq!(
+			extension.validate(&who, &remark(), &info(), LEN),
+			Err(InvalidTransaction::Custom(ALLOWANCE_EXCEEDED).into())
+		);
+		assert_eq!(Balances::free_balance(&account_of(&bob)), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn rejects_replayed_sponsorship() {
+	let (alice, bob) = (pair("Alice"), pair("Bob"));
+	new_test_ext(vec![account_of(&bob)]).execute_with(|| {
+		assert_ok!(Sponsorship::set_allowance(RuntimeOrigin::signed(account_of(&bob)), 3 * fee()));
+		let who = account_of(&alice);
+		let extension =
+			ChargeSponsoredTransactionPayment::sponsored(0, sponsor(&bob, &who, &remark(), 0));
+		assert_ok!(charge(extension.clone(), &who));
+
+		assert_eq!(
+			extension.validate(&who, &remark(), &info(), LEN),
+			Err(InvalidTransaction::Stale.into())
+		);
+		assert_eq!(charge(extension, &who), Err(InvalidTransaction::Stale.into()));
+		// Sponsorships signed ahead of time wait for the sponsor's earlier ones.
+		let future =
+			ChargeSponsoredTransactionPayment::sponsored(0, sponsor(&bob, &who, &remark(), 2));
+		assert_eq!(charge(future, &who), Err(InvalidTransaction::Future.into()));
+	});
+}
+
+#[test]
+fn set_allowance_replaces_previous_one() {
+	let sponsor_account = AccountId32::new([1; 32]);
+	new_test_ext(vec![]).execute_with(|| {
+		assert_ok!(Sponsorship::set_allowance(RuntimeOrigin::signed(sponsor_account.clone()), 10));
+		assert_ok!(Sponsorship::set_allowance(RuntimeOrigin::signed(sponsor_account.clone()), 5));
+
+		assert_eq!(Sponsorship::allowance(&sponsor_account), 5);
+		System::assert_last_event(
+			Event::AllowanceSet { sponsor: sponsor_account, allowance: 5 }.into(),
+		);
+	});
+}
@@ -14,10 +14,31 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+/// Ethereum keys for the example offchain worker.
+pub mod crypto {
+	pub use account::ocw::{AuthorityId, KEY_TYPE};
+	use sp_core::ecdsa;
+	use sp_runtime::{MultiSignature, MultiSigner};
+
+	/// Signs offchain worker transactions with the local keys of [`KEY_TYPE`], whose accounts are
+	/// those of the Ethereum keys.
+	pub struct EthereumOcwAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for EthereumOcwAuthId {
+		type RuntimeAppPublic = AuthorityId;
+		type GenericSignature = ecdsa::Signature;
+		type GenericPublic = ecdsa::Public;
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::*;
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+		pallet_prelude::*,
+	};
+	use sp_runtime::traits::SaturatedConversion;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
@@ -25,9 +46,13 @@ pub mod pallet {
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The keys the offchain worker signs its transactions with, e.g.
+		/// [`crate::crypto::EthereumOcwAuthId`].
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
 	}
 
 	// The pallet's runtime storage items.
@@ -57,6 +82,18 @@ pub mod pallet {
 		StorageOverflow,
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// An example offchain worker, which stores the number of every imported block with a
+		/// signed `do_something` transaction.
+		///
+		/// Nodes without a local key of [`Config::AuthorityId`] don't submit anything.
+		fn offchain_worker(block_number: T::BlockNumber) {
+			// Offchain workers have nobody to report errors to.
+			let _ = Self::submit_something(block_number.saturated_into());
+		}
+	}
+
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
@@ -99,4 +136,15 @@ pub mod pallet {
 			}
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		/// Submit a `do_something` transaction signed by any local key of [`Config::AuthorityId`].
+		pub fn submit_something(something: u32) -> Result<(), &'static str> {
+			let (_, result) = Signer::<T, T::AuthorityId>::any_account()
+				.send_signed_transaction(|_| Call::do_something { something })
+				.ok_or("No local offchain worker key")?;
+
+			result.map_err(|()| "Failed to submit the transaction")
+		}
+	}
 }
@@ -1,10 +1,12 @@
 use crate as pallet_template;
+use codec::Encode;
 use frame_support::traits::{ConstU16, ConstU64};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, TestXt},
 	traits::{BlakeTwo256, IdentityLookup},
+	AccountId32, MultiSignature, MultiSigner,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -33,7 +35,7 @@ impl system::Config for Test {
 	type BlockNumber = u64;
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
-	type AccountId = u64;
+	type AccountId = AccountId32;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type RuntimeEvent = RuntimeEvent;
@@ -51,6 +53,38 @@ impl system::Config for Test {
 
 impl pallet_template::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type AuthorityId = pallet_template::crypto::EthereumOcwAuthId;
+}
+
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl system::offchain::SigningTypes for Test {
+	type Public = MultiSigner;
+	type Signature = MultiSignature;
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<C> system::offchain::CreateSignedTransaction<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	fn create_transaction<S: system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: MultiSigner,
+		_account: AccountId32,
+		nonce: u64,
+	) -> Option<(RuntimeCall, (u64, ()))> {
+		// The signature isn't part of a `TestXt`, but the key must be able to sign.
+		S::sign(&call.encode(), public)?;
+		Some((call, (nonce, ())))
+	}
 }
 
 // Build genesis storage according to the mock runtime.
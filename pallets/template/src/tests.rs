@@ -1,11 +1,19 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{crypto::KEY_TYPE, mock::*, Call, Error};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::AccountId32;
+use std::sync::Arc;
 
 #[test]
 fn it_works_for_default_value() {
 	new_test_ext().execute_with(|| {
 		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(
+			RuntimeOrigin::signed(AccountId32::new([1; 32])),
+			42
+		));
 		// Read pallet storage and assert an expected result.
 		assert_eq!(TemplateModule::something(), Some(42));
 	});
@@ -16,8 +24,47 @@ fn correct_error_for_none_value() {
 	new_test_ext().execute_with(|| {
 		// Ensure the expected error is thrown when no value is present.
 		assert_noop!(
-			TemplateModule::cause_error(RuntimeOrigin::signed(1)),
+			TemplateModule::cause_error(RuntimeOrigin::signed(AccountId32::new([1; 32]))),
 			Error::<Test>::NoneValue
 		);
 	});
 }
+
+#[test]
+fn offchain_worker_submits_the_block_number_signed_with_its_ethereum_key() {
+	let mut ext = new_test_ext();
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	SyncCryptoStore::ecdsa_generate_new(&keystore, KEY_TYPE, Some("//Alice")).unwrap();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	ext.execute_with(|| {
+		TemplateModule::offchain_worker(7);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((0, ())));
+		assert_eq!(tx.call, RuntimeCall::TemplateModule(Call::do_something { something: 7 }));
+	});
+}
+
+#[test]
+fn offchain_worker_without_a_key_submits_nothing() {
+	let mut ext = new_test_ext();
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+
+	ext.execute_with(|| {
+		TemplateModule::offchain_worker(7);
+
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(TemplateModule::submit_something(7), Err("No local offchain worker key"));
+	});
+}
@@ -0,0 +1,304 @@
+//! # Validator Set Pallet
+//!
+//! A proof-of-authority validator set for `pallet_session`. [`Config::AddRemoveOrigin`] adds and
+//! removes validators, which are identified by their Ethereum addresses. The pallet is the
+//! session manager: changes to the set are handed to `pallet_session` at the next session
+//! boundary, and the validators' Aura and GRANDPA keys are the session keys they set with
+//! `pallet_session::set_keys`.
+//!
+//! Validators are added by their secp256k1 public key, since accounts are hashes of public keys
+//! and the address of an account can't be derived from it. [`Validators`] holds the address and
+//! the account of each.
+//!
+//! The pallet also handles the offences `pallet_offences` collects, e.g. unresponsiveness reported
+//! by `pallet_im_online` and equivocations reported by `pallet_grandpa`. Validators have no stake
+//! to slash, so offenders are removed from the set instead, as long as [`Config::MinValidators`]
+//! remain. Until they leave the session's validator set, removed offenders are disabled in
+//! `pallet_session`, which keeps them from authoring blocks. For key ownership proofs the pallet
+//! also manages `pallet_session::historical`, which identifies validators by their accounts.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::{
+	traits::{Get, ValidatorSet, ValidatorSetWithIdentification},
+	weights::Weight,
+};
+use sp_runtime::{traits::ConvertInto, Perbill};
+use sp_staking::{
+	offence::{DisableStrategy, OffenceDetails, OnOffenceHandler},
+	SessionIndex,
+};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use account::AccountId20;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_core::ecdsa;
+	use sp_runtime::traits::Convert;
+	use sp_std::prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config
+		+ pallet_session::Config<ValidatorId = <Self as frame_system::Config>::AccountId>
+	{
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The account controlled by a secp256k1 public key.
+		type SignerToAccountId: Convert<ecdsa::Public, Self::AccountId>;
+
+		/// Number of validators neither removals nor offences can go below.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+
+		/// Maximum number of validators, at most the maximum number of Aura and GRANDPA
+		/// authorities.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+	}
+
+	/// The address and account of each validator.
+	#[pallet::storage]
+	pub type Validators<T: Config> =
+		StorageValue<_, BoundedVec<(AccountId20, T::AccountId), T::MaxValidators>, ValueQuery>;
+
+	/// Whether [`Validators`] changed since it was last handed to `pallet_session`.
+	#[pallet::storage]
+	pub type Changed<T> = StorageValue<_, bool, ValueQuery>;
+
+	/// Offenders removed from [`Validators`] that may still be in the session's validator set.
+	#[pallet::storage]
+	pub type Offenders<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxValidators>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		/// Public keys of the initial validators.
+		pub validators: Vec<ecdsa::Public>,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self { validators: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			let validators = self
+				.validators
+				.iter()
+				.map(|public| {
+					(
+						AccountId20::from(public.clone()),
+						T::SignerToAccountId::convert(public.clone()),
+					)
+				})
+				.collect::<Vec<_>>();
+			let validators = BoundedVec::<_, T::MaxValidators>::try_from(validators)
+				.expect("genesis validators exceed `MaxValidators`");
+
+			Changed::<T>::put(!validators.is_empty());
+			Validators::<T>::put(validators);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `validator` joins the set at the next session boundary.
+		ValidatorAdded { validator: AccountId20 },
+		/// `validator` leaves the set at the next session boundary.
+		ValidatorRemoved { validator: AccountId20 },
+		/// `validator` committed an offence, e.g. went offline, and leaves the set at the next
+		/// session boundary.
+		OffenderRemoved { validator: AccountId20 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The public key is not a point on the secp256k1 curve.
+		InvalidKey,
+		/// The key's address is already a validator.
+		AlreadyValidator,
+		/// The address is not a validator.
+		NotValidator,
+		/// The set already has [`Config::MaxValidators`] validators.
+		TooManyValidators,
+		/// The set only has [`Config::MinValidators`] validators.
+		TooFewValidators,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add the owner of `public` to the validator set.
+		///
+		/// It still has to set its session keys to author blocks.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 3).ref_time())]
+		pub fn add_validator(origin: OriginFor<T>, public: ecdsa::Public) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+			let validator = AccountId20::from_public(&public).ok_or(Error::<T>::InvalidKey)?;
+			let account = T::SignerToAccountId::convert(public);
+
+			Validators::<T>::try_mutate(|validators| {
+				ensure!(
+					!validators.iter().any(|(address, _)| *address == validator),
+					Error::<T>::AlreadyValidator
+				);
+				validators
+					.try_push((validator, account.clone()))
+					.map_err(|_| Error::<T>::TooManyValidators)
+			})?;
+			Changed::<T>::put(true);
+			// A former offender is trusted again.
+			Offenders::<T>::mutate(|offenders| offenders.retain(|offender| *offender != account));
+
+			Self::deposit_event(Event::ValidatorAdded { validator });
+			Ok(())
+		}
+
+		/// Remove `validator` from the validator set.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 2).ref_time())]
+		pub fn remove_validator(origin: OriginFor<T>, validator: AccountId20) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			Validators::<T>::try_mutate(|validators| {
+				ensure!(
+					validators.len() > T::MinValidators::get() as usize,
+					Error::<T>::TooFewValidators
+				);
+				let index = validators
+					.iter()
+					.position(|(address, _)| *address == validator)
+					.ok_or(Error::<T>::NotValidator)?;
+				validators.remove(index);
+				Ok::<_, Error<T>>(())
+			})?;
+			Changed::<T>::put(true);
+
+			Self::deposit_event(Event::ValidatorRemoved { validator });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Remove the validators with the accounts `offenders` from the set and disable them for the
+	/// rest of their sessions, as long as [`Config::MinValidators`] remain.
+	pub fn remove_offenders(offenders: impl IntoIterator<Item = T::AccountId>) {
+		let min = T::MinValidators::get() as usize;
+		let mut validators = Validators::<T>::get();
+		let mut disabled = Offenders::<T>::get();
+
+		for offender in offenders {
+			if validators.len() <= min {
+				break
+			}
+			if let Some(index) = validators.iter().position(|(_, account)| *account == offender) {
+				let (validator, _) = validators.remove(index);
+				pallet_session::Pallet::<T>::disable(&offender);
+				// There are never more offenders than validators.
+				let _ = disabled.try_push(offender);
+				Changed::<T>::put(true);
+				Self::deposit_event(Event::OffenderRemoved { validator });
+			}
+		}
+		Validators::<T>::put(validators);
+		Offenders::<T>::put(disabled);
+	}
+
+	/// Disable the offenders still in the new session's validator set again, since
+	/// `pallet_session` enables all validators when the set changes, and forget the others.
+	fn disable_offenders() {
+		let validators = pallet_session::Pallet::<T>::validators();
+
+		Offenders::<T>::mutate(|offenders| {
+			offenders.retain(|offender| validators.contains(offender));
+			for offender in offenders.iter() {
+				pallet_session::Pallet::<T>::disable(offender);
+			}
+		});
+	}
+}
+
+impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
+	fn new_session(_: SessionIndex) -> Option<Vec<T::AccountId>> {
+		if !Changed::<T>::take() {
+			return None
+		}
+		Some(Validators::<T>::get().into_iter().map(|(_, account)| account).collect())
+	}
+
+	fn end_session(_: SessionIndex) {}
+
+	fn start_session(_: SessionIndex) {
+		Self::disable_offenders();
+	}
+}
+
+/// Validators are their own full identification in `pallet_session::historical`.
+impl<T: Config> pallet_session::historical::SessionManager<T::AccountId, T::AccountId>
+	for Pallet<T>
+{
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v.clone(), v)).collect())
+	}
+
+	fn end_session(_: SessionIndex) {}
+
+	fn start_session(_: SessionIndex) {
+		Self::disable_offenders();
+	}
+}
+
+impl<T: Config> ValidatorSet<T::AccountId> for Pallet<T> {
+	type ValidatorId = T::AccountId;
+	type ValidatorIdOf = ConvertInto;
+
+	fn session_index() -> SessionIndex {
+		pallet_session::Pallet::<T>::current_index()
+	}
+
+	fn validators() -> Vec<T::AccountId> {
+		pallet_session::Pallet::<T>::validators()
+	}
+}
+
+/// Validators are identified by their accounts, there is no exposure to identify them by.
+impl<T: Config> ValidatorSetWithIdentification<T::AccountId> for Pallet<T> {
+	type Identification = T::AccountId;
+	type IdentificationOf = ConvertInto;
+}
+
+impl<T: Config> OnOffenceHandler<T::AccountId, (T::AccountId, T::AccountId), Weight> for Pallet<T> {
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, (T::AccountId, T::AccountId)>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+		_disable_strategy: DisableStrategy,
+	) -> Weight {
+		Self::remove_offenders(offenders.iter().map(|details| details.offender.0.clone()));
+		T::DbWeight::get().reads_writes(3, 4)
+	}
+}
@@ -0,0 +1,136 @@
+use crate as pallet_validator_set;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, GenesisBuild, OnInitialize};
+use frame_system::{self as system, EnsureRoot};
+use sp_core::{crypto::KeyTypeId, ecdsa, Pair, H256};
+use sp_runtime::{
+	testing::{Header, UintAuthorityId},
+	traits::{BlakeTwo256, Convert, ConvertInto, IdentifyAccount, IdentityLookup, OpaqueKeys},
+	AccountId32, MultiSigner, RuntimeAppPublic,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		ValidatorSet: pallet_validator_set,
+		Session: pallet_session,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+/// Sessions of one block each.
+pub type Sessions = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+
+pub struct TestSessionHandler;
+impl pallet_session::SessionHandler<AccountId32> for TestSessionHandler {
+	const KEY_TYPE_IDS: &'static [KeyTypeId] = &[UintAuthorityId::ID];
+
+	fn on_genesis_session<Ks: OpaqueKeys>(_: &[(AccountId32, Ks)]) {}
+
+	fn on_new_session<Ks: OpaqueKeys>(_: bool, _: &[(AccountId32, Ks)], _: &[(AccountId32, Ks)]) {}
+
+	fn on_disabled(_: u32) {}
+}
+
+impl pallet_session::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = AccountId32;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = Sessions;
+	type NextSessionRotation = Sessions;
+	type SessionManager = ValidatorSet;
+	type SessionHandler = TestSessionHandler;
+	type Keys = UintAuthorityId;
+	type WeightInfo = ();
+}
+
+pub struct EcdsaAccount;
+impl Convert<ecdsa::Public, AccountId32> for EcdsaAccount {
+	fn convert(public: ecdsa::Public) -> AccountId32 {
+		MultiSigner::from(public).into_account()
+	}
+}
+
+impl pallet_validator_set::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AddRemoveOrigin = EnsureRoot<AccountId32>;
+	type SignerToAccountId = EcdsaAccount;
+	type MinValidators = ConstU32<1>;
+	type MaxValidators = ConstU32<3>;
+}
+
+/// The secp256k1 key of `seed`.
+pub fn public(seed: &str) -> ecdsa::Public {
+	ecdsa::Pair::from_string(&format!("//{}", seed), None).unwrap().public()
+}
+
+/// The account of the secp256k1 key of `seed`.
+pub fn account(seed: &str) -> AccountId32 {
+	EcdsaAccount::convert(public(seed))
+}
+
+// Build genesis storage according to the mock runtime, with the validators of `seeds`.
+pub fn new_test_ext(seeds: &[&str]) -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&pallet_validator_set::GenesisConfig {
+			validators: seeds.iter().map(|seed| public(seed)).collect(),
+		},
+		&mut storage,
+	)
+	.unwrap();
+	// Every account may become a validator, so all of them have keys.
+	pallet_session::GenesisConfig::<Test> {
+		keys: ["Alice", "Bob", "Charlie", "Dave"]
+			.iter()
+			.zip(1..)
+			.map(|(seed, key)| (account(seed), account(seed), UintAuthorityId(key)))
+			.collect(),
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Advance to the next session.
+pub fn next_session() {
+	let block = System::block_number() + 1;
+	System::set_block_number(block);
+	Session::on_initialize(block);
+}
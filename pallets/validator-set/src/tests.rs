@@ -0,0 +1,117 @@
+use crate::{mock::*, Error, Event, Offenders, Validators};
+use account::AccountId20;
+use frame_support::{assert_noop, assert_ok};
+use sp_core::ecdsa;
+use sp_runtime::DispatchError;
+
+fn addresses() -> Vec<AccountId20> {
+	Validators::<Test>::get().into_iter().map(|(address, _)| address).collect()
+}
+
+#[test]
+fn added_validators_join_the_session_two_sessions_later() {
+	new_test_ext(&["Alice"]).execute_with(|| {
+		assert_eq!(Session::validators(), vec![account("Alice")]);
+
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), public("Bob")));
+		let validator = AccountId20::from(public("Bob"));
+		System::assert_last_event(Event::ValidatorAdded { validator }.into());
+		assert_eq!(addresses(), vec![AccountId20::from(public("Alice")), validator]);
+
+		// The new set is queued at the next session and active at the one after.
+		next_session();
+		assert_eq!(Session::validators(), vec![account("Alice")]);
+		next_session();
+		assert_eq!(Session::validators(), vec![account("Alice"), account("Bob")]);
+
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), validator));
+		System::assert_last_event(Event::ValidatorRemoved { validator }.into());
+		next_session();
+		next_session();
+		assert_eq!(Session::validators(), vec![account("Alice")]);
+	});
+}
+
+#[test]
+fn only_the_add_remove_origin_changes_the_set() {
+	new_test_ext(&["Alice"]).execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::signed(account("Alice")), public("Bob")),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ValidatorSet::remove_validator(
+				RuntimeOrigin::signed(account("Alice")),
+				public("Alice").into()
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn rejects_keys_off_the_curve() {
+	new_test_ext(&["Alice"]).execute_with(|| {
+		// x = 5 has no point on the curve.
+		let mut invalid = [0u8; 33];
+		invalid[0] = 2;
+		invalid[32] = 5;
+
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), ecdsa::Public::from_raw(invalid)),
+			Error::<Test>::InvalidKey
+		);
+	});
+}
+
+#[test]
+fn set_stays_within_its_bounds() {
+	new_test_ext(&["Alice", "Bob", "Charlie"]).execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), public("Alice")),
+			Error::<Test>::AlreadyValidator
+		);
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), public("Dave")),
+			Error::<Test>::TooManyValidators
+		);
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), public("Dave").into()),
+			Error::<Test>::NotValidator
+		);
+
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), public("Bob").into()));
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), public("Charlie").into()));
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), public("Alice").into()),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn offenders_are_removed_down_to_the_minimum() {
+	new_test_ext(&["Alice", "Bob", "Charlie"]).execute_with(|| {
+		next_session();
+
+		ValidatorSet::remove_offenders(vec![account("Dave"), account("Bob")]);
+		System::assert_has_event(Event::OffenderRemoved { validator: public("Bob").into() }.into());
+		assert_eq!(
+			addresses(),
+			vec![AccountId20::from(public("Alice")), AccountId20::from(public("Charlie"))]
+		);
+		assert_eq!(Session::disabled_validators(), vec![1]);
+
+		ValidatorSet::remove_offenders(vec![account("Alice"), account("Charlie")]);
+		assert_eq!(addresses(), vec![AccountId20::from(public("Charlie"))]);
+		assert_eq!(Session::disabled_validators(), vec![0, 1]);
+
+		// Offenders stay disabled until they leave the session's validator set.
+		next_session();
+		assert_eq!(Session::disabled_validators(), vec![0, 1]);
+		next_session();
+		assert_eq!(Session::validators(), vec![account("Charlie")]);
+		assert!(Session::disabled_validators().is_empty());
+		assert!(Offenders::<Test>::get().is_empty());
+	});
+}
@@ -0,0 +1,32 @@
+//! Runtime API converting between Ethereum addresses, public keys and accounts.
+//!
+//! Accounts are hashes of secp256k1 public keys, so neither an address nor an account can be
+//! computed from the other. The runtime knows which belong together once the key signed a
+//! transaction or a relayed call; this API makes it the one place tooling asks, instead of
+//! hard-coding the mapping.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_core::{ecdsa, H160};
+
+sp_api::decl_runtime_apis! {
+	/// Conversions between the runtime's accounts and Ethereum addresses.
+	pub trait AccountConversionApi<AccountId> where AccountId: Codec {
+		/// The account of `address`, if its key signed a transaction or a relayed call.
+		fn address_to_account(address: H160) -> Option<AccountId>;
+
+		/// The address of the key controlling `account`, if the key signed a transaction or a
+		/// relayed call.
+		///
+		/// Accounts whose key is unknown, such as pallets' accounts, have no address: any address
+		/// made up for them would convert back to no account, or to another one.
+		fn account_to_address(account: AccountId) -> Option<H160>;
+
+		/// The address of the compressed secp256k1 key `public`, if it is a valid key.
+		fn public_to_address(public: ecdsa::Public) -> Option<H160>;
+
+		/// The account of the compressed secp256k1 key `public`.
+		fn public_to_account(public: ecdsa::Public) -> AccountId;
+	}
+}
@@ -0,0 +1,44 @@
+//! Aura authority keys on secp256k1.
+//!
+//! With these as the runtime's Aura `AuthorityId`, validators seal blocks with the same kind of key
+//! that controls their accounts, and a validator's operator key can double as its Aura key.
+//!
+//! Switching a runtime from sr25519 to these keys can't be migrated: the new keys are the
+//! validators' to generate, and blocks sealed with the old keys no longer verify once the upgrade
+//! is enacted. Chains that ran with sr25519 Aura keys have to be restarted from a new genesis.
+
+mod app {
+	use sp_application_crypto::{app_crypto, ecdsa, key_types::AURA};
+	app_crypto!(ecdsa, AURA);
+}
+
+sp_application_crypto::with_pair! {
+	/// An Aura authority key pair.
+	pub type AuthorityPair = app::Pair;
+}
+
+/// An Aura authority signature.
+pub type AuthoritySignature = app::Signature;
+
+/// An Aura authority identifier.
+pub type AuthorityId = app::Public;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::AccountId20;
+	use sp_core::{ecdsa, Pair};
+
+	#[test]
+	fn aura_key_has_the_address_of_the_account_key() {
+		let account = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let aura = AuthorityPair::from_string("//Alice", None).unwrap();
+
+		let aura_public: ecdsa::Public = aura.public().into();
+		assert_eq!(aura_public, account.public());
+		assert_eq!(AccountId20::from(aura_public), AccountId20::from(account.public()));
+
+		let signature = aura.sign(b"header hash");
+		assert!(AuthorityPair::verify(&signature, b"header hash", &aura.public()));
+	}
+}
@@ -0,0 +1,458 @@
+//! Ethereum-style accounts.
+//!
+//! Accounts on this chain are controlled by secp256k1 keys, the same keys Ethereum wallets use.
+//! [`AccountId20`] is the 20-byte address such a key has on Ethereum: the last 20 bytes of the
+//! keccak-256 hash of the uncompressed public key. It renders with the EIP-55 mixed-case checksum.
+//!
+//! [`aura`] and [`ocw`] provide Aura authority and offchain worker keys of the same kind, so
+//! neither block authorship nor offchain workers need a separate key family.
+//!
+//! [`rlp`] and [`transaction`] decode and encode Ethereum's wire formats, such as the raw
+//! transactions wallets sign, and recover their senders.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod aura;
+pub mod ocw;
+pub mod rlp;
+pub mod transaction;
+
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use sp_core::Pair;
+use sp_core::{ecdsa, H160};
+use sp_io::hashing::keccak_256;
+
+/// A 20-byte Ethereum address.
+#[derive(
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Copy,
+	Clone,
+	Default,
+	Hash,
+	Encode,
+	Decode,
+	MaxEncodedLen,
+	TypeInfo,
+)]
+pub struct AccountId20(pub [u8; 20]);
+
+impl AccountId20 {
+	/// Address of the uncompressed secp256k1 public key `public`, given as its 64-byte `x ‖ y`
+	/// coordinates without the `0x04` prefix.
+	pub fn from_uncompressed(public: &[u8; 64]) -> Self {
+		let hash = keccak_256(public);
+		let mut address = [0u8; 20];
+		address.copy_from_slice(&hash[12..]);
+		Self(address)
+	}
+
+	/// Address of the compressed secp256k1 public key `public`, or `None` if `public` isn't a point
+	/// of the curve, as untrusted input may not be.
+	pub fn from_public(public: &ecdsa::Public) -> Option<Self> {
+		let public = libsecp256k1::PublicKey::parse_compressed(&public.0).ok()?;
+		let mut uncompressed = [0u8; 64];
+		uncompressed.copy_from_slice(&public.serialize()[1..]);
+		Some(Self::from_uncompressed(&uncompressed))
+	}
+
+	/// The hex digits of the address, capitalized according to the EIP-55 checksum.
+	pub fn to_checksummed(&self) -> [u8; 40] {
+		let mut hex = [0u8; 40];
+		hex::encode_to_slice(self.0, &mut hex).expect("40 hex digits encode 20 bytes; qed");
+
+		let hash = keccak_256(&hex);
+		for (i, digit) in hex.iter_mut().enumerate() {
+			let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+			if nibble >= 8 {
+				digit.make_ascii_uppercase();
+			}
+		}
+		hex
+	}
+}
+
+impl core::fmt::Display for AccountId20 {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let hex = self.to_checksummed();
+		write!(f, "0x{}", core::str::from_utf8(&hex).expect("hex digits are ASCII; qed"))
+	}
+}
+
+impl core::fmt::Debug for AccountId20 {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		core::fmt::Display::fmt(self, f)
+	}
+}
+
+/// Error parsing an [`AccountId20`] from a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+	/// Not `0x` followed by 40 hex digits.
+	InvalidFormat,
+	/// Mixed-case address whose capitalization does not match its EIP-55 checksum.
+	InvalidChecksum,
+}
+
+impl core::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str(match self {
+			Self::InvalidFormat => "expected `0x` followed by 40 hex digits",
+			Self::InvalidChecksum => "invalid EIP-55 checksum",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+impl core::str::FromStr for AccountId20 {
+	type Err = ParseError;
+
+	/// Parse a `0x`-prefixed address. Following EIP-55, the checksum is only enforced if the
+	/// address mixes upper and lower case.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let digits = s.strip_prefix("0x").ok_or(ParseError::InvalidFormat)?;
+		let mut address = [0u8; 20];
+		hex::decode_to_slice(digits, &mut address).map_err(|_| ParseError::InvalidFormat)?;
+		let address = Self(address);
+
+		let has_lower = digits.bytes().any(|b| b.is_ascii_lowercase());
+		let has_upper = digits.bytes().any(|b| b.is_ascii_uppercase());
+		if has_lower && has_upper && address.to_checksummed() != digits.as_bytes() {
+			return Err(ParseError::InvalidChecksum)
+		}
+		Ok(address)
+	}
+}
+
+/// Serialized as its checksummed string.
+#[cfg(feature = "std")]
+impl serde::Serialize for AccountId20 {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for AccountId20 {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+impl From<[u8; 20]> for AccountId20 {
+	fn from(bytes: [u8; 20]) -> Self {
+		Self(bytes)
+	}
+}
+
+impl From<AccountId20> for [u8; 20] {
+	fn from(account: AccountId20) -> Self {
+		account.0
+	}
+}
+
+impl From<H160> for AccountId20 {
+	fn from(address: H160) -> Self {
+		Self(address.0)
+	}
+}
+
+impl From<AccountId20> for H160 {
+	fn from(account: AccountId20) -> Self {
+		H160(account.0)
+	}
+}
+
+impl AsRef<[u8]> for AccountId20 {
+	fn as_ref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl From<ecdsa::Public> for AccountId20 {
+	fn from(public: ecdsa::Public) -> Self {
+		Self::from_public(&public)
+			.expect("`ecdsa::Public` is a valid compressed secp256k1 key; qed")
+	}
+}
+
+/// The hash EIP-191 wallets sign for `personal_sign(message)`:
+/// `keccak256("\x19Ethereum Signed Message:\n" ‖ len(message) ‖ message)`.
+pub fn eip191_hash(message: &[u8]) -> [u8; 32] {
+	const PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+	// The length in decimal, most significant digit first.
+	let mut len = [0u8; 20];
+	let mut start = len.len();
+	let mut remaining = message.len();
+	loop {
+		start -= 1;
+		len[start] = b'0' + (remaining % 10) as u8;
+		remaining /= 10;
+		if remaining == 0 {
+			break
+		}
+	}
+
+	let mut prefixed = sp_std::vec::Vec::with_capacity(PREFIX.len() + len.len() + message.len());
+	prefixed.extend_from_slice(PREFIX);
+	prefixed.extend_from_slice(&len[start..]);
+	prefixed.extend_from_slice(message);
+	keccak_256(&prefixed)
+}
+
+/// Recover the signer of the 65-byte `r ‖ s ‖ v` `signature` over `message_hash`.
+///
+/// `v` may be either `0`/`1` or Ethereum's `27`/`28`. Returns the signer's address together with
+/// its compressed public key.
+pub fn ecrecover(
+	signature: &[u8; 65],
+	message_hash: &[u8; 32],
+) -> Option<(AccountId20, ecdsa::Public)> {
+	let public = sp_io::crypto::secp256k1_ecdsa_recover(signature, message_hash).ok()?;
+
+	let mut compressed = [0u8; 33];
+	compressed[0] = 2 + (public[63] & 1);
+	compressed[1..].copy_from_slice(&public[..32]);
+
+	Some((AccountId20::from_uncompressed(&public), ecdsa::Public::from_raw(compressed)))
+}
+
+/// Why a signature of a message doesn't verify, see [`verify_message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+	/// No key produces the signature, e.g. because its recovery id is out of range.
+	InvalidSignature,
+	/// The signature recovers to another address: it is another key's, or of another message.
+	WrongSigner(AccountId20),
+}
+
+impl core::fmt::Display for VerifyError {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		match self {
+			Self::InvalidSignature => f.write_str("invalid signature"),
+			Self::WrongSigner(signer) => write!(f, "the signature recovers to {}", signer),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+/// The address whose `personal_sign` signature of `message` is `signature`, i.e. which signed its
+/// [`eip191_hash`].
+pub fn recover_message_signer(message: &[u8], signature: &[u8; 65]) -> Option<AccountId20> {
+	ecrecover(signature, &eip191_hash(message)).map(|(address, _)| address)
+}
+
+/// Check that `signature` is the `personal_sign` signature of `message` by `address`.
+pub fn verify_message(
+	address: &AccountId20,
+	message: &[u8],
+	signature: &[u8; 65],
+) -> Result<(), VerifyError> {
+	match recover_message_signer(message, signature) {
+		Some(signer) if signer == *address => Ok(()),
+		Some(signer) => Err(VerifyError::WrongSigner(signer)),
+		None => Err(VerifyError::InvalidSignature),
+	}
+}
+
+/// A secp256k1 key pair, as held by Ethereum wallets.
+///
+/// Signs like [`ecdsa::Pair`], i.e. over the blake2-256 hash of the message, which is what the
+/// runtime verifies transaction signatures against.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct EthereumPair(ecdsa::Pair);
+
+#[cfg(feature = "std")]
+impl EthereumPair {
+	/// Key pair of the raw 32-byte private key `secret`, as exported by Ethereum wallets.
+	pub fn from_private_key(secret: &[u8; 32]) -> Self {
+		Self(ecdsa::Pair::from_seed(secret))
+	}
+
+	/// Key pair of a secret URI: a `0x`-prefixed private key, a dev seed like `//Alice` or a
+	/// BIP-39 phrase, optionally followed by derivation junctions.
+	///
+	/// Phrases are expanded the Substrate way, not along Ethereum's BIP-44 derivation path.
+	pub fn from_string(
+		suri: &str,
+		password: Option<&str>,
+	) -> Result<Self, sp_core::crypto::SecretStringError> {
+		ecdsa::Pair::from_string(suri, password).map(Self)
+	}
+
+	/// The compressed public key.
+	pub fn public(&self) -> ecdsa::Public {
+		self.0.public()
+	}
+
+	/// The Ethereum address of the key.
+	pub fn address(&self) -> AccountId20 {
+		self.public().into()
+	}
+
+	/// Sign `message`.
+	pub fn sign(&self, message: &[u8]) -> ecdsa::Signature {
+		self.0.sign(message)
+	}
+
+	/// Sign the 32-byte `message_hash` as is, e.g. an [`eip191_hash`], the way Ethereum wallets do.
+	pub fn sign_prehashed(&self, message_hash: &[u8; 32]) -> ecdsa::Signature {
+		self.0.sign_prehashed(message_hash)
+	}
+}
+
+#[cfg(feature = "std")]
+impl From<ecdsa::Pair> for EthereumPair {
+	fn from(pair: ecdsa::Pair) -> Self {
+		Self(pair)
+	}
+}
+
+#[cfg(feature = "std")]
+impl AsRef<ecdsa::Pair> for EthereumPair {
+	fn as_ref(&self) -> &ecdsa::Pair {
+		&self.0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::str::FromStr;
+	use hex_literal::hex;
+
+	// From https://eips.ethereum.org/EIPS/eip-55.
+	const CHECKSUMMED: [&str; 4] = [
+		"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+		"0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+		"0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+		"0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+	];
+
+	#[test]
+	fn displays_eip55_checksum() {
+		for address in CHECKSUMMED {
+			let parsed = AccountId20::from_str(&address.to_lowercase()).unwrap();
+			assert_eq!(parsed.to_string(), address);
+		}
+	}
+
+	#[test]
+	fn parsing_enforces_checksum_of_mixed_case() {
+		for address in CHECKSUMMED {
+			assert!(AccountId20::from_str(address).is_ok());
+			assert!(AccountId20::from_str(&address.to_uppercase().replace("0X", "0x")).is_ok());
+			assert_eq!(
+				AccountId20::from_str(&address.replacen('a', "A", 1)),
+				Err(ParseError::InvalidChecksum)
+			);
+		}
+		assert_eq!(
+			AccountId20::from_str("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+			Err(ParseError::InvalidFormat)
+		);
+		assert_eq!(
+			AccountId20::from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"),
+			Err(ParseError::InvalidFormat)
+		);
+	}
+
+	#[test]
+	fn serializes_as_checksummed_string() {
+		let address = AccountId20::from_str(CHECKSUMMED[0]).unwrap();
+		let json = format!("\"{}\"", CHECKSUMMED[0]);
+
+		assert_eq!(serde_json::to_string(&address).unwrap(), json);
+		assert_eq!(serde_json::from_str::<AccountId20>(&json).unwrap(), address);
+		assert!(serde_json::from_str::<AccountId20>("\"0x5aAeb6\"").is_err());
+	}
+
+	#[test]
+	fn derives_address_from_public_key() {
+		let pair = ecdsa::Pair::from_seed(&hex!(
+			"4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+		));
+
+		assert_eq!(
+			AccountId20::from(pair.public()).to_string(),
+			"0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"
+		);
+		assert_eq!(AccountId20::from_public(&pair.public()), Some(pair.public().into()));
+		// Not on the curve: x = 5 has no y.
+		let mut invalid = [0u8; 33];
+		invalid[0] = 2;
+		invalid[32] = 5;
+		assert_eq!(AccountId20::from_public(&ecdsa::Public::from_raw(invalid)), None);
+		assert_eq!(
+			EthereumPair::from_string(
+				"0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+				None
+			)
+			.unwrap()
+			.address(),
+			AccountId20::from(pair.public())
+		);
+	}
+
+	#[test]
+	fn eip191_hash_prefixes_decimal_length() {
+		let mut prefixed = b"\x19Ethereum Signed Message:\n12".to_vec();
+		prefixed.extend_from_slice(b"Hello world!");
+		assert_eq!(eip191_hash(b"Hello world!"), keccak_256(&prefixed));
+
+		prefixed = b"\x19Ethereum Signed Message:\n0".to_vec();
+		assert_eq!(eip191_hash(b""), keccak_256(&prefixed));
+		prefixed = b"\x19Ethereum Signed Message:\n100".to_vec();
+		prefixed.extend_from_slice(&[7; 100]);
+		assert_eq!(eip191_hash(&[7; 100]), keccak_256(&prefixed));
+	}
+
+	#[test]
+	fn recovers_signer_of_prehashed_message() {
+		let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let message_hash = keccak_256(b"relay");
+		let mut signature = pair.sign_prehashed(&message_hash).0;
+
+		let expected = Some((AccountId20::from(pair.public()), pair.public()));
+		assert_eq!(ecrecover(&signature, &message_hash), expected);
+		// Ethereum-style recovery id.
+		signature[64] += 27;
+		assert_eq!(ecrecover(&signature, &message_hash), expected);
+		assert_ne!(ecrecover(&signature, &keccak_256(b"relay all")), expected);
+	}
+
+	#[test]
+	fn verifies_personal_sign_signatures() {
+		// From the documentation of web3.js' `web3.eth.accounts.sign`.
+		let address = AccountId20::from_str("0x2c7536E3605D9C16a7a3D7b1898e529396a65c23").unwrap();
+		let mut signature = hex!(
+			"b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd"
+			"6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+		);
+
+		assert_eq!(recover_message_signer(b"Some data", &signature), Some(address));
+		assert_eq!(verify_message(&address, b"Some data", &signature), Ok(()));
+		assert!(matches!(
+			verify_message(&address, b"Some other data", &signature),
+			Err(VerifyError::WrongSigner(signer)) if signer != address
+		));
+
+		signature[64] = 31;
+		assert_eq!(recover_message_signer(b"Some data", &signature), None);
+		assert_eq!(
+			verify_message(&address, b"Some data", &signature),
+			Err(VerifyError::InvalidSignature)
+		);
+	}
+}
@@ -0,0 +1,26 @@
+//! Offchain worker keys on secp256k1.
+//!
+//! Offchain workers sign transactions with keys of [`KEY_TYPE`] from the node's keystore. Their
+//! signatures are ordinary ECDSA signatures over the blake2-256 hash of the payload, so the signer
+//! is the account of an Ethereum key and passes the allowlist like any other.
+
+use sp_core::crypto::KeyTypeId;
+
+/// Key type of the offchain worker keys, e.g. for `author_insertKey`.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"eocw");
+
+mod app {
+	use sp_application_crypto::{app_crypto, ecdsa};
+	app_crypto!(ecdsa, super::KEY_TYPE);
+}
+
+sp_application_crypto::with_pair! {
+	/// An offchain worker key pair.
+	pub type AuthorityPair = app::Pair;
+}
+
+/// An offchain worker signature.
+pub type AuthoritySignature = app::Signature;
+
+/// An offchain worker key.
+pub type AuthorityId = app::Public;
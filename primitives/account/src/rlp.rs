@@ -0,0 +1,425 @@
+//! Recursive length prefix (RLP), Ethereum's serialization of transactions, receipts and headers.
+//!
+//! An item is either a string of bytes or a list of items. [`Rlp`] decodes strictly: lengths
+//! that have a shorter encoding, single bytes wrapped in a string and integers with leading zeros
+//! are rejected, so every value has exactly one encoding and hashes of decoded values match.
+
+use sp_core::{H160, H256, U256};
+use sp_std::vec::Vec;
+
+/// Error decoding RLP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecoderError {
+	/// The input ends within an item.
+	InputTooShort,
+	/// Bytes follow the item.
+	InputTooLong,
+	/// A length or single byte encoded in more bytes than necessary.
+	NonCanonical,
+	/// A list where a string is expected.
+	ExpectedString,
+	/// A string where a list is expected.
+	ExpectedList,
+	/// An integer with leading zeros, or too large for its type.
+	InvalidInteger,
+	/// A string of the wrong length for a fixed-size value, such as an address.
+	InvalidLength,
+	/// A list with the wrong number of items.
+	InvalidItemCount,
+	/// A value its type doesn't allow, such as an unknown transaction type.
+	InvalidValue,
+}
+
+impl core::fmt::Display for DecoderError {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str(match self {
+			Self::InputTooShort => "input ends within an item",
+			Self::InputTooLong => "bytes follow the item",
+			Self::NonCanonical => "non-canonical encoding",
+			Self::ExpectedString => "expected a string, found a list",
+			Self::ExpectedList => "expected a list, found a string",
+			Self::InvalidInteger => "integer with leading zeros or out of range",
+			Self::InvalidLength => "string of the wrong length",
+			Self::InvalidItemCount => "list with the wrong number of items",
+			Self::InvalidValue => "invalid value",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecoderError {}
+
+/// An encoded item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rlp<'a> {
+	raw: &'a [u8],
+	payload: &'a [u8],
+	is_list: bool,
+}
+
+impl<'a> Rlp<'a> {
+	/// The item `raw` encodes, which must be all of `raw`.
+	pub fn new(raw: &'a [u8]) -> Result<Self, DecoderError> {
+		let (item, rest) = Self::split(raw)?;
+		if !rest.is_empty() {
+			return Err(DecoderError::InputTooLong)
+		}
+		Ok(item)
+	}
+
+	/// The first item of `input`, and the bytes after it.
+	pub fn split(input: &'a [u8]) -> Result<(Self, &'a [u8]), DecoderError> {
+		let (&prefix, rest) = input.split_first().ok_or(DecoderError::InputTooShort)?;
+		let (is_list, offset, len) = match prefix {
+			0x00..=0x7f => (false, 0, 1),
+			0x80..=0xb7 => (false, 1, (prefix - 0x80) as usize),
+			0xb8..=0xbf => {
+				let size = (prefix - 0xb7) as usize;
+				(false, 1 + size, long_length(rest, size)?)
+			},
+			0xc0..=0xf7 => (true, 1, (prefix - 0xc0) as usize),
+			0xf8..=0xff => {
+				let size = (prefix - 0xf7) as usize;
+				(true, 1 + size, long_length(rest, size)?)
+			},
+		};
+
+		let end = offset.checked_add(len).ok_or(DecoderError::InputTooShort)?;
+		if input.len() < end {
+			return Err(DecoderError::InputTooShort)
+		}
+		let payload = &input[offset..end];
+		// A single byte below 0x80 is its own encoding.
+		if prefix == 0x81 && payload[0] < 0x80 {
+			return Err(DecoderError::NonCanonical)
+		}
+
+		Ok((Self { raw: &input[..end], payload, is_list }, &input[end..]))
+	}
+
+	/// The encoding of the item, prefix included.
+	pub fn as_raw(&self) -> &'a [u8] {
+		self.raw
+	}
+
+	/// Whether the item is a list rather than a string.
+	pub fn is_list(&self) -> bool {
+		self.is_list
+	}
+
+	/// The bytes of a string.
+	pub fn data(&self) -> Result<&'a [u8], DecoderError> {
+		if self.is_list {
+			return Err(DecoderError::ExpectedString)
+		}
+		Ok(self.payload)
+	}
+
+	/// The items of a list.
+	pub fn items(&self) -> Result<Vec<Rlp<'a>>, DecoderError> {
+		if !self.is_list {
+			return Err(DecoderError::ExpectedList)
+		}
+		let mut items = Vec::new();
+		let mut rest = self.payload;
+		while !rest.is_empty() {
+			let (item, next) = Self::split(rest)?;
+			items.push(item);
+			rest = next;
+		}
+		Ok(items)
+	}
+
+	/// Decode the item as a `T`.
+	pub fn as_val<T: Decodable>(&self) -> Result<T, DecoderError> {
+		T::decode(self)
+	}
+
+	/// Decode the item as a list of `T`s.
+	pub fn as_list<T: Decodable>(&self) -> Result<Vec<T>, DecoderError> {
+		self.items()?.iter().map(T::decode).collect()
+	}
+}
+
+/// The length of a long string or list, given in the first `size` bytes of `input`.
+fn long_length(input: &[u8], size: usize) -> Result<usize, DecoderError> {
+	let bytes = input.get(..size).ok_or(DecoderError::InputTooShort)?;
+	if bytes[0] == 0 {
+		return Err(DecoderError::NonCanonical)
+	}
+	let len = bytes.iter().try_fold(0usize, |len, &byte| {
+		len.checked_mul(256)
+			.map(|len| len + byte as usize)
+			.ok_or(DecoderError::InputTooShort)
+	})?;
+	// Up to 55 bytes, the length fits in the prefix.
+	if len <= 55 {
+		return Err(DecoderError::NonCanonical)
+	}
+	Ok(len)
+}
+
+/// A value decoded from an RLP item.
+pub trait Decodable: Sized {
+	/// Decode `rlp`.
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError>;
+}
+
+/// A value encoded as an RLP item.
+pub trait Encodable {
+	/// Append the encoding of `self` to `out`.
+	fn rlp_append(&self, out: &mut Vec<u8>);
+}
+
+/// The encoding of `value`.
+pub fn encode<T: Encodable + ?Sized>(value: &T) -> Vec<u8> {
+	let mut out = Vec::new();
+	value.rlp_append(&mut out);
+	out
+}
+
+/// Append the prefix of a string (`offset` 0x80) or list (`offset` 0xc0) of `len` bytes.
+fn append_prefix(out: &mut Vec<u8>, offset: u8, len: usize) {
+	if len <= 55 {
+		out.push(offset + len as u8);
+	} else {
+		let len = (len as u64).to_be_bytes();
+		let start = len.iter().position(|&byte| byte != 0).unwrap_or(len.len() - 1);
+		out.push(offset + 55 + (len.len() - start) as u8);
+		out.extend_from_slice(&len[start..]);
+	}
+}
+
+/// A list, encoded item by item.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct List(Vec<u8>);
+
+impl List {
+	/// An empty list.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Append `value` to the list.
+	pub fn append<T: Encodable + ?Sized>(&mut self, value: &T) -> &mut Self {
+		value.rlp_append(&mut self.0);
+		self
+	}
+
+	/// Append a list of `values` to the list.
+	pub fn append_list<T: Encodable>(&mut self, values: &[T]) -> &mut Self {
+		let mut list = List::new();
+		for value in values {
+			list.append(value);
+		}
+		self.append(&list)
+	}
+
+	/// Append an item that is already `encoded`.
+	pub fn append_raw(&mut self, encoded: &[u8]) -> &mut Self {
+		self.0.extend_from_slice(encoded);
+		self
+	}
+}
+
+impl Encodable for List {
+	fn rlp_append(&self, out: &mut Vec<u8>) {
+		append_prefix(out, 0xc0, self.0.len());
+		out.extend_from_slice(&self.0);
+	}
+}
+
+impl Encodable for [u8] {
+	fn rlp_append(&self, out: &mut Vec<u8>) {
+		match self {
+			[byte] if *byte < 0x80 => out.push(*byte),
+			_ => {
+				append_prefix(out, 0x80, self.len());
+				out.extend_from_slice(self);
+			},
+		}
+	}
+}
+
+impl Encodable for Vec<u8> {
+	fn rlp_append(&self, out: &mut Vec<u8>) {
+		self[..].rlp_append(out)
+	}
+}
+
+impl Decodable for Vec<u8> {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		rlp.data().map(<[u8]>::to_vec)
+	}
+}
+
+/// Integers are encoded as their big-endian bytes without leading zeros, zero as the empty string.
+macro_rules! impl_uint {
+	($($type:ty),*) => {$(
+		impl Encodable for $type {
+			fn rlp_append(&self, out: &mut Vec<u8>) {
+				let bytes = self.to_be_bytes();
+				let start = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+				bytes[start..].rlp_append(out)
+			}
+		}
+
+		impl Decodable for $type {
+			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+				let mut be = [0u8; core::mem::size_of::<$type>()];
+				let bytes = uint_bytes(rlp, be.len())?;
+				be[be.len() - bytes.len()..].copy_from_slice(bytes);
+				Ok(<$type>::from_be_bytes(be))
+			}
+		}
+	)*};
+}
+
+impl_uint!(u8, u16, u32, u64, u128);
+
+impl Encodable for U256 {
+	fn rlp_append(&self, out: &mut Vec<u8>) {
+		let mut bytes = [0u8; 32];
+		self.to_big_endian(&mut bytes);
+		let start = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+		bytes[start..].rlp_append(out)
+	}
+}
+
+impl Decodable for U256 {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		uint_bytes(rlp, 32).map(U256::from_big_endian)
+	}
+}
+
+/// The bytes of an integer of at most `size` bytes.
+fn uint_bytes<'a>(rlp: &Rlp<'a>, size: usize) -> Result<&'a [u8], DecoderError> {
+	let bytes = rlp.data()?;
+	if bytes.first() == Some(&0) || bytes.len() > size {
+		return Err(DecoderError::InvalidInteger)
+	}
+	Ok(bytes)
+}
+
+/// Booleans are encoded as the integers 0 and 1.
+impl Encodable for bool {
+	fn rlp_append(&self, out: &mut Vec<u8>) {
+		(*self as u8).rlp_append(out)
+	}
+}
+
+impl Decodable for bool {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		match rlp.as_val::<u8>()? {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(DecoderError::InvalidValue),
+		}
+	}
+}
+
+/// Hashes and addresses are encoded as strings of their fixed length.
+macro_rules! impl_fixed_hash {
+	($($type:ty),*) => {$(
+		impl Encodable for $type {
+			fn rlp_append(&self, out: &mut Vec<u8>) {
+				self.as_bytes().rlp_append(out)
+			}
+		}
+
+		impl Decodable for $type {
+			fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+				let bytes = rlp.data()?;
+				if bytes.len() != <$type>::len_bytes() {
+					return Err(DecoderError::InvalidLength)
+				}
+				Ok(<$type>::from_slice(bytes))
+			}
+		}
+	)*};
+}
+
+impl_fixed_hash!(H160, H256);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	const LOREM: &[u8] = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+
+	// From https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/.
+	#[test]
+	fn encodes_examples() {
+		assert_eq!(encode(&b"dog"[..]), hex!("83646f67"));
+		let mut list = List::new();
+		list.append(&b"cat"[..]).append(&b"dog"[..]);
+		assert_eq!(encode(&list), hex!("c88363617483646f67"));
+		assert_eq!(encode(&b""[..]), hex!("80"));
+		assert_eq!(encode(&List::new()), hex!("c0"));
+		assert_eq!(encode(&0u64), hex!("80"));
+		assert_eq!(encode(&b"\x00"[..]), hex!("00"));
+		assert_eq!(encode(&15u8), hex!("0f"));
+		assert_eq!(encode(&1024u32), hex!("820400"));
+		assert_eq!(encode(&U256::from(1024)), hex!("820400"));
+		assert_eq!(encode(LOREM), [&hex!("b838")[..], LOREM].concat());
+
+		// The set theoretical representation of three, [ [], [[]], [ [], [[]] ] ].
+		let mut one = List::new();
+		one.append(&List::new());
+		let mut two = List::new();
+		two.append(&List::new()).append(&one);
+		let mut three = List::new();
+		three.append(&List::new()).append(&one).append(&two);
+		assert_eq!(encode(&three), hex!("c7c0c1c0c3c0c1c0"));
+	}
+
+	#[test]
+	fn decodes_examples() {
+		let list = hex!("c88363617483646f67");
+		let items = Rlp::new(&list).unwrap().items().unwrap();
+		assert_eq!(items.len(), 2);
+		assert_eq!(items[0].data(), Ok(&b"cat"[..]));
+		assert_eq!(items[1].as_raw(), hex!("83646f67"));
+		assert_eq!(
+			Rlp::new(&list).unwrap().as_list::<Vec<u8>>(),
+			Ok(vec![b"cat".to_vec(), b"dog".to_vec()])
+		);
+
+		assert_eq!(Rlp::new(&hex!("80")).unwrap().as_val::<u64>(), Ok(0));
+		assert_eq!(Rlp::new(&hex!("820400")).unwrap().as_val::<u16>(), Ok(1024));
+		assert_eq!(Rlp::new(&hex!("820400")).unwrap().as_val::<U256>(), Ok(1024.into()));
+		let long = [&hex!("b838")[..], LOREM].concat();
+		assert_eq!(Rlp::new(&long).unwrap().data(), Ok(LOREM));
+	}
+
+	#[test]
+	fn rejects_non_canonical_encodings() {
+		let decode = |raw: &[u8]| Rlp::new(raw).and_then(|rlp| rlp.as_val::<u64>());
+
+		// A single byte wrapped in a string.
+		assert_eq!(decode(&hex!("8105")), Err(DecoderError::NonCanonical));
+		// Leading zeros.
+		assert_eq!(decode(&hex!("820004")), Err(DecoderError::InvalidInteger));
+		assert_eq!(decode(&hex!("00")), Err(DecoderError::InvalidInteger));
+		// Too large for the type.
+		assert_eq!(decode(&hex!("89010000000000000000")), Err(DecoderError::InvalidInteger));
+		// A short string with a long length.
+		assert_eq!(Rlp::new(&hex!("b803646f67")), Err(DecoderError::NonCanonical));
+		// A long length with leading zeros.
+		let long = [&hex!("b90038")[..], LOREM].concat();
+		assert_eq!(Rlp::new(&long), Err(DecoderError::NonCanonical));
+
+		assert_eq!(Rlp::new(&hex!("83646f")), Err(DecoderError::InputTooShort));
+		assert_eq!(Rlp::new(&hex!("83646f6700")), Err(DecoderError::InputTooLong));
+		assert_eq!(Rlp::new(&hex!("c28364")).unwrap().items(), Err(DecoderError::InputTooShort));
+		assert_eq!(
+			Rlp::new(&hex!("c88363617483646f67")).unwrap().data(),
+			Err(DecoderError::ExpectedString)
+		);
+		assert_eq!(
+			Rlp::new(&hex!("83646f67")).unwrap().as_val::<H160>(),
+			Err(DecoderError::InvalidLength)
+		);
+	}
+}
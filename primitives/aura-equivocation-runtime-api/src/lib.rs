@@ -0,0 +1,43 @@
+//! Runtime API reporting Aura equivocations.
+//!
+//! Aura's own runtime API only lists the authorities. The node uses this one to report authorities
+//! that sealed two blocks for the same slot, which it notices while importing blocks.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+pub use sp_consensus_slots::EquivocationProof;
+use sp_std::vec::Vec;
+
+/// A proof that a key belongs to a validator, opaque to the node.
+#[derive(Decode, Encode, PartialEq, TypeInfo)]
+pub struct OpaqueKeyOwnershipProof(Vec<u8>);
+
+impl OpaqueKeyOwnershipProof {
+	/// Wrap an encoded key ownership proof.
+	pub fn new(encoded: Vec<u8>) -> Self {
+		Self(encoded)
+	}
+
+	/// Decode the key ownership proof.
+	pub fn decode<T: Decode>(self) -> Option<T> {
+		T::decode(&mut &self.0[..]).ok()
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Reporting of Aura authorities that sealed two blocks for the same slot.
+	pub trait AuraEquivocationApi<AuthorityId: Codec> {
+		/// Proof that `authority_id` is the Aura key of a validator of the current session.
+		fn generate_key_ownership_proof(
+			authority_id: AuthorityId,
+		) -> Option<OpaqueKeyOwnershipProof>;
+
+		/// Submit an unsigned extrinsic reporting `equivocation_proof` to the transaction pool.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: EquivocationProof<Block::Header, AuthorityId>,
+			key_owner_proof: OpaqueKeyOwnershipProof,
+		) -> Option<()>;
+	}
+}
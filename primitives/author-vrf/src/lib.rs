@@ -0,0 +1,158 @@
+//! Primitives of the author VRF, the runtime's source of randomness.
+//!
+//! The author of every block evaluates a verifiable random function (VRF) with its VRF session key
+//! on the previous randomness and the block number, and includes the output and its proof in the
+//! block through an inherent. The runtime checks the proof against the author's key, and the
+//! output becomes the new randomness.
+//!
+//! [`InherentDataProvider`] evaluates the VRF with a key from the node's keystore, and
+//! [`AuthorVrfApi`] tells the node which key and input that is.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_application_crypto::ByteArray;
+use sp_consensus_vrf::schnorrkel::PublicKey;
+pub use sp_consensus_vrf::schnorrkel::{Randomness, VRFOutput, VRFProof, RANDOMNESS_LENGTH};
+use sp_inherents::InherentIdentifier;
+use sp_runtime::KeyTypeId;
+use sp_std::vec::Vec;
+#[cfg(feature = "std")]
+use {
+	sp_inherents::InherentData,
+	sp_keystore::{
+		vrf::{VRFTranscriptData, VRFTranscriptValue},
+		SyncCryptoStore,
+	},
+};
+
+/// Key type of the VRF session key.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"avrf");
+
+/// Identifier of the author VRF inherent.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"authvrf0";
+
+/// Label of the VRF transcript.
+pub const VRF_TRANSCRIPT_LABEL: &[u8] = b"AuthorVRF";
+
+/// Context the randomness is derived from the VRF output in.
+pub const VRF_INOUT_CONTEXT: &[u8] = b"AuthorVRFInOutContext";
+
+mod app {
+	use sp_application_crypto::{app_crypto, sr25519};
+	app_crypto!(sr25519, super::KEY_TYPE);
+}
+
+/// A VRF session key.
+pub type AuthorityId = app::Public;
+
+/// A VRF session key pair.
+#[cfg(feature = "std")]
+pub type AuthorityPair = app::Pair;
+
+/// The VRF output of the block's author and its proof.
+pub type InherentType = (VRFOutput, VRFProof);
+
+/// The transcript the VRF of the block with `number` is evaluated on.
+pub fn make_transcript(randomness: &Randomness, number: u64) -> merlin::Transcript {
+	let mut transcript = merlin::Transcript::new(VRF_TRANSCRIPT_LABEL);
+	transcript.append_message(b"randomness", randomness);
+	transcript.append_u64(b"block number", number);
+	transcript
+}
+
+/// [`make_transcript`] in the form the keystore signs.
+#[cfg(feature = "std")]
+pub fn make_transcript_data(randomness: &Randomness, number: u64) -> VRFTranscriptData {
+	VRFTranscriptData {
+		label: VRF_TRANSCRIPT_LABEL,
+		items: vec![
+			("randomness", VRFTranscriptValue::Bytes(randomness.to_vec())),
+			("block number", VRFTranscriptValue::U64(number)),
+		],
+	}
+}
+
+/// The randomness of the block with `number`, if `proof` shows that `output` is the VRF output of
+/// `author` on the previous `randomness`.
+pub fn verify(
+	author: &AuthorityId,
+	randomness: &Randomness,
+	number: u64,
+	output: &VRFOutput,
+	proof: &VRFProof,
+) -> Option<Randomness> {
+	let public = PublicKey::from_bytes(author.as_slice()).ok()?;
+	let (inout, _) = public
+		.vrf_verify(make_transcript(randomness, number), &output.0, &proof.0)
+		.ok()?;
+	Some(inout.make_bytes(VRF_INOUT_CONTEXT))
+}
+
+/// Provides the author VRF of a block, if the keystore has the author's key.
+///
+/// Nodes that don't author the block provide nothing.
+#[cfg(feature = "std")]
+pub struct InherentDataProvider(Option<InherentType>);
+
+#[cfg(feature = "std")]
+impl InherentDataProvider {
+	/// Evaluate the VRF of `author` for the block with `number` on the previous `randomness`.
+	pub fn new(
+		keystore: &dyn SyncCryptoStore,
+		author: &AuthorityId,
+		randomness: &Randomness,
+		number: u64,
+	) -> Self {
+		let signature = SyncCryptoStore::sr25519_vrf_sign(
+			keystore,
+			KEY_TYPE,
+			&author.clone().into(),
+			make_transcript_data(randomness, number),
+		)
+		.ok()
+		.flatten();
+
+		Self(signature.map(|signature| (VRFOutput(signature.output), VRFProof(signature.proof))))
+	}
+
+	/// The VRF output and proof, if the keystore has the author's key.
+	pub fn vrf(&self) -> Option<&InherentType> {
+		self.0.as_ref()
+	}
+}
+
+#[cfg(feature = "std")]
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		match &self.0 {
+			Some(vrf) => inherent_data.put_data(INHERENT_IDENTIFIER, vrf),
+			None => Ok(()),
+		}
+	}
+
+	async fn try_handle_error(
+		&self,
+		_: &InherentIdentifier,
+		_: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		// The runtime checks the VRF when executing the block, not as an inherent check.
+		None
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The input of the author VRF.
+	pub trait AuthorVrfApi {
+		/// The VRF keys of the validators, in the order of the Aura authorities.
+		///
+		/// The author of a slot is at the index of the slot modulo their number.
+		fn authorities() -> Vec<AuthorityId>;
+
+		/// The randomness the VRF of the next block is evaluated on.
+		fn randomness() -> Randomness;
+	}
+}
@@ -0,0 +1,19 @@
+//! Runtime API behind the node's Ethereum-compatible RPC methods.
+//!
+//! Ethereum tooling prices transactions in gas. This API tells the node what the runtime's fees
+//! amount to in those terms, so that the node doesn't have to replicate the runtime's fee logic.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// Fees of the runtime in Ethereum terms.
+	pub trait EthApi<Balance> where Balance: Codec {
+		/// What one unit of gas costs in the next block, scaled by the current fee multiplier.
+		fn gas_price() -> Balance;
+
+		/// `ref_time` weight of one unit of gas.
+		fn weight_per_gas() -> u64;
+	}
+}
@@ -0,0 +1,19 @@
+//! Runtime API reporting the calls paused for maintenance.
+//!
+//! Lets the node tell operators which calls the runtime currently rejects, without decoding the
+//! maintenance pallet's storage itself.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Paused calls of the runtime.
+	pub trait MaintenanceApi {
+		/// Whether every call but the whitelisted ones is paused.
+		fn maintenance_mode() -> bool;
+
+		/// The paused pallets, with no call name, and the paused calls, by pallet and call name.
+		fn paused() -> Vec<(Vec<u8>, Option<Vec<u8>>)>;
+	}
+}
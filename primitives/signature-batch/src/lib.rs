@@ -0,0 +1,171 @@
+//! Signatures verified by the node ahead of block execution.
+//!
+//! Checking the signature of every extrinsic is the dominant cost of importing a block packed with
+//! ECDSA-signed transfers, and the runtime does it one extrinsic at a time. The node can do the
+//! same work up front on a thread pool and record every signature that checked out in
+//! [`VerifiedSignatures`], which is handed to the runtime as an externalities extension. The
+//! runtime's [`Preverified`] signature type asks the [`batch_verify`] host function whether its
+//! `(signature, signer, message)` triple is in that set and only verifies it itself when it is not.
+//!
+//! A lookup can only short-circuit a check the node already performed on the very same inputs, so
+//! executing a block with or without the extension yields the same state.
+//!
+//! [`batch_verify`] is not one of Substrate's host functions, so a runtime using [`Preverified`]
+//! can only be instantiated by executors extended with [`batch_verify::HostFunctions`]. Nodes have
+//! to be upgraded to provide it before such a runtime is enacted, and tools executing the runtime,
+//! like try-runtime, need it as well.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{IdentifyAccount, Lazy, Verify},
+	RuntimeDebug,
+};
+use sp_runtime_interface::runtime_interface;
+#[cfg(feature = "std")]
+use std::{collections::HashSet, sync::Arc};
+
+/// Identifies a `(signature, signer, message)` triple in [`VerifiedSignatures`].
+pub type SignatureKey = [u8; 32];
+
+/// Compute the [`SignatureKey`] of `signature` over `message` by `signer`.
+///
+/// `signature` and `signer` are the SCALE encodings of the signature and of the signing account.
+pub fn signature_key(signature: &[u8], signer: &[u8], message: &[u8]) -> SignatureKey {
+	let message_hash = sp_io::hashing::blake2_256(message);
+	(signature, signer, message_hash).using_encoded(sp_io::hashing::blake2_256)
+}
+
+/// Signatures the node has already checked, shared between block import and the executor.
+#[cfg(feature = "std")]
+#[derive(Clone, Default)]
+pub struct VerifiedSignatures(Arc<parking_lot::RwLock<HashSet<SignatureKey>>>);
+
+#[cfg(feature = "std")]
+impl VerifiedSignatures {
+	/// Whether the signature identified by `key` has been verified.
+	pub fn contains(&self, key: &SignatureKey) -> bool {
+		self.0.read().contains(key)
+	}
+
+	/// Record signatures that passed verification.
+	pub fn insert(&self, keys: impl IntoIterator<Item = SignatureKey>) {
+		self.0.write().extend(keys)
+	}
+
+	/// Forget signatures once the block they belong to has been imported.
+	pub fn remove(&self, keys: &[SignatureKey]) {
+		let mut verified = self.0.write();
+		for key in keys {
+			verified.remove(key);
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+	/// Gives the runtime read access to the node's [`VerifiedSignatures`].
+	pub struct VerifiedSignaturesExt(VerifiedSignatures);
+}
+
+/// Interface for looking up signatures pre-verified by the node.
+#[runtime_interface]
+pub trait BatchVerify {
+	/// Returns whether the node already verified the signature identified by `key`.
+	///
+	/// Always `false` if no [`VerifiedSignaturesExt`] is registered.
+	fn is_verified(&mut self, key: &SignatureKey) -> bool {
+		use sp_externalities::ExternalitiesExt;
+
+		self.extension::<VerifiedSignaturesExt>()
+			.map_or(false, |verified| verified.contains(key))
+	}
+}
+
+/// Signature wrapper that consults [`batch_verify`] before verifying the inner signature.
+///
+/// Encodes exactly like `S`, so it can replace a runtime's signature type without changing the
+/// transaction format.
+#[derive(Eq, PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Preverified<S>(pub S);
+
+impl<S> From<S> for Preverified<S> {
+	fn from(signature: S) -> Self {
+		Self(signature)
+	}
+}
+
+impl<S> Verify for Preverified<S>
+where
+	S: Verify + Encode,
+	<S::Signer as IdentifyAccount>::AccountId: Encode,
+{
+	type Signer = S::Signer;
+
+	fn verify<L: Lazy<[u8]>>(
+		&self,
+		mut msg: L,
+		signer: &<Self::Signer as IdentifyAccount>::AccountId,
+	) -> bool {
+		let key = signature_key(&self.0.encode(), &signer.encode(), msg.get());
+		batch_verify::is_verified(&key) || self.0.verify(msg, signer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{ecdsa, Pair};
+	use sp_io::TestExternalities;
+	use sp_runtime::{AccountId32, MultiSignature, MultiSigner};
+
+	fn signed(message: &[u8]) -> (Preverified<MultiSignature>, AccountId32) {
+		let pair = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let signer = MultiSigner::from(pair.public()).into_account();
+		(MultiSignature::from(pair.sign(message)).into(), signer)
+	}
+
+	fn key_of(
+		signature: &Preverified<MultiSignature>,
+		signer: &AccountId32,
+		msg: &[u8],
+	) -> SignatureKey {
+		signature_key(&signature.encode(), &signer.encode(), msg)
+	}
+
+	#[test]
+	fn lookup_requires_registered_extension() {
+		let (signature, signer) = signed(b"transfer");
+		let key = key_of(&signature, &signer, b"transfer");
+
+		TestExternalities::default().execute_with(|| assert!(!batch_verify::is_verified(&key)));
+
+		let verified = VerifiedSignatures::default();
+		verified.insert([key]);
+		let mut ext = TestExternalities::default();
+		ext.register_extension(VerifiedSignaturesExt(verified));
+		ext.execute_with(|| assert!(batch_verify::is_verified(&key)));
+	}
+
+	#[test]
+	fn preverified_matches_sequential_verification() {
+		let (signature, signer) = signed(b"transfer");
+		let verified = VerifiedSignatures::default();
+		verified.insert([key_of(&signature, &signer, b"transfer")]);
+
+		let mut ext = TestExternalities::default();
+		ext.register_extension(VerifiedSignaturesExt(verified));
+		ext.execute_with(|| {
+			assert!(signature.verify(&b"transfer"[..], &signer));
+			// A cached entry never vouches for a different message.
+			assert!(!signature.verify(&b"transfer all"[..], &signer));
+		});
+
+		TestExternalities::default().execute_with(|| {
+			assert!(signature.verify(&b"transfer"[..], &signer));
+			assert!(!signature.verify(&b"transfer all"[..], &signer));
+		});
+	}
+}
@@ -6,19 +6,23 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use account::aura::AuthorityId as AuraId;
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
+use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
+use pallet_session::historical as pallet_session_historical;
+use signature_batch::Preverified;
 use sp_api::impl_runtime_apis;
-use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{crypto::KeyTypeId, OpaqueMetadata, H160};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto,
+		IdentifyAccount, NumberFor, One, OpaqueKeys, SaturatedConversion, Verify,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
+	ApplyExtrinsicResult, FixedPointNumber, MultiSignature, MultiSigner,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -26,33 +30,44 @@ use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
 // A few exports that help ease life for downstream crates.
+use frame_support::traits::{ConstBool, EitherOfDiverse, EqualPrivilegeOnly};
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstU128, ConstU32, ConstU64, ConstU8, FindAuthor, KeyOwnerProofSystem, Randomness,
+		StorageInfo,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
-		IdentityFee, Weight,
+		IdentityFee, Weight, WeightToFee,
 	},
-	StorageValue,
+	ConsensusEngineId, PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::EnsureRoot;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_maintenance::Call as MaintenanceCall;
+pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
-use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{Perbill, Permill, Perquintill};
 
 /// Import the template pallet.
 pub use pallet_template;
 
+pub mod migrations;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
-pub type Signature = MultiSignature;
+///
+/// Signatures the node already checked while importing a block are looked up instead of being
+/// verified a second time, see [`signature_batch`]. The lookup is a host function only nodes with
+/// [`signature_batch::batch_verify::HostFunctions`] provide.
+pub type Signature = Preverified<MultiSignature>;
 
 /// Some way of identifying an account on the chain. We intentionally make it equivalent
 /// to the public key of our transaction signing scheme.
@@ -87,6 +102,8 @@ pub mod opaque {
 		pub struct SessionKeys {
 			pub aura: Aura,
 			pub grandpa: Grandpa,
+			pub im_online: ImOnline,
+			pub author_vrf: AuthorVrf,
 		}
 	}
 }
@@ -103,10 +120,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 126,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 14,
 	state_version: 1,
 };
 
@@ -127,6 +144,12 @@ pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
 pub const HOURS: BlockNumber = MINUTES * 60;
 pub const DAYS: BlockNumber = HOURS * 24;
 
+/// Weight of one unit of gas, for Ethereum tooling that thinks in gas.
+///
+/// Ethereum clients execute about 40 million gas per second, and a second of compute is 10^12
+/// units of `ref_time` weight, so one unit of gas is 25 000 of them.
+pub const WEIGHT_PER_GAS: u64 = 25_000;
+
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
 pub fn native_version() -> NativeVersion {
@@ -152,8 +175,8 @@ parameter_types! {
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Config for Runtime {
-	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = frame_support::traits::Everything;
+	/// The basic call filter to use in dispatchable: everything not paused for maintenance.
+	type BaseCallFilter = Maintenance;
 	/// Block & extrinsics weights: base values and limits.
 	type BlockWeights = BlockWeights;
 	/// The maximum length of a block (in bytes).
@@ -203,18 +226,29 @@ impl frame_system::Config for Runtime {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
-impl pallet_randomness_collective_flip::Config for Runtime {}
-
 impl pallet_aura::Config for Runtime {
+	/// Secp256k1 keys, which replaced sr25519 ones without a migration: upgrading a chain that
+	/// sealed with sr25519 keys stalls it, so such chains restart from a new genesis.
 	type AuthorityId = AuraId;
-	type DisabledValidators = ();
+	/// Offenders are disabled until they leave the session's validator set.
+	type DisabledValidators = Session;
+	type MaxAuthorities = ConstU32<32>;
+}
+
+/// The source of randomness, e.g. for `Randomness` in other pallets.
+impl pallet_author_vrf::Config for Runtime {
 	type MaxAuthorities = ConstU32<32>;
 }
 
+parameter_types! {
+	/// Equivocation reports are accepted for a day.
+	pub const ReportLongevity: u64 = DAYS as u64;
+}
+
 impl pallet_grandpa::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 
-	type KeyOwnerProofSystem = ();
+	type KeyOwnerProofSystem = Historical;
 
 	type KeyOwnerProof =
 		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -224,12 +258,105 @@ impl pallet_grandpa::Config for Runtime {
 		GrandpaId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation = ();
+	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
+		Self::KeyOwnerIdentification,
+		Offences,
+		ReportLongevity,
+	>;
 
 	type WeightInfo = ();
 	type MaxAuthorities = ConstU32<32>;
 }
 
+impl pallet_aura_equivocation::Config for Runtime {
+	type KeyOwnerProofSystem = Historical;
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, AuraId)>>::Proof;
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		AuraId,
+	)>>::IdentificationTuple;
+	type ReportOffence = Offences;
+	type ReportLongevity = ReportLongevity;
+}
+
+impl pallet_authorship::Config for Runtime {
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+	type UncleGenerations = ConstU32<0>;
+	type FilterUncle = ();
+	/// Authoring a block counts as a heartbeat.
+	type EventHandler = (ImOnline,);
+}
+
+impl pallet_offences::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+	/// Offenders are removed from the validator set.
+	type OnOffenceHandler = ValidatorSet;
+}
+
+impl pallet_validator_set::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	/// Validators are chosen by root or two thirds of the council.
+	type AddRemoveOrigin = EitherOfDiverse<EnsureRoot<AccountId>, CouncilFraction<2, 3>>;
+	type SignerToAccountId = EthereumAccount;
+	type MinValidators = ConstU32<1>;
+	/// As many as there can be Aura and GRANDPA authorities.
+	type MaxValidators = ConstU32<32>;
+}
+
+parameter_types! {
+	/// Validator set changes take effect at hourly session boundaries.
+	pub const SessionPeriod: BlockNumber = HOURS;
+	pub const SessionOffset: BlockNumber = 0;
+}
+
+/// Sessions of [`SessionPeriod`] blocks.
+pub type Sessions = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+
+impl pallet_session::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = Sessions;
+	type NextSessionRotation = Sessions;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_session::historical::Config for Runtime {
+	type FullIdentification = AccountId;
+	type FullIdentificationOf = ConvertInto;
+}
+
+parameter_types! {
+	pub const ImOnlineUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+}
+
+impl pallet_im_online::Config for Runtime {
+	type AuthorityId = ImOnlineId;
+	type RuntimeEvent = RuntimeEvent;
+	type NextSessionRotation = Sessions;
+	type ValidatorSet = ValidatorSet;
+	/// Validators that miss a session's heartbeat are removed from the set.
+	type ReportUnresponsiveness = Offences;
+	type UnsignedPriority = ImOnlineUnsignedPriority;
+	type WeightInfo = pallet_im_online::weights::SubstrateWeight<Runtime>;
+	type MaxKeys = ConstU32<32>;
+	type MaxPeerInHeartbeats = ConstU32<10_000>;
+	type MaxPeerDataEncodingSize = ConstU32<1_000>;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
+}
+
 impl pallet_timestamp::Config for Runtime {
 	/// A timestamp: milliseconds since the unix epoch.
 	type Moment = u64;
@@ -249,23 +376,50 @@ impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
 	/// The ubiquitous event type.
 	type RuntimeEvent = RuntimeEvent;
-	type DustRemoval = ();
+	type DustRemoval = pallet_fee_router::ToTreasury<Runtime>;
 	type ExistentialDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
 	type AccountStore = System;
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
-	pub FeeMultiplier: Multiplier = Multiplier::one();
+	/// Fees rise while blocks are more than a quarter full, measured against the weight normal
+	/// extrinsics may use (`NORMAL_DISPATCH_RATIO` of a block), and fall while they are less.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	/// How fast fees react to congestion: a day of full blocks raises them by about 40%.
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(3, 100_000);
+	/// Fees never drop below the plain weight and length fee.
+	pub MinimumMultiplier: Multiplier = Multiplier::one();
+	pub MaximumMultiplier: Multiplier = Multiplier::saturating_from_integer(1_000);
 }
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = CurrencyAdapter<Balances, pallet_fee_router::DealWithFees<Runtime>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightToFee = IdentityFee<Balance>;
 	type LengthToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
+	type FeeMultiplierUpdate = TargetedFeeAdjustment<
+		Self,
+		TargetBlockFullness,
+		AdjustmentVariable,
+		MinimumMultiplier,
+		MaximumMultiplier,
+	>;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
+}
+
+impl pallet_fee_router::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	/// The validator whose Aura key authored the current block.
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Runtime, Aura>;
+	type TreasuryAccount = TreasuryAccount;
+	type SharesOrigin = EnsureRoot<AccountId>;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -273,9 +427,286 @@ impl pallet_sudo::Config for Runtime {
 	type RuntimeCall = RuntimeCall;
 }
 
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = 100 * EXISTENTIAL_DEPOSIT;
+	pub const PreimageByteDeposit: Balance = EXISTENTIAL_DEPOSIT;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const TechnicalMotionDuration: BlockNumber = 3 * DAYS;
+}
+
+/// The council, which proposes referenda by motion.
+pub type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = ConstU32<100>;
+	type MaxMembers = ConstU32<100>;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// The technical committee, which fast-tracks referenda, e.g. runtime upgrades.
+pub type TechnicalCollective = pallet_collective::Instance2;
+impl pallet_collective::Config<TechnicalCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = ConstU32<100>;
+	type MaxMembers = ConstU32<100>;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// At least `N / D` of the council.
+type CouncilFraction<const N: u32, const D: u32> =
+	pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, N, D>;
+/// At least `N / D` of the technical committee.
+type TechnicalFraction<const N: u32, const D: u32> =
+	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, N, D>;
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+	pub const VotingPeriod: BlockNumber = 7 * DAYS;
+	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+	pub const EnactmentPeriod: BlockNumber = DAYS;
+	pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+	pub const MinimumDeposit: Balance = 1_000 * EXISTENTIAL_DEPOSIT;
+}
+
+impl pallet_democracy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type EnactmentPeriod = EnactmentPeriod;
+	type LaunchPeriod = LaunchPeriod;
+	type VotingPeriod = VotingPeriod;
+	type VoteLockingPeriod = EnactmentPeriod;
+	type MinimumDeposit = MinimumDeposit;
+	/// A council majority can have the next external referendum be majority-carries.
+	type ExternalOrigin = CouncilFraction<1, 2>;
+	/// Three quarters of the council can have the next external referendum be a simple majority.
+	type ExternalMajorityOrigin = CouncilFraction<3, 4>;
+	/// The whole council can have the next external referendum be negative-turnout-biased.
+	type ExternalDefaultOrigin = CouncilFraction<1, 1>;
+	/// Two thirds of the technical committee can fast-track external proposals.
+	type FastTrackOrigin = TechnicalFraction<2, 3>;
+	/// The whole technical committee can enact external proposals immediately.
+	type InstantOrigin = TechnicalFraction<1, 1>;
+	type InstantAllowed = ConstBool<true>;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	type CancellationOrigin = CouncilFraction<2, 3>;
+	type CancelProposalOrigin = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalFraction<1, 1>>;
+	type BlacklistOrigin = EnsureRoot<AccountId>;
+	/// Any technical committee member can veto a council proposal, once per proposal.
+	type VetoOrigin = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
+	type CooloffPeriod = CooloffPeriod;
+	/// Slashed deposits go to the treasury.
+	type Slash = pallet_fee_router::ToTreasury<Runtime>;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type MaxVotes = ConstU32<100>;
+	type WeightInfo = pallet_democracy::weights::SubstrateWeight<Runtime>;
+	type MaxProposals = ConstU32<100>;
+	type Preimages = Preimage;
+	type MaxDeposits = ConstU32<100>;
+	type MaxBlacklisted = ConstU32<100>;
+}
+
+/// Calls users may have relayed: anything but privileged and nested relayed calls.
+pub struct RelayableCalls;
+impl frame_support::traits::Contains<RuntimeCall> for RelayableCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		!matches!(call, RuntimeCall::Sudo(_) | RuntimeCall::Relayer(_))
+	}
+}
+
+/// The account of an Ethereum (secp256k1) key, the same one that key's signed transactions use.
+pub struct EthereumAccount;
+impl sp_runtime::traits::Convert<sp_core::ecdsa::Public, AccountId> for EthereumAccount {
+	fn convert(public: sp_core::ecdsa::Public) -> AccountId {
+		sp_runtime::MultiSigner::from(public).into_account()
+	}
+}
+
+/// Relayed calls need an allowlisted signer, like signed transactions.
+pub struct AllowlistedSigners;
+impl pallet_relayer::SignerFilter<AccountId, RuntimeCall> for AllowlistedSigners {
+	fn admit(public: &sp_core::ecdsa::Public, _: &AccountId, call: &RuntimeCall) -> bool {
+		Allowlist::check(account::AccountId20::from(public.clone()), call).is_ok()
+	}
+
+	fn admitted(public: &sp_core::ecdsa::Public, account: &AccountId) -> Weight {
+		Allowlist::link(account::AccountId20::from(public.clone()), account)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn admit_for_benchmark(public: &sp_core::ecdsa::Public) {
+		pallet_allowlist::Members::<Runtime>::insert(
+			account::AccountId20::from(public.clone()),
+			pallet_allowlist::Role::User,
+		);
+	}
+}
+
+/// Ethereum chain id of the chain, which EIP-712 and sign-in messages carry, matching the SS58
+/// prefix.
+pub const CHAIN_ID: u64 = 42;
+
+impl pallet_relayer::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type CallFilter = RelayableCalls;
+	type SignerToAccountId = EthereumAccount;
+	type SignerFilter = AllowlistedSigners;
+	type ChainId = ConstU64<CHAIN_ID>;
+	type WeightInfo = pallet_relayer::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_sponsorship::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type SignerToAccountId = EthereumAccount;
+}
+
+/// Sudo calls are reserved for allowlisted operators.
+pub struct OperatorCalls;
+impl frame_support::traits::Contains<RuntimeCall> for OperatorCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::Sudo(_))
+	}
+}
+
+impl pallet_allowlist::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type SignerToAccountId = EthereumAccount;
+	type OperatorCalls = OperatorCalls;
+}
+
+/// Calls that stay available during maintenance: sudo and governance, to manage the chain, the
+/// timestamp and author VRF inherents, without which no block can be authored, and validators'
+/// heartbeats and equivocation reports.
+pub struct MaintenanceWhitelist;
+impl frame_support::traits::Contains<RuntimeCall> for MaintenanceWhitelist {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::Sudo(_) |
+				RuntimeCall::Council(_) |
+				RuntimeCall::TechnicalCommittee(_) |
+				RuntimeCall::Democracy(_) |
+				RuntimeCall::Timestamp(_) |
+				RuntimeCall::AuthorVrf(_) |
+				RuntimeCall::ImOnline(_) |
+				RuntimeCall::Grandpa(_) |
+				RuntimeCall::AuraEquivocation(_)
+		)
+	}
+}
+
+impl pallet_maintenance::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WhitelistedCalls = MaintenanceWhitelist;
+	/// Two thirds of the technical committee can react to incidents without a referendum.
+	type MaintenanceOrigin = EitherOfDiverse<EnsureRoot<AccountId>, TechnicalFraction<2, 3>>;
+	type MaxNameLen = ConstU32<64>;
+}
+
+impl pallet_eth_bridge::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	/// Relayers and the deposit event are managed by root or two thirds of the council.
+	type AdminOrigin = EitherOfDiverse<EnsureRoot<AccountId>, CouncilFraction<2, 3>>;
+	type SignerToAccountId = EthereumAccount;
+	type MaxRelayers = ConstU32<16>;
+	/// No single relayer can import a header.
+	type RequiredConfirmations = ConstU32<2>;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type AuthorityId = pallet_template::crypto::EthereumOcwAuthId;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = MultiSigner;
+	type Signature = MultiSignature;
+}
+
+/// Offchain workers sign transactions like users do, so only allowlisted Ethereum keys can submit
+/// them.
+impl<C> frame_system::offchain::CreateSignedTransaction<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	fn create_transaction<S: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: MultiSigner,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(
+		RuntimeCall,
+		<UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload,
+	)> {
+		use codec::Encode;
+
+		// Valid for half of the block hashes kept, as a power of two.
+		let period = BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2);
+		// The parent block, as the transaction is included in a later one.
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let signer = match &public {
+			MultiSigner::Ecdsa(public) => Some(public.clone()),
+			_ => None,
+		};
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::new(),
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(generic::Era::mortal(period as u64, current_block)),
+			frame_system::CheckNonce::from(nonce),
+			frame_system::CheckWeight::new(),
+			pallet_sponsorship::ChargeSponsoredTransactionPayment::new(0),
+			pallet_allowlist::CheckAllowlist::new(signer),
+		);
+		let payload = SignedPayload::new(call, extra).ok()?;
+		let signature = payload.using_encoded(|payload| S::sign(payload, public))?;
+		let (call, extra, _) = payload.deconstruct();
+
+		Some((call, (sp_runtime::MultiAddress::Id(account), signature.into(), extra)))
+	}
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -287,13 +718,32 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system,
-		RandomnessCollectiveFlip: pallet_randomness_collective_flip,
 		Timestamp: pallet_timestamp,
+		Authorship: pallet_authorship,
+		// Before Aura and GRANDPA, whose authorities the session sets at genesis.
+		ValidatorSet: pallet_validator_set,
+		Session: pallet_session,
+		Historical: pallet_session_historical,
+		Offences: pallet_offences,
 		Aura: pallet_aura,
+		AuthorVrf: pallet_author_vrf,
 		Grandpa: pallet_grandpa,
+		ImOnline: pallet_im_online,
+		AuraEquivocation: pallet_aura_equivocation,
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
+		FeeRouter: pallet_fee_router,
 		Sudo: pallet_sudo,
+		Preimage: pallet_preimage,
+		Scheduler: pallet_scheduler,
+		Council: pallet_collective<Instance1>,
+		TechnicalCommittee: pallet_collective<Instance2>,
+		Democracy: pallet_democracy,
+		Relayer: pallet_relayer,
+		Sponsorship: pallet_sponsorship,
+		Allowlist: pallet_allowlist,
+		Maintenance: pallet_maintenance,
+		EthBridge: pallet_eth_bridge,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
 	}
@@ -314,7 +764,8 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_sponsorship::ChargeSponsoredTransactionPayment<Runtime>,
+	pallet_allowlist::CheckAllowlist<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -329,6 +780,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	migrations::Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -341,11 +793,26 @@ mod benches {
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
+		[pallet_collective, Council]
+		[pallet_democracy, Democracy]
+		[pallet_im_online, ImOnline]
+		[pallet_preimage, Preimage]
+		[pallet_scheduler, Scheduler]
 		[pallet_timestamp, Timestamp]
+		[pallet_relayer, Relayer]
 		[pallet_template, TemplateModule]
 	);
 }
 
+/// Fee of one unit of gas in the next block: the fee of [`WEIGHT_PER_GAS`], scaled by the fee
+/// multiplier.
+pub fn gas_price() -> Balance {
+	let fee = <Runtime as pallet_transaction_payment::Config>::WeightToFee::weight_to_fee(
+		&Weight::from_ref_time(WEIGHT_PER_GAS),
+	);
+	TransactionPayment::next_fee_multiplier().saturating_mul_int(fee)
+}
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -426,6 +893,43 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl aura_equivocation_runtime_api::AuraEquivocationApi<Block, AuraId> for Runtime {
+		fn generate_key_ownership_proof(
+			authority_id: AuraId,
+		) -> Option<aura_equivocation_runtime_api::OpaqueKeyOwnershipProof> {
+			use codec::Encode;
+
+			Historical::prove((<AuraId as sp_runtime::RuntimeAppPublic>::ID, authority_id))
+				.map(|proof| proof.encode())
+				.map(aura_equivocation_runtime_api::OpaqueKeyOwnershipProof::new)
+		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: aura_equivocation_runtime_api::EquivocationProof<
+				<Block as BlockT>::Header,
+				AuraId,
+			>,
+			key_owner_proof: aura_equivocation_runtime_api::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			AuraEquivocation::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
+		}
+	}
+
+	impl author_vrf::AuthorVrfApi<Block> for Runtime {
+		fn authorities() -> Vec<author_vrf::AuthorityId> {
+			AuthorVrf::authorities().into_inner()
+		}
+
+		fn randomness() -> author_vrf::Randomness {
+			AuthorVrf::randomness()
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> GrandpaAuthorityList {
 			Grandpa::grandpa_authorities()
@@ -436,23 +940,26 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: fg_primitives::EquivocationProof<
+			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(equivocation_proof, key_owner_proof)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: fg_primitives::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			use codec::Encode;
+
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|proof| proof.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
 		}
 	}
 
@@ -477,6 +984,44 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl eth_rpc_runtime_api::EthApi<Block, Balance> for Runtime {
+		fn gas_price() -> Balance {
+			gas_price()
+		}
+
+		fn weight_per_gas() -> u64 {
+			WEIGHT_PER_GAS
+		}
+	}
+
+	impl maintenance_runtime_api::MaintenanceApi<Block> for Runtime {
+		fn maintenance_mode() -> bool {
+			Maintenance::maintenance_mode()
+		}
+
+		fn paused() -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+			Maintenance::paused()
+		}
+	}
+
+	impl account_conversion_runtime_api::AccountConversionApi<Block, AccountId> for Runtime {
+		fn address_to_account(address: H160) -> Option<AccountId> {
+			Allowlist::account(account::AccountId20::from(address))
+		}
+
+		fn account_to_address(account: AccountId) -> Option<H160> {
+			Allowlist::address(&account).map(Into::into)
+		}
+
+		fn public_to_address(public: sp_core::ecdsa::Public) -> Option<H160> {
+			account::AccountId20::from_public(&public).map(Into::into)
+		}
+
+		fn public_to_account(public: sp_core::ecdsa::Public) -> AccountId {
+			<EthereumAccount as sp_runtime::traits::Convert<_, _>>::convert(public)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
 		for Runtime
 	{
@@ -560,10 +1105,602 @@ impl_runtime_apis! {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::traits::WhitelistedStorageKeys;
-	use sp_core::hexdisplay::HexDisplay;
+	use frame_support::{
+		assert_noop, assert_ok, dispatch::DispatchClass, traits::WhitelistedStorageKeys,
+	};
+	use signature_batch::{signature_key, SignatureKey, VerifiedSignatures, VerifiedSignaturesExt};
+	use sp_core::{ecdsa, ed25519, hexdisplay::HexDisplay, sr25519, Encode, Pair};
+	use sp_io::TestExternalities;
+	use sp_runtime::{
+		traits::Convert,
+		transaction_validity::{InvalidTransaction, TransactionValidityError},
+		MultiSigner, StateVersion,
+	};
 	use std::collections::HashSet;
 
+	/// Genesis with the secp256k1 keys of `seeds` as validators, without which `pallet_session`
+	/// can't start.
+	fn genesis(seeds: &[&str]) -> GenesisConfig {
+		let validators: Vec<_> = seeds
+			.iter()
+			.map(|seed| ecdsa::Pair::from_string(&format!("//{}", seed), None).unwrap().public())
+			.collect();
+		let keys = seeds
+			.iter()
+			.zip(&validators)
+			.map(|(seed, validator)| {
+				let seed = format!("//{}", seed);
+				let account = EthereumAccount::convert(validator.clone());
+				let keys = opaque::SessionKeys {
+					aura: ecdsa::Pair::from_string(&seed, None).unwrap().public().into(),
+					grandpa: ed25519::Pair::from_string(&seed, None).unwrap().public().into(),
+					im_online: sr25519::Pair::from_string(&seed, None).unwrap().public().into(),
+					author_vrf: sr25519::Pair::from_string(&seed, None).unwrap().public().into(),
+				};
+				(account.clone(), account, keys)
+			})
+			.collect();
+
+		GenesisConfig {
+			validator_set: ValidatorSetConfig { validators },
+			session: SessionConfig { keys },
+			..Default::default()
+		}
+	}
+
+	/// Storage with `signers` endowed and allowlisted.
+	fn new_test_ext(signers: &[&ecdsa::Pair]) -> TestExternalities {
+		GenesisConfig {
+			balances: BalancesConfig {
+				balances: signers
+					.iter()
+					.map(|pair| (EthereumAccount::convert(pair.public()), 1 << 60))
+					.collect(),
+			},
+			allowlist: AllowlistConfig {
+				members: signers
+					.iter()
+					.map(|pair| (pair.public().into(), pallet_allowlist::Role::User))
+					.collect(),
+			},
+			..genesis(&["Alice"])
+		}
+		.build_storage()
+		.unwrap()
+		.into()
+	}
+
+	/// Build a transfer signed by `signer` along with the key the node would record for it.
+	fn signed_transfer(
+		signer: &ecdsa::Pair,
+		dest: AccountId,
+	) -> (UncheckedExtrinsic, SignatureKey) {
+		let call = RuntimeCall::Balances(BalancesCall::transfer {
+			dest: dest.into(),
+			value: EXISTENTIAL_DEPOSIT,
+		});
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::new(),
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(generic::Era::Immortal),
+			frame_system::CheckNonce::from(0),
+			frame_system::CheckWeight::new(),
+			pallet_sponsorship::ChargeSponsoredTransactionPayment::new(0),
+			pallet_allowlist::CheckAllowlist::new(Some(signer.public())),
+		);
+		let payload = SignedPayload::new(call.clone(), extra.clone()).unwrap();
+		let sender = MultiSigner::from(signer.public()).into_account();
+		let signature: Signature =
+			MultiSignature::from(payload.using_encoded(|e| signer.sign(e))).into();
+		let key =
+			payload.using_encoded(|e| signature_key(&signature.encode(), &sender.encode(), e));
+
+		(UncheckedExtrinsic::new_signed(call, sender.into(), signature, extra), key)
+	}
+
+	#[test]
+	fn preverified_signatures_yield_same_state_root() {
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let initialize = || System::initialize(&1, &Default::default(), &Default::default());
+
+		let (xt, key) = new_test_ext(&[&alice]).execute_with(|| {
+			initialize();
+			signed_transfer(&alice, AccountId::from([2u8; 32]))
+		});
+		let import = |mut ext: TestExternalities| {
+			ext.execute_with(|| {
+				initialize();
+				assert_eq!(Executive::apply_extrinsic(xt.clone()), Ok(Ok(())));
+				sp_io::storage::root(StateVersion::V1)
+			})
+		};
+
+		let sequential = import(new_test_ext(&[&alice]));
+
+		let verified = VerifiedSignatures::default();
+		verified.insert([key]);
+		let mut ext = new_test_ext(&[&alice]);
+		ext.register_extension(VerifiedSignaturesExt(verified));
+		assert_eq!(import(ext), sequential);
+	}
+
+	#[test]
+	fn import_looks_up_preverified_signatures() {
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let bob = ecdsa::Pair::from_string("//Bob", None).unwrap();
+		let initialize = || System::initialize(&1, &Default::default(), &Default::default());
+
+		// Alice's transfer signed by Bob, recorded as verified, which only a lookup accepts.
+		let (xt, key) = new_test_ext(&[&alice]).execute_with(|| {
+			initialize();
+			let (mut xt, _) = signed_transfer(&alice, AccountId::from([2u8; 32]));
+			let (sender, _, extra) = xt.signature.take().unwrap();
+			let payload = SignedPayload::new(xt.function.clone(), extra.clone()).unwrap();
+			let signature: Signature =
+				MultiSignature::from(payload.using_encoded(|e| bob.sign(e))).into();
+			let signer = MultiSigner::from(alice.public()).into_account();
+			let key =
+				payload.using_encoded(|e| signature_key(&signature.encode(), &signer.encode(), e));
+			xt.signature = Some((sender, signature, extra));
+			(xt, key)
+		});
+
+		new_test_ext(&[&alice]).execute_with(|| {
+			initialize();
+			assert_eq!(
+				Executive::apply_extrinsic(xt.clone()),
+				Err(TransactionValidityError::Invalid(InvalidTransaction::BadProof))
+			);
+		});
+
+		let verified = VerifiedSignatures::default();
+		verified.insert([key]);
+		let mut ext = new_test_ext(&[&alice]);
+		ext.register_extension(VerifiedSignaturesExt(verified));
+		ext.execute_with(|| {
+			initialize();
+			assert_eq!(Executive::apply_extrinsic(xt), Ok(Ok(())));
+		});
+	}
+
+	#[test]
+	fn signers_outside_allowlist_are_rejected() {
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+
+		new_test_ext(&[&alice]).execute_with(|| {
+			System::initialize(&1, &Default::default(), &Default::default());
+			assert_ok!(Allowlist::remove_member(RuntimeOrigin::root(), alice.public().into()));
+
+			let (xt, _) = signed_transfer(&alice, AccountId::from([2u8; 32]));
+			assert_eq!(
+				Executive::apply_extrinsic(xt),
+				Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(
+					pallet_allowlist::NOT_ALLOWLISTED
+				)))
+			);
+		});
+	}
+
+	#[test]
+	fn relayed_calls_need_an_allowlisted_signer() {
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let bob = ecdsa::Pair::from_string("//Bob", None).unwrap();
+		let remark = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+		let relay = |signer: &ecdsa::Pair| {
+			let hash = Relayer::signing_hash(&remark.encode(), 0, 10);
+			Relayer::relay(
+				RuntimeOrigin::signed(EthereumAccount::convert(alice.public())),
+				Box::new(remark.clone()),
+				account::AccountId20::from(signer.public()).into(),
+				0,
+				10,
+				signer.sign_prehashed(&hash).0,
+			)
+		};
+
+		new_test_ext(&[&alice]).execute_with(|| {
+			System::initialize(&1, &Default::default(), &Default::default());
+
+			assert_noop!(relay(&bob), pallet_relayer::Error::<Runtime>::SignerFiltered);
+			assert_ok!(relay(&alice));
+			// Relayed signers are linked like those of transactions.
+			assert_eq!(
+				Allowlist::account(account::AccountId20::from(alice.public())),
+				Some(EthereumAccount::convert(alice.public()))
+			);
+		});
+	}
+
+	#[test]
+	fn maintenance_keeps_sudo_and_timestamp_available() {
+		use frame_support::traits::Contains;
+
+		let transfer = RuntimeCall::Balances(BalancesCall::transfer {
+			dest: AccountId::from([2u8; 32]).into(),
+			value: EXISTENTIAL_DEPOSIT,
+		});
+		let set_timestamp = RuntimeCall::Timestamp(pallet_timestamp::Call::set { now: 1 });
+		let exit = RuntimeCall::Maintenance(pallet_maintenance::Call::exit_maintenance {});
+		let sudo = RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(exit.clone()) });
+
+		new_test_ext(&[]).execute_with(|| {
+			assert_ok!(Maintenance::enter_maintenance(RuntimeOrigin::root()));
+
+			assert!(!Maintenance::contains(&transfer));
+			assert!(Maintenance::contains(&set_timestamp));
+			assert!(Maintenance::contains(&sudo));
+			assert!(Maintenance::contains(&exit));
+		});
+	}
+
+	#[test]
+	fn technical_committee_can_enter_maintenance() {
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let member = EthereumAccount::convert(alice.public());
+		let enter = RuntimeCall::Maintenance(pallet_maintenance::Call::enter_maintenance {});
+
+		let mut ext: TestExternalities = GenesisConfig {
+			technical_committee: TechnicalCommitteeConfig {
+				members: vec![member.clone()],
+				phantom: Default::default(),
+			},
+			..genesis(&["Alice"])
+		}
+		.build_storage()
+		.unwrap()
+		.into();
+		ext.execute_with(|| {
+			// A motion with a threshold of one is executed right away.
+			assert_ok!(TechnicalCommittee::propose(
+				RuntimeOrigin::signed(member),
+				1,
+				Box::new(enter.clone()),
+				enter.encoded_size() as u32
+			));
+			assert!(Maintenance::maintenance_mode());
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_retires_sudo() {
+		use frame_support::traits::StorageVersion;
+		use migrations::RetireSudo;
+
+		let alice =
+			EthereumAccount::convert(ecdsa::Pair::from_string("//Alice", None).unwrap().public());
+		let remark = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+
+		let mut ext: TestExternalities =
+			GenesisConfig { sudo: SudoConfig { key: Some(alice.clone()) }, ..genesis(&["Alice"]) }
+				.build_storage()
+				.unwrap()
+				.into();
+		ext.execute_with(|| {
+			assert!(!RetireSudo::is_retired());
+			assert_ok!(Sudo::sudo(RuntimeOrigin::signed(alice.clone()), Box::new(remark.clone())));
+
+			Executive::execute_on_runtime_upgrade();
+
+			assert!(RetireSudo::is_retired());
+			assert_noop!(
+				Sudo::sudo(RuntimeOrigin::signed(alice.clone()), Box::new(remark)),
+				pallet_sudo::Error::<Runtime>::RequireSudo
+			);
+			assert_eq!(StorageVersion::get::<Sudo>(), RetireSudo::RETIRED);
+
+			// Running the migration again leaves governance alone.
+			assert_ok!(Council::set_members(RuntimeOrigin::root(), vec![], None, 0));
+			Executive::execute_on_runtime_upgrade();
+			assert!(Council::members().is_empty());
+		});
+	}
+
+	#[test]
+	fn root_is_reachable_after_retiring_sudo() {
+		use frame_support::traits::{OnInitialize, StorePreimage};
+		use pallet_democracy::{AccountVote, Conviction, Vote};
+
+		let alice =
+			EthereumAccount::convert(ecdsa::Pair::from_string("//Alice", None).unwrap().public());
+		let bob = AccountId::from([2u8; 32]);
+		// Only root can set balances.
+		let set_balance = RuntimeCall::Balances(BalancesCall::set_balance {
+			who: bob.clone().into(),
+			new_free: EXISTENTIAL_DEPOSIT,
+			new_reserved: 0,
+		});
+		let run_to = |block: BlockNumber| {
+			while System::block_number() < block {
+				System::set_block_number(System::block_number() + 1);
+				Democracy::on_initialize(System::block_number());
+				Scheduler::on_initialize(System::block_number());
+			}
+		};
+
+		// The state before the upgrade: sudo, and a council and technical committee without
+		// members.
+		let mut ext: TestExternalities = GenesisConfig {
+			sudo: SudoConfig { key: Some(alice.clone()) },
+			balances: BalancesConfig { balances: vec![(alice.clone(), 1 << 60)] },
+			..genesis(&["Alice"])
+		}
+		.build_storage()
+		.unwrap()
+		.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			assert!(Council::members().is_empty());
+			assert!(TechnicalCommittee::members().is_empty());
+
+			Executive::execute_on_runtime_upgrade();
+
+			assert_eq!(Council::members(), vec![alice.clone()]);
+			assert_eq!(TechnicalCommittee::members(), vec![alice.clone()]);
+
+			// The council proposes the call, the technical committee fast-tracks it, and the
+			// referendum passes.
+			let proposal = Preimage::bound(set_balance).unwrap();
+			let external =
+				RuntimeCall::Democracy(pallet_democracy::Call::external_propose_majority {
+					proposal: proposal.clone(),
+				});
+			assert_ok!(Council::propose(
+				RuntimeOrigin::signed(alice.clone()),
+				1,
+				Box::new(external.clone()),
+				external.encoded_size() as u32
+			));
+			let fast_track = RuntimeCall::Democracy(pallet_democracy::Call::fast_track {
+				proposal_hash: proposal.hash(),
+				voting_period: 1,
+				delay: 1,
+			});
+			assert_ok!(TechnicalCommittee::propose(
+				RuntimeOrigin::signed(alice.clone()),
+				1,
+				Box::new(fast_track.clone()),
+				fast_track.encoded_size() as u32
+			));
+			let aye = Vote { aye: true, conviction: Conviction::Locked1x };
+			assert_ok!(Democracy::vote(
+				RuntimeOrigin::signed(alice),
+				0,
+				AccountVote::Standard { vote: aye, balance: EXISTENTIAL_DEPOSIT }
+			));
+
+			run_to(3);
+
+			assert_eq!(Balances::free_balance(&bob), EXISTENTIAL_DEPOSIT);
+		});
+	}
+
+	/// Two prevotes of `signer` for different blocks in the same round of the set `set_id`.
+	fn equivocation_proof(
+		signer: &ed25519::Pair,
+		set_id: fg_primitives::SetId,
+	) -> fg_primitives::EquivocationProof<Hash, BlockNumber> {
+		let round = 1;
+		let prevote = |target_hash| {
+			let prevote = finality_grandpa::Prevote { target_hash, target_number: 1 };
+			let message = finality_grandpa::Message::Prevote(prevote.clone());
+			let payload = fg_primitives::localized_payload(round, set_id, &message);
+			(prevote, signer.sign(&payload).into())
+		};
+
+		fg_primitives::EquivocationProof::new(
+			set_id,
+			fg_primitives::Equivocation::Prevote(finality_grandpa::Equivocation {
+				round_number: round,
+				identity: signer.public().into(),
+				first: prevote(Hash::repeat_byte(1)),
+				second: prevote(Hash::repeat_byte(2)),
+			}),
+		)
+	}
+
+	/// The accounts of the validator set.
+	fn validators() -> Vec<AccountId> {
+		pallet_validator_set::Validators::<Runtime>::get()
+			.into_iter()
+			.map(|(_, account)| account)
+			.collect()
+	}
+
+	#[test]
+	fn grandpa_equivocators_are_removed_from_the_validator_set() {
+		let bob = ed25519::Pair::from_string("//Bob", None).unwrap();
+		let bob_account =
+			EthereumAccount::convert(ecdsa::Pair::from_string("//Bob", None).unwrap().public());
+
+		let mut ext: TestExternalities = genesis(&["Alice", "Bob"]).build_storage().unwrap().into();
+		ext.execute_with(|| {
+			System::initialize(&1, &Default::default(), &Default::default());
+			assert!(validators().contains(&bob_account));
+
+			let report = || {
+				Grandpa::report_equivocation_unsigned(
+					RuntimeOrigin::none(),
+					Box::new(equivocation_proof(&bob, Grandpa::current_set_id())),
+					Historical::prove((fg_primitives::KEY_TYPE, bob.public().into())).unwrap(),
+				)
+			};
+			assert_ok!(report());
+
+			assert!(System::events().iter().any(|record| matches!(
+				record.event,
+				RuntimeEvent::Offences(pallet_offences::Event::Offence { .. })
+			)));
+			assert!(!validators().contains(&bob_account));
+			assert_noop!(report(), pallet_grandpa::Error::<Runtime>::DuplicateOffenceReport);
+		});
+	}
+
+	/// The header of block `number` of `slot`, sealed by `author`.
+	fn aura_header(author: &ecdsa::Pair, slot: u64, number: BlockNumber) -> Header {
+		use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+		use sp_runtime::{traits::Header as _, DigestItem};
+
+		let mut header = Header::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		header
+			.digest_mut()
+			.push(DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(slot).encode()));
+		let signature = author.sign(header.hash().as_ref());
+		header.digest_mut().push(DigestItem::Seal(AURA_ENGINE_ID, signature.encode()));
+		header
+	}
+
+	#[test]
+	fn aura_equivocators_are_disabled_and_removed_from_the_validator_set() {
+		// Bob's Aura key is the key of Bob's account.
+		let bob = ecdsa::Pair::from_string("//Bob", None).unwrap();
+		let bob_account = EthereumAccount::convert(bob.public());
+
+		let mut ext: TestExternalities = genesis(&["Alice", "Bob"]).build_storage().unwrap().into();
+		ext.execute_with(|| {
+			System::initialize(&1, &Default::default(), &Default::default());
+
+			let equivocation_proof = aura_equivocation_runtime_api::EquivocationProof {
+				offender: AuraId::from(bob.public()),
+				slot: 1.into(),
+				first_header: aura_header(&bob, 1, 1),
+				second_header: aura_header(&bob, 1, 2),
+			};
+			let key_owner_proof = Historical::prove((
+				<AuraId as sp_runtime::RuntimeAppPublic>::ID,
+				bob.public().into(),
+			))
+			.unwrap();
+			assert_ok!(AuraEquivocation::report_equivocation_unsigned(
+				RuntimeOrigin::none(),
+				Box::new(equivocation_proof),
+				key_owner_proof,
+			));
+
+			assert!(!validators().contains(&bob_account));
+			// Bob, the second validator of the session, can't author blocks anymore.
+			assert_eq!(Session::disabled_validators(), vec![1]);
+		});
+	}
+
+	#[test]
+	fn author_vrf_keys_come_from_the_session_in_aura_order() {
+		let vrf_key = |seed: &str| -> author_vrf::AuthorityId {
+			sr25519::Pair::from_string(&format!("//{}", seed), None)
+				.unwrap()
+				.public()
+				.into()
+		};
+
+		let mut ext: TestExternalities = genesis(&["Alice", "Bob"]).build_storage().unwrap().into();
+		ext.execute_with(|| {
+			assert_eq!(
+				AuthorVrf::authorities().into_inner(),
+				vec![vrf_key("Alice"), vrf_key("Bob")]
+			);
+			assert_eq!(Aura::authorities().len(), 2);
+		});
+	}
+
+	#[test]
+	fn offchain_workers_sign_transactions_with_ethereum_keys() {
+		use frame_system::offchain::CreateSignedTransaction;
+		use pallet_template::crypto::{EthereumOcwAuthId, KEY_TYPE};
+		use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+
+		let alice = ecdsa::Pair::from_string("//Alice", None).unwrap();
+		let keystore = KeyStore::new();
+		SyncCryptoStore::ecdsa_generate_new(&keystore, KEY_TYPE, Some("//Alice")).unwrap();
+		let mut ext = new_test_ext(&[&alice]);
+		ext.register_extension(KeystoreExt(std::sync::Arc::new(keystore)));
+
+		ext.execute_with(|| {
+			System::initialize(&1, &Default::default(), &Default::default());
+			let call =
+				RuntimeCall::TemplateModule(pallet_template::Call::do_something { something: 42 });
+			let account = EthereumAccount::convert(alice.public());
+
+			let (call, (address, signature, extra)) =
+				<Runtime as CreateSignedTransaction<RuntimeCall>>::create_transaction::<
+					EthereumOcwAuthId,
+				>(call, alice.public().into(), account, 0)
+				.unwrap();
+			let xt = UncheckedExtrinsic::new_signed(call, address, signature, extra);
+
+			assert_eq!(Executive::apply_extrinsic(xt), Ok(Ok(())));
+			assert_eq!(TemplateModule::something(), Some(42));
+		});
+	}
+
+	/// The fee multiplier after `blocks` blocks that each use `fullness` of the weight available to
+	/// normal extrinsics.
+	fn multiplier_after(start: Multiplier, fullness: Perbill, blocks: u32) -> Multiplier {
+		let max_normal = BlockWeights::get().get(DispatchClass::Normal).max_total.unwrap();
+		let weight = Weight::from_ref_time(fullness * max_normal.ref_time());
+
+		new_test_ext(&[]).execute_with(|| {
+			(0..blocks).fold(start, |multiplier, _| {
+				System::set_block_consumed_resources(weight, 0);
+				<Runtime as pallet_transaction_payment::Config>::FeeMultiplierUpdate::convert(
+					multiplier,
+				)
+			})
+		})
+	}
+
+	#[test]
+	fn full_blocks_raise_fees() {
+		let one = Multiplier::one();
+		let after_10 = multiplier_after(one, Perbill::one(), 10);
+		let after_100 = multiplier_after(one, Perbill::one(), 100);
+
+		assert!(one < after_10 && after_10 < after_100);
+		// Blocks right at the target leave fees alone.
+		assert_eq!(multiplier_after(after_100, Perbill::from_percent(25), 10), after_100);
+	}
+
+	#[test]
+	fn empty_blocks_lower_fees_down_to_minimum() {
+		let congested = Multiplier::saturating_from_integer(2);
+
+		assert!(multiplier_after(congested, Perbill::zero(), 100) < congested);
+		assert_eq!(
+			multiplier_after(Multiplier::one(), Perbill::zero(), 100),
+			MinimumMultiplier::get()
+		);
+	}
+
+	#[test]
+	fn fees_are_capped() {
+		assert_eq!(
+			multiplier_after(MaximumMultiplier::get(), Perbill::one(), 100),
+			MaximumMultiplier::get()
+		);
+	}
+
+	#[test]
+	fn gas_matches_ethereum_throughput() {
+		assert_eq!(WEIGHT_PER_SECOND.ref_time() / WEIGHT_PER_GAS, 40_000_000);
+	}
+
+	#[test]
+	fn gas_price_follows_multiplier() {
+		new_test_ext(&[]).execute_with(|| {
+			assert_eq!(gas_price(), WEIGHT_PER_GAS as Balance);
+			pallet_transaction_payment::NextFeeMultiplier::<Runtime>::put(
+				Multiplier::saturating_from_integer(3),
+			);
+			assert_eq!(gas_price(), 3 * WEIGHT_PER_GAS as Balance);
+		});
+	}
+
 	#[test]
 	fn check_whitelist() {
 		let whitelist: HashSet<String> = AllPalletsWithSystem::whitelisted_storage_keys()
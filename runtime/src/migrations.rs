@@ -0,0 +1,86 @@
+//! Storage migrations of the runtime.
+
+use crate::{AccountId, Council, Runtime, Sudo, TechnicalCommittee};
+use frame_support::{
+	storage::{storage_prefix, unhashed},
+	traits::{Get, InitializeMembers, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+
+/// Migrations run by the next runtime upgrade.
+pub type Migrations = (RetireSudo,);
+
+/// Hands root over to governance: makes the sudo key the only member of the council and the
+/// technical committee if they have none, then removes the sudo key.
+///
+/// Without members, neither collective could propose a referendum and root would be out of reach.
+/// The `Sudo` pallet stays in the runtime, without a key any of its calls could pass, until a later
+/// upgrade removes it altogether. The migration sets the on-chain storage version of `Sudo` to
+/// [`RetireSudo::RETIRED`], so that it runs once.
+pub struct RetireSudo;
+
+impl RetireSudo {
+	/// Storage version of `Sudo` once its key is retired.
+	pub const RETIRED: StorageVersion = StorageVersion::new(1);
+
+	/// Whether the origin of sudo calls is gone.
+	pub fn is_retired() -> bool {
+		Sudo::key().is_none()
+	}
+
+	/// Makes `key` the only member of the collective `C` if it has none.
+	///
+	/// Returns whether it did.
+	fn seed<C: InitializeMembers<AccountId>>(members: &[AccountId], key: &AccountId) -> bool {
+		if !members.is_empty() {
+			return false
+		}
+		C::initialize_members(&[key.clone()]);
+		true
+	}
+}
+
+impl OnRuntimeUpgrade for RetireSudo {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		if StorageVersion::get::<Sudo>() >= Self::RETIRED {
+			return db_weight.reads(1)
+		}
+
+		let mut writes = 1;
+		if let Some(key) = Sudo::key() {
+			writes += u64::from(Self::seed::<Council>(&Council::members(), &key));
+			writes +=
+				u64::from(Self::seed::<TechnicalCommittee>(&TechnicalCommittee::members(), &key));
+
+			// `pallet_sudo` keeps its `Key` storage private.
+			unhashed::kill(&storage_prefix(<Sudo as PalletInfoAccess>::name().as_bytes(), b"Key"));
+			writes += 1;
+		}
+		Self::RETIRED.put::<Sudo>();
+
+		db_weight.reads_writes(4, writes)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<sp_std::vec::Vec<u8>, &'static str> {
+		use codec::Encode;
+
+		Ok(Sudo::key().encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: sp_std::vec::Vec<u8>) -> Result<(), &'static str> {
+		use codec::Decode;
+		use frame_support::ensure;
+
+		let key = Option::<AccountId>::decode(&mut &state[..]).map_err(|_| "invalid state")?;
+		ensure!(Self::is_retired(), "the sudo key is still set");
+		ensure!(StorageVersion::get::<Sudo>() == Self::RETIRED, "the storage version isn't set");
+		if key.is_some() {
+			ensure!(!Council::members().is_empty(), "the council has no members");
+			ensure!(!TechnicalCommittee::members().is_empty(), "the committee has no members");
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems
+set -e
+
+# Regenerates the weights of a pallet from its benchmarks, e.g.
+#   ./scripts/benchmark_weights.sh relayer
+pallet=${1:?usage: $0 PALLET_DIR}
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+echo "*** Building the node with runtime benchmarks"
+cargo build --release --features runtime-benchmarks
+
+echo "*** Benchmarking pallet_${pallet//-/_}"
+./target/release/node-template benchmark pallet \
+	--chain dev \
+	--execution wasm \
+	--wasm-execution compiled \
+	--pallet "pallet_${pallet//-/_}" \
+	--extrinsic '*' \
+	--steps 50 \
+	--repeat 20 \
+	--template .maintain/frame-weight-template.hbs \
+	--output "pallets/${pallet}/src/weights.rs"
@@ -0,0 +1,315 @@
+//! Client library for the node template.
+//!
+//! Builds, signs and submits extrinsics with Ethereum (secp256k1) keys, so that services talking
+//! to the chain don't have to hand-roll SCALE encoding. The call types are re-exported from the
+//! runtime and the `SignedExtra` is built from the runtime's own definition, so a runtime change
+//! that alters the transaction format breaks the build of this crate rather than its users'
+//! transactions.
+//!
+//! ```no_run
+//! # async fn transfer() -> Result<(), node_template_sdk::Error> {
+//! use node_template_sdk::{AccountId, BalancesCall, Client, EthereumPair};
+//!
+//! let client = Client::ws("ws://127.0.0.1:9944").await?;
+//! let alice = EthereumPair::from_string("//Alice", None).expect("valid dev seed");
+//! let dest: AccountId =
+//! 	"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".parse().expect("valid address");
+//!
+//! let call = BalancesCall::transfer { dest: dest.into(), value: 1_000 };
+//! let xt = client.sign(&alice, call.into()).await?;
+//! let block_hash = client.submit_and_wait(xt).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::StreamExt;
+use jsonrpsee::{
+	core::client::{ClientT, Subscription, SubscriptionClientT},
+	http_client::{HttpClient, HttpClientBuilder},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use sp_core::{Bytes, Encode, Pair};
+use sp_runtime::{generic::Era, traits::IdentifyAccount, MultiSignature, MultiSigner};
+use sp_version::RuntimeVersion;
+
+pub use account::{AccountId20, EthereumPair};
+pub use node_template_runtime::{
+	self as runtime, AccountId, Address, Balance, BalancesCall, Hash, Index, RuntimeCall,
+	SignedExtra, SignedPayload, SystemCall, UncheckedExtrinsic,
+};
+pub use pallet_sponsorship::Sponsor;
+pub use sc_transaction_pool_api::TransactionStatus;
+
+/// Errors of the [`Client`].
+#[derive(Debug)]
+pub enum Error {
+	/// The RPC request failed.
+	Rpc(jsonrpsee::core::Error),
+	/// The transaction pool rejected or dropped the extrinsic.
+	Transaction(String),
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Rpc(e) => write!(f, "RPC request failed: {}", e),
+			Self::Transaction(reason) => write!(f, "transaction failed: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<jsonrpsee::core::Error> for Error {
+	fn from(e: jsonrpsee::core::Error) -> Self {
+		Self::Rpc(e)
+	}
+}
+
+/// Result type of the [`Client`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything a signature commits to besides the call itself.
+#[derive(Debug, Clone)]
+pub struct SigningParams {
+	/// Transaction index of the signer.
+	pub nonce: Index,
+	/// Validity period of the transaction.
+	pub era: Era,
+	/// Tip for the block author, on top of the fee.
+	pub tip: Balance,
+	/// Hash of the genesis block of the chain the transaction is meant for.
+	pub genesis_hash: Hash,
+	/// Hash of the block the era starts at; the genesis hash for immortal transactions.
+	pub birth_hash: Hash,
+	/// `spec_version` of the runtime the transaction is signed for.
+	pub spec_version: u32,
+	/// `transaction_version` of the runtime the transaction is signed for.
+	pub transaction_version: u32,
+	/// Account paying the fee and tip instead of the signer, see [`sponsor`].
+	pub sponsor: Option<Sponsor>,
+}
+
+/// The `SignedExtra` for a transaction of `signer` with `params`.
+///
+/// Only secp256k1 signers have an Ethereum address the allowlist can admit.
+pub fn signed_extra(signer: &MultiSigner, params: &SigningParams) -> SignedExtra {
+	(
+		frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
+		frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
+		frame_system::CheckTxVersion::<runtime::Runtime>::new(),
+		frame_system::CheckGenesis::<runtime::Runtime>::new(),
+		frame_system::CheckEra::<runtime::Runtime>::from(params.era),
+		frame_system::CheckNonce::<runtime::Runtime>::from(params.nonce),
+		frame_system::CheckWeight::<runtime::Runtime>::new(),
+		match &params.sponsor {
+			Some(sponsor) => pallet_sponsorship::ChargeSponsoredTransactionPayment::sponsored(
+				params.tip,
+				sponsor.clone(),
+			),
+			None => pallet_sponsorship::ChargeSponsoredTransactionPayment::new(params.tip),
+		},
+		pallet_allowlist::CheckAllowlist::new(match signer {
+			MultiSigner::Ecdsa(public) => Some(public.clone()),
+			_ => None,
+		}),
+	)
+}
+
+/// Have `sponsor` pay for `who` signing `call` with `params`.
+///
+/// `nonce` is the sponsor's next sponsorship nonce, which is separate from its transaction index.
+/// The returned [`Sponsor`] goes into [`SigningParams::sponsor`] of the transaction, and only pays
+/// for a transaction with the nonce in `params`.
+pub fn sponsor(
+	sponsor: &EthereumPair,
+	who: &AccountId,
+	call: &RuntimeCall,
+	params: &SigningParams,
+	nonce: u64,
+) -> Sponsor {
+	let signer_nonce = params.nonce.into();
+	let hash = pallet_sponsorship::sponsorship_hash(
+		params.genesis_hash,
+		who,
+		signer_nonce,
+		call,
+		params.tip,
+		nonce,
+	);
+
+	Sponsor {
+		address: sponsor.address().into(),
+		nonce,
+		signer_nonce,
+		signature: sponsor.sign_prehashed(&hash).0,
+	}
+}
+
+/// The payload a signature over `call` and `extra` commits to.
+///
+/// Only the genesis hash, the birth hash and the runtime versions are taken from `params`; nonce,
+/// era and tip are part of `extra`.
+pub fn signed_payload(
+	call: RuntimeCall,
+	extra: SignedExtra,
+	params: &SigningParams,
+) -> SignedPayload {
+	SignedPayload::from_raw(
+		call,
+		extra,
+		(
+			(),
+			params.spec_version,
+			params.transaction_version,
+			params.genesis_hash,
+			params.birth_hash,
+			(),
+			(),
+			(),
+			(),
+		),
+	)
+}
+
+/// Sign `call` with `signer`.
+pub fn sign_extrinsic<P>(
+	signer: &P,
+	call: RuntimeCall,
+	params: &SigningParams,
+) -> UncheckedExtrinsic
+where
+	P: Pair,
+	P::Public: Into<MultiSigner>,
+	P::Signature: Into<MultiSignature>,
+{
+	let sender: MultiSigner = signer.public().into();
+	let extra = signed_extra(&sender, params);
+	let raw_payload = signed_payload(call.clone(), extra.clone(), params);
+	let signature: MultiSignature = raw_payload.using_encoded(|e| signer.sign(e)).into();
+
+	UncheckedExtrinsic::new_signed(call, sender.into_account().into(), signature.into(), extra)
+}
+
+/// Account controlled by the Ethereum key `signer`.
+pub fn account_id(signer: &EthereumPair) -> AccountId {
+	MultiSigner::from(signer.public()).into_account()
+}
+
+/// Connection to a node.
+pub struct Client<C> {
+	rpc: C,
+}
+
+impl Client<HttpClient> {
+	/// Connect to the HTTP RPC endpoint at `url`, e.g. `http://127.0.0.1:9933`.
+	///
+	/// HTTP connections can't watch extrinsics, use [`Client::ws`] for that.
+	pub fn http(url: &str) -> Result<Self> {
+		Ok(Self::new(HttpClientBuilder::default().build(url)?))
+	}
+}
+
+impl Client<WsClient> {
+	/// Connect to the WebSocket RPC endpoint at `url`, e.g. `ws://127.0.0.1:9944`.
+	pub async fn ws(url: &str) -> Result<Self> {
+		Ok(Self::new(WsClientBuilder::default().build(url).await?))
+	}
+}
+
+impl<C: ClientT + Sync> Client<C> {
+	/// Use the given RPC client.
+	pub fn new(rpc: C) -> Self {
+		Self { rpc }
+	}
+
+	/// Next transaction index of `account`, including transactions in the pool.
+	pub async fn nonce(&self, account: &AccountId) -> Result<Index> {
+		Ok(self
+			.rpc
+			.request("system_accountNextIndex", rpc_params![account.to_string()])
+			.await?)
+	}
+
+	/// Hash of the genesis block.
+	pub async fn genesis_hash(&self) -> Result<Hash> {
+		Ok(self.rpc.request("chain_getBlockHash", rpc_params![0u32]).await?)
+	}
+
+	/// Version of the runtime at the best block.
+	pub async fn runtime_version(&self) -> Result<RuntimeVersion> {
+		Ok(self.rpc.request("state_getRuntimeVersion", None).await?)
+	}
+
+	/// Parameters for an immortal, unsponsored transaction of `account` without tip.
+	pub async fn signing_params(&self, account: &AccountId) -> Result<SigningParams> {
+		let genesis_hash = self.genesis_hash().await?;
+		let version = self.runtime_version().await?;
+
+		Ok(SigningParams {
+			nonce: self.nonce(account).await?,
+			era: Era::Immortal,
+			tip: 0,
+			genesis_hash,
+			birth_hash: genesis_hash,
+			spec_version: version.spec_version,
+			transaction_version: version.transaction_version,
+			sponsor: None,
+		})
+	}
+
+	/// Sign `call` with `signer` for the chain and runtime the node is on, using the signer's next
+	/// nonce.
+	pub async fn sign(
+		&self,
+		signer: &EthereumPair,
+		call: RuntimeCall,
+	) -> Result<UncheckedExtrinsic> {
+		let params = self.signing_params(&account_id(signer)).await?;
+		Ok(sign_extrinsic(signer.as_ref(), call, &params))
+	}
+
+	/// Submit `xt` to the node's transaction pool, returning its hash.
+	pub async fn submit(&self, xt: &UncheckedExtrinsic) -> Result<Hash> {
+		Ok(self
+			.rpc
+			.request("author_submitExtrinsic", rpc_params![Bytes(xt.encode())])
+			.await?)
+	}
+}
+
+impl<C: SubscriptionClientT + Sync> Client<C> {
+	/// Submit `xt` and follow its progress through the transaction pool.
+	pub async fn submit_and_watch(
+		&self,
+		xt: &UncheckedExtrinsic,
+	) -> Result<Subscription<TransactionStatus<Hash, Hash>>> {
+		Ok(self
+			.rpc
+			.subscribe(
+				"author_submitAndWatchExtrinsic",
+				rpc_params![Bytes(xt.encode())],
+				"author_unwatchExtrinsic",
+			)
+			.await?)
+	}
+
+	/// Submit `xt` and wait until it is included in a block, returning the block's hash.
+	pub async fn submit_and_wait(&self, xt: UncheckedExtrinsic) -> Result<Hash> {
+		let mut watch = self.submit_and_watch(&xt).await?;
+
+		while let Some(status) = watch.next().await {
+			match status? {
+				TransactionStatus::InBlock(block) | TransactionStatus::Finalized(block) =>
+					return Ok(block),
+				TransactionStatus::Future |
+				TransactionStatus::Ready |
+				TransactionStatus::Broadcast(_) |
+				TransactionStatus::Retracted(_) => (),
+				status => return Err(Error::Transaction(format!("{:?}", status))),
+			}
+		}
+		Err(Error::Transaction("subscription closed".into()))
+	}
+}